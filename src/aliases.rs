@@ -0,0 +1,79 @@
+//! Commands of one's own, from the config's `aliases`, an object of what each stands for:
+//!
+//! ```json
+//! "aliases": {"morning": "new coffee=FSL28 dose=18", "fsl": "filter coffee:FSL28"}
+//! ```
+//!
+//! `:morning` then runs `:new coffee=FSL28 dose=18`. Whatever is typed after an alias goes after
+//! what it stands for, so `:morning yield=40` fills in the yield too, and a range before it stays
+//! in front. An alias can stand for another one, but can't take the name of a command.
+
+use std::io;
+
+use crate::{
+    command::{range_len, COMMANDS},
+    json::{invalid, Value},
+};
+
+/// how many aliases deep a command can go before it's taken to be going round in circles
+const MAX_DEPTH: usize = 10;
+
+/// whether `name` can be typed as a command without being read as a range
+fn valid_name(name: &str) -> bool {
+    name.starts_with(char::is_alphabetic)
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// reads the config's `aliases`
+pub fn aliases_from_config(value: &Value) -> io::Result<Vec<(String, String)>> {
+    let fields = match value {
+        Value::Null => return Ok(Vec::new()),
+        Value::Object(fields) => fields,
+        _ => return Err(invalid("`aliases` should be an object")),
+    };
+    fields
+        .iter()
+        .map(|(name, command)| {
+            if !valid_name(name) {
+                return Err(invalid(format!(
+                    "alias `{name}` should be letters, digits, - and _, starting with a letter"
+                )));
+            }
+            if COMMANDS.iter().any(|(known, _)| known == name) {
+                return Err(invalid(format!(
+                    "alias `{name}` would hide the command :{name}"
+                )));
+            }
+            let command = command
+                .as_str()
+                .ok_or_else(|| invalid(format!("alias `{name}` should be a string")))?;
+            let command = command.trim().trim_start_matches(':');
+            Ok((name.clone(), command.to_string()))
+        })
+        .collect()
+}
+
+/// `line` with the alias it's named after, if any, put back as the command it stands for
+pub fn expand(line: &str, aliases: &[(String, String)]) -> Result<String, String> {
+    let mut line = line.trim().to_string();
+    let mut first = None;
+    for _ in 0..MAX_DEPTH {
+        let Some(after) = line.strip_prefix(':') else {
+            return Ok(line);
+        };
+        let (range, after) = after.split_at(range_len(after));
+        let after = after.trim_start();
+        let name = after.split(char::is_whitespace).next().unwrap_or_default();
+        let Some((_, command)) = aliases.iter().find(|(alias, _)| alias == name) else {
+            return Ok(line);
+        };
+        first.get_or_insert_with(|| name.to_string());
+        line = format!(":{range}{command}{}", &after[name.len()..]);
+    }
+    Err(format!(
+        "The alias :{} goes round in circles",
+        first.unwrap_or_default()
+    ))
+}
@@ -0,0 +1,77 @@
+//! Wear on grinder burrs. How much a grinder has ground since its burrs were fitted is the sum of
+//! the doses of its entries from that day on, and a grinder with a burr life set is flagged as
+//! that amount comes near.
+
+use ratatui::{
+    style::Stylize,
+    text::{Line, Span},
+};
+
+use crate::{App, Grinder};
+
+/// grinders are flagged once they've ground this share of their burr life
+const BURR_WARNING_SHARE: f64 = 0.9;
+
+/// e.g. "850 g" or "12.3 kg"
+fn weight_text(grams: f64) -> String {
+    if grams < 1000.0 {
+        format!("{grams:.0} g")
+    } else {
+        format!("{:.1} kg", grams / 1000.0)
+    }
+}
+
+impl App {
+    /// grams ground by the grinder since its burrs were fitted, or ever if that's not known
+    pub(crate) fn grams_ground(&self, grinder: &Grinder) -> f64 {
+        self.data
+            .entries
+            .iter()
+            .filter(|e| e.grinder_id == grinder.uuid)
+            .filter(|e| {
+                grinder
+                    .burrs_fitted
+                    .is_none_or(|day| e.dt_taken.date_naive() >= day)
+            })
+            .map(|e| e.dose)
+            .sum()
+    }
+
+    /// how far through their life the grinder's burrs are, if it has a burr life set
+    fn burr_wear(&self, grinder: &Grinder) -> Option<f64> {
+        let life_g = grinder.burr_life_kg.filter(|kg| *kg > 0.0)? * 1000.0;
+        Some(self.grams_ground(grinder) / life_g)
+    }
+
+    /// "12.3 kg ground", in yellow when the burrs are nearly due and red once they're past it
+    pub(crate) fn ground_span(&self, grinder: &Grinder) -> Span<'static> {
+        let text = format!("{} ground", weight_text(self.grams_ground(grinder)));
+        match self.burr_wear(grinder) {
+            Some(wear) if wear >= 1.0 => text.red(),
+            Some(wear) if wear >= BURR_WARNING_SHARE => text.yellow(),
+            _ => text.into(),
+        }
+    }
+
+    /// a warning naming the grinders whose burrs are nearly due, if any are
+    pub(crate) fn burr_warning(&self) -> Option<Line<'static>> {
+        let due: Vec<String> = self
+            .data
+            .grinders
+            .iter()
+            .filter(|g| self.burr_wear(g).is_some_and(|w| w >= BURR_WARNING_SHARE))
+            .map(|g| {
+                format!(
+                    "{} ({} of {})",
+                    g.name,
+                    weight_text(self.grams_ground(g)),
+                    weight_text(g.burr_life_kg.unwrap_or_default() * 1000.0)
+                )
+            })
+            .collect();
+        if due.is_empty() {
+            return None;
+        }
+        Some(Line::from(format!(" Burrs due: {} ", due.join(", "))).yellow())
+    }
+}
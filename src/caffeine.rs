@@ -0,0 +1,76 @@
+//! How much caffeine the day's coffee added up to. Each entry is estimated from its dose and its
+//! coffee's caffeine content: the coffee's own figure if it has one, otherwise a typical one for
+//! arabica, or next to nothing for decaf. Days over the `caffeine_limit_mg` setting are flagged on
+//! the stats screen, and the entry list warns once today's goes over.
+
+use chrono::{Days, Local, NaiveDate};
+use ratatui::{style::Stylize, text::Line};
+
+use crate::{App, Entry};
+
+/// milligrams of caffeine a brew gets out of each gram of arabica, used for coffees without their
+/// own figure
+const DEFAULT_MG_PER_G: f64 = 10.0;
+/// decaf has about 97% of its caffeine taken out
+const DECAF_MG_PER_G: f64 = 0.3;
+/// used when the config doesn't set `caffeine_limit_mg`, the usual guidance for adults
+const DEFAULT_LIMIT_MG: f64 = 400.0;
+/// how many days back the stats screen goes, today included
+const DAYS_SHOWN: u64 = 7;
+
+impl App {
+    /// the estimated caffeine in the entry, in milligrams
+    fn caffeine_mg(&self, entry: &Entry) -> f64 {
+        let coffee = self.data.coffees.iter().find(|c| c.uuid == entry.coffee_id);
+        let mg_per_g = match coffee {
+            Some(c) if c.decaf => DECAF_MG_PER_G,
+            Some(c) => c.caffeine_mg_per_g.unwrap_or(DEFAULT_MG_PER_G),
+            None => DEFAULT_MG_PER_G,
+        };
+        entry.dose * mg_per_g
+    }
+
+    fn caffeine_limit(&self) -> f64 {
+        self.config.caffeine_limit_mg.unwrap_or(DEFAULT_LIMIT_MG)
+    }
+
+    /// the estimated caffeine of everything brewed on `day`
+    fn caffeine_on(&self, day: NaiveDate) -> f64 {
+        self.data
+            .entries
+            .iter()
+            .filter(|e| e.dt_taken.date_naive() == day)
+            // not `sum`, which is -0 for no entries
+            .fold(0.0, |total, e| total + self.caffeine_mg(e))
+    }
+
+    /// the caffeine section of the stats screen: the last few days, today first
+    pub(crate) fn caffeine_lines(&self) -> Vec<Line<'static>> {
+        let limit = self.caffeine_limit();
+        let mut lines = vec![Line::from(format!("  Caffeine by day (limit {limit:.0} mg)")).bold()];
+        let today = Local::now().date_naive();
+        for back in 0..DAYS_SHOWN {
+            let Some(day) = today.checked_sub_days(Days::new(back)) else {
+                break;
+            };
+            let mg = self.caffeine_on(day);
+            let label = match back {
+                0 => String::from("Today"),
+                _ => day.format("%A %-d").to_string(),
+            };
+            let line = Line::from(format!("    {label:<16} {mg:>7.0} mg"));
+            lines.push(if mg > limit { line.red() } else { line });
+        }
+        lines
+    }
+
+    /// a warning once today's caffeine is over the limit
+    pub(crate) fn caffeine_warning(&self) -> Option<Line<'static>> {
+        let mg = self.caffeine_on(Local::now().date_naive());
+        let limit = self.caffeine_limit();
+        if mg <= limit {
+            return None;
+        }
+        Some(Line::from(format!(" Caffeine today: {mg:.0} mg of {limit:.0} mg ")).red())
+    }
+}
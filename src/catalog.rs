@@ -0,0 +1,576 @@
+//! The screens for managing the coffees, grinders, brewers, waters and baskets that entries refer
+//! to, and the roasters that coffees refer to: adding, renaming and deleting them. One that is
+//! still referred to can only be deleted by moving what refers to it to another one.
+//!
+//! Coffees that are finished with can be archived instead. They're left out of the coffee list
+//! unless asked for, and can't be picked for new entries, but the entries that had them keep them.
+
+mod details;
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{Event, KeyCode, KeyEvent},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Clear, List, ListState, Paragraph, StatefulWidget, Widget},
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+use uuid::Uuid;
+
+use self::details::Details;
+use crate::{
+    keymap::CATALOG_KEYS, App, Basket, Brewer, Coffee, Entry, Grinder, Phase, Roaster, Water,
+    SELECTED_SYMBOL,
+};
+
+/// what a catalog screen lists
+#[derive(Debug, Clone, Copy)]
+pub enum Kind {
+    Coffee,
+    Grinder,
+    Brewer,
+    Roaster,
+    Water,
+    Basket,
+}
+
+impl Kind {
+    pub(crate) fn singular(self) -> &'static str {
+        match self {
+            Kind::Coffee => "coffee",
+            Kind::Grinder => "grinder",
+            Kind::Brewer => "brewer",
+            Kind::Roaster => "roaster",
+            Kind::Water => "water",
+            Kind::Basket => "basket",
+        }
+    }
+
+    pub(crate) fn plural(self) -> &'static str {
+        match self {
+            Kind::Coffee => "Coffees",
+            Kind::Grinder => "Grinders",
+            Kind::Brewer => "Brewers",
+            Kind::Roaster => "Roasters",
+            Kind::Water => "Waters",
+            Kind::Basket => "Baskets",
+        }
+    }
+
+    /// what refers to items of this kind
+    fn users(self) -> &'static str {
+        match self {
+            Kind::Roaster => "coffees",
+            _ => "entries",
+        }
+    }
+
+    /// the id of the item of this kind the entry refers to; only coffees and grinders are required
+    pub(crate) fn id(self, entry: &Entry) -> Option<Uuid> {
+        match self {
+            Kind::Coffee => Some(entry.coffee_id),
+            Kind::Grinder => Some(entry.grinder_id),
+            Kind::Brewer => entry.brewer_id,
+            // entries only know their roaster through their coffee
+            Kind::Roaster => None,
+            Kind::Water => entry.water_id,
+            Kind::Basket => entry.basket_id,
+        }
+    }
+
+    /// points the entry at the item of this kind with id `uuid`
+    pub(crate) fn set_id(self, entry: &mut Entry, uuid: Uuid) {
+        match self {
+            Kind::Coffee => entry.coffee_id = uuid,
+            Kind::Grinder => entry.grinder_id = uuid,
+            Kind::Brewer => entry.brewer_id = Some(uuid),
+            Kind::Roaster => {}
+            Kind::Water => entry.water_id = Some(uuid),
+            Kind::Basket => entry.basket_id = Some(uuid),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CatalogScreen {
+    list_state: ListState,
+    mode: Mode,
+    /// whether archived coffees are listed
+    show_archived: bool,
+}
+
+#[derive(Debug, Default)]
+enum Mode {
+    #[default]
+    Browse,
+    /// typing the name of a new item
+    Add(Input),
+    /// typing a new name for the selected item
+    Rename(Input),
+    /// confirming the deletion of the selected item. Entries using it move to the item at
+    /// `reassign_to`; without one, it can only be deleted if nothing uses it.
+    Delete { reassign_to: Option<usize> },
+    /// looking at or editing the selected item's fields
+    Details(Details),
+}
+
+impl Mode {
+    fn typing(&self) -> bool {
+        match self {
+            Mode::Add(_) | Mode::Rename(_) => true,
+            Mode::Details(details) => details.typing(),
+            Mode::Browse | Mode::Delete { .. } => false,
+        }
+    }
+}
+
+impl Default for CatalogScreen {
+    fn default() -> Self {
+        Self {
+            list_state: ListState::default().with_selected(Some(0)),
+            mode: Mode::default(),
+            show_archived: false,
+        }
+    }
+}
+
+impl App {
+    pub(crate) fn open_catalog(&mut self, kind: Kind) {
+        self.state.catalog = CatalogScreen::default();
+        self.phase = match kind {
+            Kind::Coffee => Phase::EditCoffee,
+            Kind::Grinder => Phase::EditGrinder,
+            Kind::Brewer => Phase::EditBrewer,
+            Kind::Roaster => Phase::EditRoaster,
+            Kind::Water => Phase::EditWater,
+            Kind::Basket => Phase::EditBasket,
+        };
+    }
+
+    /// whether a name or other text is being typed on a catalog screen
+    pub(crate) fn catalog_typing(&self) -> bool {
+        let catalog = matches!(
+            self.phase,
+            Phase::EditCoffee
+                | Phase::EditGrinder
+                | Phase::EditBrewer
+                | Phase::EditRoaster
+                | Phase::EditWater
+                | Phase::EditBasket
+        );
+        catalog && self.state.catalog.mode.typing()
+    }
+
+    /// whether the catalog screen is just showing its list, with nothing open over it
+    pub(crate) fn catalog_browsing(&self) -> bool {
+        matches!(self.state.catalog.mode, Mode::Browse)
+    }
+
+    pub(crate) fn catalog_names(&self, kind: Kind) -> Vec<&str> {
+        match kind {
+            Kind::Coffee => self.data.coffees.iter().map(|c| c.name.as_str()).collect(),
+            Kind::Grinder => self.data.grinders.iter().map(|g| g.name.as_str()).collect(),
+            Kind::Brewer => self.data.brewers.iter().map(|b| b.name.as_str()).collect(),
+            Kind::Roaster => self.data.roasters.iter().map(|r| r.name.as_str()).collect(),
+            Kind::Water => self.data.waters.iter().map(|x| x.name.as_str()).collect(),
+            Kind::Basket => self.data.baskets.iter().map(|x| x.name.as_str()).collect(),
+        }
+    }
+
+    pub(crate) fn catalog_uuid(&self, kind: Kind, i: usize) -> Uuid {
+        match kind {
+            Kind::Coffee => self.data.coffees[i].uuid,
+            Kind::Grinder => self.data.grinders[i].uuid,
+            Kind::Brewer => self.data.brewers[i].uuid,
+            Kind::Roaster => self.data.roasters[i].uuid,
+            Kind::Water => self.data.waters[i].uuid,
+            Kind::Basket => self.data.baskets[i].uuid,
+        }
+    }
+
+    /// whether item `i` is an archived coffee
+    pub(crate) fn catalog_archived(&self, kind: Kind, i: usize) -> bool {
+        matches!(kind, Kind::Coffee) && self.data.coffees[i].archived
+    }
+
+    /// indices of the items listed, in order
+    fn catalog_rows(&self, kind: Kind) -> Vec<usize> {
+        (0..self.catalog_names(kind).len())
+            .filter(|&i| self.state.catalog.show_archived || !self.catalog_archived(kind, i))
+            .collect()
+    }
+
+    /// the index of the item on the highlighted row
+    fn catalog_selected(&self, kind: Kind) -> Option<usize> {
+        let row = self.state.catalog.list_state.selected()?;
+        self.catalog_rows(kind).get(row).copied()
+    }
+
+    /// keeps the highlight on a row that's still there after rows went away
+    fn clamp_catalog_selection(&mut self, kind: Kind) {
+        let rows = self.catalog_rows(kind).len();
+        if self
+            .state
+            .catalog
+            .list_state
+            .selected()
+            .is_some_and(|row| row >= rows)
+        {
+            self.state.catalog.list_state.select(rows.checked_sub(1));
+        }
+    }
+
+    /// archives the coffee, or brings it back
+    fn toggle_archived(&mut self, i: usize) {
+        self.data.coffees[i].archived = !self.data.coffees[i].archived;
+        self.clamp_catalog_selection(Kind::Coffee);
+        self.mark_changed();
+    }
+
+    fn catalog_uses(&self, kind: Kind, i: usize) -> usize {
+        let uuid = self.catalog_uuid(kind, i);
+        if let Kind::Roaster = kind {
+            return self
+                .data
+                .coffees
+                .iter()
+                .filter(|c| c.roaster_id == Some(uuid))
+                .count();
+        }
+        self.data
+            .entries
+            .iter()
+            .filter(|e| kind.id(e) == Some(uuid))
+            .count()
+    }
+
+    /// names must be non-empty and, ignoring case, different from every other item's
+    pub(crate) fn check_catalog_name(
+        &self,
+        kind: Kind,
+        name: &str,
+        except: Option<usize>,
+    ) -> Result<(), String> {
+        if name.is_empty() {
+            return Err(format!("A {} needs a name", kind.singular()));
+        }
+        if self
+            .catalog_names(kind)
+            .iter()
+            .enumerate()
+            .any(|(i, other)| Some(i) != except && other.eq_ignore_ascii_case(name))
+        {
+            return Err(format!(
+                "There's already a {} called {name}",
+                kind.singular()
+            ));
+        }
+        Ok(())
+    }
+
+    /// renames item `i`, or adds a new item if `i` is `None`
+    pub(crate) fn save_catalog_name(&mut self, kind: Kind, i: Option<usize>, name: String) {
+        match (kind, i) {
+            (Kind::Coffee, Some(i)) => self.data.coffees[i].name = name,
+            (Kind::Grinder, Some(i)) => self.data.grinders[i].name = name,
+            (Kind::Brewer, Some(i)) => self.data.brewers[i].name = name,
+            (Kind::Roaster, Some(i)) => self.data.roasters[i].name = name,
+            (Kind::Water, Some(i)) => self.data.waters[i].name = name,
+            (Kind::Basket, Some(i)) => self.data.baskets[i].name = name,
+            (Kind::Coffee, None) => self.data.coffees.push(Coffee::new(name)),
+            (Kind::Grinder, None) => self.data.grinders.push(Grinder::new(name)),
+            (Kind::Brewer, None) => self.data.brewers.push(Brewer::new(name)),
+            (Kind::Roaster, None) => self.data.roasters.push(Roaster::new(name)),
+            (Kind::Water, None) => self.data.waters.push(Water::new(name)),
+            (Kind::Basket, None) => self.data.baskets.push(Basket::new(name)),
+        }
+        if i.is_none() {
+            let last = self.catalog_rows(kind).len() - 1;
+            self.state.catalog.list_state.select(Some(last));
+        }
+        self.mark_changed();
+    }
+
+    /// deletes item `i`, first moving the entries (or for roasters, coffees) that use it to item
+    /// `reassign_to`
+    fn delete_catalog_item(&mut self, kind: Kind, i: usize, reassign_to: Option<usize>) {
+        if self.catalog_uses(kind, i) > 0 {
+            let Some(to) = reassign_to else {
+                return;
+            };
+            let (from, to) = (self.catalog_uuid(kind, i), self.catalog_uuid(kind, to));
+            for coffee in &mut self.data.coffees {
+                if let Kind::Roaster = kind
+                    && coffee.roaster_id == Some(from)
+                {
+                    coffee.roaster_id = Some(to);
+                }
+            }
+            for entry in &mut self.data.entries {
+                if kind.id(entry) == Some(from) {
+                    kind.set_id(entry, to);
+                }
+            }
+            for session in &mut self.data.dial_in_sessions {
+                if let Kind::Coffee = kind
+                    && session.coffee_id == from
+                {
+                    session.coffee_id = to;
+                }
+            }
+        }
+        match kind {
+            Kind::Coffee => {
+                let uuid = self.data.coffees.remove(i).uuid;
+                // sessions whose shots were all deleted along the way
+                self.data.dial_in_sessions.retain(|s| s.coffee_id != uuid);
+            }
+            Kind::Grinder => _ = self.data.grinders.remove(i),
+            Kind::Brewer => _ = self.data.brewers.remove(i),
+            Kind::Roaster => _ = self.data.roasters.remove(i),
+            Kind::Water => _ = self.data.waters.remove(i),
+            Kind::Basket => _ = self.data.baskets.remove(i),
+        }
+        self.clamp_catalog_selection(kind);
+        self.mark_changed();
+    }
+
+    pub(crate) fn handle_key_events_catalog(&mut self, kind: Kind, key_event: KeyEvent) {
+        let len = self.catalog_names(kind).len();
+        let rows = self.catalog_rows(kind).len();
+        let selected = self.catalog_selected(kind);
+        match &mut self.state.catalog.mode {
+            Mode::Browse => match key_event.code {
+                KeyCode::Char('q') | KeyCode::Esc => self.phase = Phase::ListView,
+                KeyCode::Char('j') | KeyCode::Down => self.state.catalog.list_state.select_next(),
+                KeyCode::Char('k') | KeyCode::Up => self.state.catalog.list_state.select_previous(),
+                KeyCode::Char('a') | KeyCode::Char('n') => {
+                    self.state.catalog.mode = Mode::Add(Input::default());
+                    // the new item's row goes below the existing ones
+                    self.state.catalog.list_state.select(Some(rows));
+                }
+                KeyCode::Char('r') | KeyCode::Char('e') => {
+                    if let Some(i) = selected {
+                        let name = self.catalog_names(kind)[i].to_string();
+                        self.state.catalog.mode = Mode::Rename(Input::new(name));
+                    }
+                }
+                KeyCode::Char('d') => {
+                    if let Some(i) = selected {
+                        let reassign_to = (0..len).find(|j| *j != i);
+                        self.state.catalog.mode = Mode::Delete { reassign_to };
+                    }
+                }
+                KeyCode::Enter if selected.is_some() => {
+                    self.state.catalog.mode = Mode::Details(Details::default());
+                }
+                KeyCode::Char('o') if matches!(kind, Kind::Coffee) => {
+                    self.open_catalog(Kind::Roaster);
+                }
+                KeyCode::Char('x') if matches!(kind, Kind::Coffee) => {
+                    if let Some(i) = selected {
+                        self.toggle_archived(i);
+                    }
+                }
+                KeyCode::Char('A') if matches!(kind, Kind::Coffee) => {
+                    self.state.catalog.show_archived = !self.state.catalog.show_archived;
+                    self.clamp_catalog_selection(kind);
+                }
+                _ => {}
+            },
+            Mode::Add(input) | Mode::Rename(input) => match key_event.code {
+                KeyCode::Esc => self.state.catalog.mode = Mode::Browse,
+                KeyCode::Enter => {
+                    let name = input.value().trim().to_string();
+                    let renaming = match self.state.catalog.mode {
+                        Mode::Rename(_) => selected,
+                        _ => None,
+                    };
+                    match self.check_catalog_name(kind, &name, renaming) {
+                        Ok(()) => {
+                            self.state.catalog.mode = Mode::Browse;
+                            self.save_catalog_name(kind, renaming, name);
+                        }
+                        Err(e) => self.warn(e),
+                    }
+                }
+                _ => {
+                    input.handle_event(&Event::Key(key_event));
+                }
+            },
+            Mode::Delete { reassign_to } => match key_event.code {
+                KeyCode::Char('n') | KeyCode::Esc => self.state.catalog.mode = Mode::Browse,
+                KeyCode::Char('h') | KeyCode::Left | KeyCode::Char('l') | KeyCode::Right => {
+                    let (Some(i), Some(to)) = (selected, *reassign_to) else {
+                        return;
+                    };
+                    let forward = matches!(key_event.code, KeyCode::Char('l') | KeyCode::Right);
+                    // step over the item being deleted
+                    let step = |j: usize| {
+                        if forward {
+                            (j + 1) % len
+                        } else {
+                            (j + len - 1) % len
+                        }
+                    };
+                    let mut next = step(to);
+                    if next == i {
+                        next = step(next);
+                    }
+                    *reassign_to = Some(next);
+                }
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    let reassign_to = *reassign_to;
+                    if let Some(i) = selected {
+                        self.state.catalog.mode = Mode::Browse;
+                        self.delete_catalog_item(kind, i, reassign_to);
+                    }
+                }
+                _ => {}
+            },
+            Mode::Details(_) => match selected {
+                Some(i) => self.handle_key_events_details(kind, i, key_event),
+                None => self.state.catalog.mode = Mode::Browse,
+            },
+        }
+    }
+
+    pub(crate) fn render_catalog_view(&mut self, kind: Kind, area: Rect, buf: &mut Buffer) {
+        if let Mode::Details(_) = self.state.catalog.mode
+            && let Some(i) = self.catalog_selected(kind)
+        {
+            self.render_details(kind, i, area, buf);
+            return;
+        }
+        let block = Block::bordered()
+            .title(format!(" Coffee Tracking - {} ", kind.plural()))
+            .border_set(border::ROUNDED);
+        let selected = self.catalog_selected(kind);
+        let names = self.catalog_names(kind);
+        let mut items: Vec<Line> = self
+            .catalog_rows(kind)
+            .into_iter()
+            .map(|i| (i, names[i]))
+            .map(|(i, name)| match &self.state.catalog.mode {
+                Mode::Rename(input) if Some(i) == selected => Line::from(vec![
+                    Span::from("  "),
+                    Span::styled(input.value().to_string(), self.theme.selected),
+                ]),
+                _ => {
+                    let mut line = Line::from(format!(
+                        "  {name} ({} {})",
+                        self.catalog_uses(kind, i),
+                        kind.users()
+                    ));
+                    if let Kind::Coffee = kind
+                        && let Some(stock) = self.stock_span(&self.data.coffees[i])
+                    {
+                        line.push_span(" | ");
+                        line.push_span(stock);
+                    }
+                    if let Kind::Grinder = kind {
+                        line.push_span(" | ");
+                        line.push_span(self.ground_span(&self.data.grinders[i]));
+                    }
+                    if self.catalog_archived(kind, i) {
+                        line.push_span(" | ");
+                        line.push_span("archived".dark_gray());
+                    }
+                    line
+                }
+            })
+            .collect();
+        if let Mode::Add(input) = &self.state.catalog.mode {
+            items.push(Line::from(vec![
+                Span::from("  "),
+                Span::styled(input.value().to_string(), self.theme.selected),
+            ]));
+        }
+        let list = List::new(items)
+            .highlight_style(self.theme.selected)
+            .highlight_symbol(SELECTED_SYMBOL)
+            .block(block);
+        StatefulWidget::render(list, area, buf, &mut self.state.catalog.list_state);
+
+        if let Mode::Delete { reassign_to } = self.state.catalog.mode
+            && let Some(i) = selected
+        {
+            self.render_delete_catalog_item(kind, i, reassign_to, area, buf);
+        }
+    }
+
+    fn render_delete_catalog_item(
+        &self,
+        kind: Kind,
+        i: usize,
+        reassign_to: Option<usize>,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let names = self.catalog_names(kind);
+        let uses = self.catalog_uses(kind, i);
+        let mut lines = vec![
+            Line::from(format!("  Delete \"{}\"?", names[i])),
+            Line::from(""),
+        ];
+        match (uses, reassign_to) {
+            (0, _) => lines.push(Line::from(format!("  No {} use it.", kind.users()))),
+            (_, Some(to)) => {
+                lines.push(Line::from(format!(
+                    "  {uses} {} use it, they will be moved to:",
+                    kind.users()
+                )));
+                lines.push(Line::from(vec![
+                    Span::from("    "),
+                    Span::styled(names[to], self.theme.selected),
+                ]));
+            }
+            (_, None) => {
+                lines.push(Line::from(format!(
+                    "  {uses} {} use it and there is no other {} to move them to.",
+                    kind.users(),
+                    kind.singular()
+                )));
+            }
+        }
+        let height = lines.len() as u16 + 3;
+        let [popup] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup] = Layout::horizontal([Constraint::Max(80)])
+            .flex(Flex::Center)
+            .areas(popup);
+        let block = Block::bordered()
+            .title(format!(" Delete {} ", kind.singular()))
+            .border_set(border::ROUNDED);
+        Clear.render(popup, buf);
+        Paragraph::new(lines).block(block).render(popup, buf);
+    }
+
+    pub(crate) fn render_footer_catalog(&self, area: Rect, buf: &mut Buffer) {
+        let controls = match self.state.catalog.mode {
+            Mode::Browse => self.controls(CATALOG_KEYS, area.width),
+            Mode::Add(_) | Mode::Rename(_) => Line::from(vec![
+                " Controls:".into(),
+                " Save ".into(),
+                "<Enter>".fg(self.theme.accent).bold(),
+                " | Cancel ".into(),
+                "<Esc> ".fg(self.theme.accent).bold(),
+            ]),
+            Mode::Delete { .. } => Line::from(vec![
+                " Controls:".into(),
+                " Delete ".into(),
+                "<y>".fg(self.theme.accent).bold(),
+                " | Change target ".into(),
+                "<h/l>".fg(self.theme.accent).bold(),
+                " | Cancel ".into(),
+                "<n> ".fg(self.theme.accent).bold(),
+            ]),
+            Mode::Details(_) => self.details_controls(),
+        };
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
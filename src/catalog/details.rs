@@ -0,0 +1,601 @@
+//! The details of a single coffee, grinder, brewer, roaster, water or basket, opened with Enter
+//! from its list.
+//! Text and date fields are edited in place; the coffee's roaster and roast level, and whether a
+//! basket is a precision one, are picked by cycling through the options. The days a coffee was
+//! frozen and thawed start out as today, and the frozen row says how long it spent in the freezer.
+//! A brewer's maintenance rows show when each task was last done, and editing one logs it as done
+//! again, today unless another day is typed.
+
+use chrono::{Local, NaiveDate};
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{Event, KeyCode, KeyEvent},
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, List, ListState, StatefulWidget},
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use super::{Kind, Mode};
+use crate::{
+    freshness::{parse_day, DAY_FMT},
+    maintenance::Task,
+    App, SELECTED_SYMBOL,
+};
+
+#[derive(Debug)]
+pub(super) struct Details {
+    list_state: ListState,
+    /// the new value of the selected field while it is being typed
+    input: Option<Input>,
+}
+
+impl Default for Details {
+    fn default() -> Self {
+        Self {
+            list_state: ListState::default().with_selected(Some(0)),
+            input: None,
+        }
+    }
+}
+
+impl Details {
+    pub(super) fn typing(&self) -> bool {
+        self.input.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Detail {
+    Name,
+    Roaster,
+    RoastDate,
+    OpenDate,
+    Frozen,
+    Thawed,
+    Country,
+    Region,
+    Process,
+    Varietal,
+    RoastLevel,
+    BagSize,
+    Remaining,
+    Price,
+    Decaf,
+    Caffeine,
+    TargetDose,
+    TargetRatio,
+    TargetTime,
+    TargetGrind,
+    BurrsFitted,
+    BurrLife,
+    Backflush,
+    Descale,
+    GasketChange,
+    Website,
+    City,
+    Gh,
+    Kh,
+    Recipe,
+    Brand,
+    BasketSize,
+    Precision,
+}
+
+impl Detail {
+    fn label(self) -> &'static str {
+        match self {
+            Detail::Name => "Name",
+            Detail::Roaster => "Roaster",
+            Detail::RoastDate => "Roast date",
+            Detail::OpenDate => "Opened",
+            Detail::Frozen => "Frozen",
+            Detail::Thawed => "Thawed",
+            Detail::Country => "Country",
+            Detail::Region => "Region",
+            Detail::Process => "Process",
+            Detail::Varietal => "Varietal",
+            Detail::RoastLevel => "Roast level",
+            Detail::BagSize => "Bag size (g)",
+            Detail::Remaining => "Left in bag (g)",
+            Detail::Price => "Price per bag",
+            Detail::Decaf => "Decaf",
+            Detail::Caffeine => "Caffeine (mg/g)",
+            Detail::TargetDose => "Target dose (g)",
+            Detail::TargetRatio => "Target ratio",
+            Detail::TargetTime => "Target time (s)",
+            Detail::TargetGrind => "Target grind",
+            Detail::BurrsFitted => "Burrs fitted",
+            Detail::BurrLife => "Burr life (kg)",
+            Detail::Backflush => "Last backflush",
+            Detail::Descale => "Last descale",
+            Detail::GasketChange => "Gasket changed",
+            Detail::Website => "Website",
+            Detail::City => "City",
+            Detail::Gh => "GH (ppm)",
+            Detail::Kh => "KH (ppm)",
+            Detail::Recipe => "Recipe",
+            Detail::Brand => "Brand",
+            Detail::BasketSize => "Size (g)",
+            Detail::Precision => "Precision",
+        }
+    }
+
+    fn of(kind: Kind) -> &'static [Detail] {
+        match kind {
+            Kind::Coffee => &[
+                Detail::Name,
+                Detail::Roaster,
+                Detail::RoastDate,
+                Detail::OpenDate,
+                Detail::Frozen,
+                Detail::Thawed,
+                Detail::Country,
+                Detail::Region,
+                Detail::Process,
+                Detail::Varietal,
+                Detail::RoastLevel,
+                Detail::BagSize,
+                Detail::Remaining,
+                Detail::Price,
+                Detail::Decaf,
+                Detail::Caffeine,
+                Detail::TargetDose,
+                Detail::TargetRatio,
+                Detail::TargetTime,
+                Detail::TargetGrind,
+            ],
+            Kind::Grinder => &[Detail::Name, Detail::BurrsFitted, Detail::BurrLife],
+            Kind::Brewer => &[
+                Detail::Name,
+                Detail::Backflush,
+                Detail::Descale,
+                Detail::GasketChange,
+            ],
+            Kind::Roaster => &[Detail::Name, Detail::Website, Detail::City],
+            Kind::Water => &[Detail::Name, Detail::Gh, Detail::Kh, Detail::Recipe],
+            Kind::Basket => &[
+                Detail::Name,
+                Detail::Brand,
+                Detail::BasketSize,
+                Detail::Precision,
+            ],
+        }
+    }
+
+    /// the maintenance task the row is for
+    fn task(self) -> Option<Task> {
+        match self {
+            Detail::Backflush => Some(Task::Backflush),
+            Detail::Descale => Some(Task::Descale),
+            Detail::GasketChange => Some(Task::GasketChange),
+            _ => None,
+        }
+    }
+}
+
+/// dates are typed as text, so an unset one is shown empty rather than as "None"
+fn day_text(day: Option<NaiveDate>) -> String {
+    day.map(|d| d.format(DAY_FMT).to_string())
+        .unwrap_or_default()
+}
+
+/// like [`day_text`], for numbers
+fn number_text(n: Option<f64>) -> String {
+    n.map(|n| n.to_string()).unwrap_or_default()
+}
+
+impl App {
+    /// a typed weight or concentration; `None`, saying why, unless it's a finite number that
+    /// isn't negative
+    fn amount(&mut self, value: &str) -> Option<f64> {
+        let amount = value
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite() && *n >= 0.0);
+        if amount.is_none() {
+            self.warn(format!(
+                "`{value}` isn't an amount, expected a number like 250"
+            ));
+        }
+        amount
+    }
+
+    fn catalog_detail(&self, kind: Kind, i: usize, detail: Detail) -> String {
+        match (kind, detail) {
+            (_, Detail::Name) => self.catalog_names(kind)[i].to_string(),
+            (Kind::Coffee, Detail::Roaster) => match self.data.coffees[i].roaster_id {
+                None => String::from("None"),
+                Some(id) => self
+                    .data
+                    .roasters
+                    .iter()
+                    .find(|r| r.uuid == id)
+                    .map_or_else(|| String::from("Unknown roaster"), |r| r.name.clone()),
+            },
+            (Kind::Coffee, Detail::RoastDate) => day_text(self.data.coffees[i].roast_date),
+            (Kind::Coffee, Detail::OpenDate) => day_text(self.data.coffees[i].open_date),
+            (Kind::Coffee, Detail::Frozen) => {
+                let coffee = &self.data.coffees[i];
+                match coffee.frozen_date {
+                    Some(_) => format!(
+                        "{} ({})",
+                        day_text(coffee.frozen_date),
+                        coffee.freezer_text()
+                    ),
+                    None => String::new(),
+                }
+            }
+            (Kind::Coffee, Detail::Thawed) => day_text(self.data.coffees[i].thawed_date),
+            (Kind::Coffee, Detail::Country) => self.data.coffees[i].country.clone(),
+            (Kind::Coffee, Detail::Region) => self.data.coffees[i].region.clone(),
+            (Kind::Coffee, Detail::Process) => self.data.coffees[i].process.clone(),
+            (Kind::Coffee, Detail::Varietal) => self.data.coffees[i].varietal.clone(),
+            (Kind::Coffee, Detail::RoastLevel) => {
+                self.data.coffees[i].roast_level.label().to_string()
+            }
+            (Kind::Coffee, Detail::BagSize) => number_text(self.data.coffees[i].bag_size_g),
+            (Kind::Coffee, Detail::Price) => number_text(self.data.coffees[i].bag_price),
+            (Kind::Coffee, Detail::Decaf) => {
+                let yes = self.data.coffees[i].decaf;
+                String::from(if yes { "Yes" } else { "No" })
+            }
+            (Kind::Coffee, Detail::Caffeine) => number_text(self.data.coffees[i].caffeine_mg_per_g),
+            (Kind::Coffee, Detail::TargetDose) => number_text(self.data.coffees[i].target.dose),
+            (Kind::Coffee, Detail::TargetRatio) => number_text(self.data.coffees[i].target.ratio),
+            (Kind::Coffee, Detail::TargetTime) => number_text(self.data.coffees[i].target.time),
+            (Kind::Coffee, Detail::TargetGrind) => number_text(self.data.coffees[i].target.grind),
+            (Kind::Coffee, Detail::Remaining) => {
+                let coffee = &self.data.coffees[i];
+                match coffee.bag_size_g {
+                    Some(_) => coffee.remaining_g.to_string(),
+                    None => String::new(),
+                }
+            }
+            (Kind::Grinder, Detail::BurrsFitted) => day_text(self.data.grinders[i].burrs_fitted),
+            (Kind::Grinder, Detail::BurrLife) => number_text(self.data.grinders[i].burr_life_kg),
+            (Kind::Brewer, _) if let Some(task) = detail.task() => {
+                self.maintenance_text(&self.data.brewers[i], task)
+            }
+            (Kind::Roaster, Detail::Website) => self.data.roasters[i].website.clone(),
+            (Kind::Roaster, Detail::City) => self.data.roasters[i].city.clone(),
+            (Kind::Water, Detail::Gh) => number_text(self.data.waters[i].gh_ppm),
+            (Kind::Water, Detail::Kh) => number_text(self.data.waters[i].kh_ppm),
+            (Kind::Water, Detail::Recipe) => self.data.waters[i].recipe.clone(),
+            (Kind::Basket, Detail::Brand) => self.data.baskets[i].brand.clone(),
+            (Kind::Basket, Detail::BasketSize) => number_text(self.data.baskets[i].size_g),
+            (Kind::Basket, Detail::Precision) => {
+                let yes = self.data.baskets[i].precision;
+                String::from(if yes { "Yes" } else { "No" })
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// stores a typed value, returning false if it was rejected
+    fn set_catalog_detail(&mut self, kind: Kind, i: usize, detail: Detail, value: &str) -> bool {
+        let value = value.trim().to_string();
+        match (kind, detail) {
+            (_, Detail::Name) => {
+                if let Err(e) = self.check_catalog_name(kind, &value, Some(i)) {
+                    self.warn(e);
+                    return false;
+                }
+                self.save_catalog_name(kind, Some(i), value);
+                return true;
+            }
+            (Kind::Coffee, Detail::RoastDate | Detail::OpenDate) => {
+                // empty clears the date
+                let day = match value.as_str() {
+                    "" => None,
+                    _ => match parse_day(&value) {
+                        Some(day) => Some(day),
+                        None => return false,
+                    },
+                };
+                let coffee = &mut self.data.coffees[i];
+                match detail {
+                    Detail::RoastDate => coffee.roast_date = day,
+                    _ => coffee.open_date = day,
+                }
+            }
+            // empty takes the coffee back out of the freezer, as if it had never gone in
+            (Kind::Coffee, Detail::Frozen) => {
+                let coffee = &mut self.data.coffees[i];
+                match value.as_str() {
+                    "" => {
+                        coffee.frozen_date = None;
+                        coffee.thawed_date = None;
+                    }
+                    _ => {
+                        let Some(day) = parse_day(&value) else {
+                            return false;
+                        };
+                        // frozen again after it was thawed
+                        if coffee.thawed_date.is_some_and(|thawed| thawed < day) {
+                            coffee.thawed_date = None;
+                        }
+                        coffee.frozen_date = Some(day);
+                    }
+                }
+            }
+            // empty puts it back in the freezer
+            (Kind::Coffee, Detail::Thawed) => {
+                let coffee = &mut self.data.coffees[i];
+                let day = match value.as_str() {
+                    "" => None,
+                    _ => match parse_day(&value) {
+                        Some(day) => Some(day),
+                        None => return false,
+                    },
+                };
+                // only frozen coffee can be thawed, and not before it was frozen
+                if let Some(day) = day
+                    && coffee.frozen_date.is_none_or(|frozen| day < frozen)
+                {
+                    return false;
+                }
+                coffee.thawed_date = day;
+            }
+            (Kind::Coffee, Detail::Country) => self.data.coffees[i].country = value,
+            (Kind::Coffee, Detail::Region) => self.data.coffees[i].region = value,
+            (Kind::Coffee, Detail::Process) => self.data.coffees[i].process = value,
+            (Kind::Coffee, Detail::Varietal) => self.data.coffees[i].varietal = value,
+            // a new bag size means a new, full bag; empty stops tracking it
+            (Kind::Coffee, Detail::BagSize) => {
+                if value.is_empty() {
+                    self.data.coffees[i].bag_size_g = None;
+                } else {
+                    let Some(grams) = self.amount(&value) else {
+                        return false;
+                    };
+                    let coffee = &mut self.data.coffees[i];
+                    coffee.bag_size_g = Some(grams);
+                    coffee.remaining_g = grams;
+                }
+            }
+            (Kind::Coffee, Detail::Remaining) => {
+                let Some(grams) = self.amount(&value) else {
+                    return false;
+                };
+                let coffee = &mut self.data.coffees[i];
+                // correcting what's left starts tracking a bag of unknown size at that weight
+                coffee.bag_size_g.get_or_insert(grams);
+                coffee.remaining_g = grams;
+            }
+            (Kind::Coffee, Detail::Price) => {
+                self.data.coffees[i].bag_price = match value.as_str() {
+                    "" => None,
+                    _ => match self.amount(&value) {
+                        Some(price) => Some(price),
+                        None => return false,
+                    },
+                };
+            }
+            // empty goes back to the typical figure
+            (Kind::Coffee, Detail::Caffeine) => {
+                self.data.coffees[i].caffeine_mg_per_g = match value.as_str() {
+                    "" => None,
+                    _ => match self.amount(&value) {
+                        Some(mg) => Some(mg),
+                        None => return false,
+                    },
+                };
+            }
+            // empty means no target
+            (
+                Kind::Coffee,
+                Detail::TargetDose | Detail::TargetRatio | Detail::TargetTime | Detail::TargetGrind,
+            ) => {
+                let value = match value.as_str() {
+                    "" => None,
+                    _ => match self.amount(&value) {
+                        Some(n) => Some(n),
+                        None => return false,
+                    },
+                };
+                let target = &mut self.data.coffees[i].target;
+                match detail {
+                    Detail::TargetDose => target.dose = value,
+                    Detail::TargetRatio => target.ratio = value,
+                    Detail::TargetTime => target.time = value,
+                    _ => target.grind = value,
+                }
+            }
+            // empty clears the date, counting everything the grinder has ground
+            (Kind::Grinder, Detail::BurrsFitted) => {
+                self.data.grinders[i].burrs_fitted = match value.as_str() {
+                    "" => None,
+                    _ => match parse_day(&value) {
+                        Some(day) => Some(day),
+                        None => return false,
+                    },
+                };
+            }
+            (Kind::Grinder, Detail::BurrLife) => {
+                self.data.grinders[i].burr_life_kg = match value.as_str() {
+                    "" => None,
+                    _ => match self.amount(&value) {
+                        Some(kg) => Some(kg),
+                        None => return false,
+                    },
+                };
+            }
+            // empty takes back the last time it was logged
+            (Kind::Brewer, _) if let Some(task) = detail.task() => match value.as_str() {
+                "" => self.unlog_maintenance(i, task),
+                _ => match parse_day(&value) {
+                    Some(day) => self.log_maintenance(i, task, day),
+                    None => return false,
+                },
+            },
+            (Kind::Roaster, Detail::Website) => self.data.roasters[i].website = value,
+            (Kind::Roaster, Detail::City) => self.data.roasters[i].city = value,
+            // empty means unknown
+            (Kind::Water, Detail::Gh | Detail::Kh) => {
+                let ppm = match value.as_str() {
+                    "" => None,
+                    _ => match self.amount(&value) {
+                        Some(ppm) => Some(ppm),
+                        None => return false,
+                    },
+                };
+                let water = &mut self.data.waters[i];
+                match detail {
+                    Detail::Gh => water.gh_ppm = ppm,
+                    _ => water.kh_ppm = ppm,
+                }
+            }
+            (Kind::Water, Detail::Recipe) => self.data.waters[i].recipe = value,
+            (Kind::Basket, Detail::Brand) => self.data.baskets[i].brand = value,
+            (Kind::Basket, Detail::BasketSize) => {
+                self.data.baskets[i].size_g = match value.as_str() {
+                    "" => None,
+                    _ => match self.amount(&value) {
+                        Some(grams) => Some(grams),
+                        None => return false,
+                    },
+                };
+            }
+            _ => return false,
+        }
+        self.mark_changed();
+        true
+    }
+
+    /// moves coffee `i` on to the next roaster, going through "none" after the last one
+    fn cycle_roaster(&mut self, i: usize) {
+        let roasters = &self.data.roasters;
+        let current = self.data.coffees[i]
+            .roaster_id
+            .and_then(|id| roasters.iter().position(|r| r.uuid == id));
+        let next = match current {
+            None => roasters.first(),
+            Some(j) => roasters.get(j + 1),
+        };
+        self.data.coffees[i].roaster_id = next.map(|r| r.uuid);
+        self.mark_changed();
+    }
+
+    pub(super) fn handle_key_events_details(&mut self, kind: Kind, i: usize, key_event: KeyEvent) {
+        let Mode::Details(details) = &mut self.state.catalog.mode else {
+            return;
+        };
+        let fields = Detail::of(kind);
+        let selected = details.list_state.selected().and_then(|d| fields.get(d));
+        match &mut details.input {
+            None => match key_event.code {
+                KeyCode::Char('q') | KeyCode::Esc => self.state.catalog.mode = Mode::Browse,
+                KeyCode::Char('j') | KeyCode::Down => details.list_state.select_next(),
+                KeyCode::Char('k') | KeyCode::Up => details.list_state.select_previous(),
+                KeyCode::Char('e') | KeyCode::Enter => match selected {
+                    Some(Detail::Roaster) => self.cycle_roaster(i),
+                    Some(Detail::Precision) => {
+                        let basket = &mut self.data.baskets[i];
+                        basket.precision = !basket.precision;
+                        self.mark_changed();
+                    }
+                    Some(Detail::Decaf) => {
+                        let coffee = &mut self.data.coffees[i];
+                        coffee.decaf = !coffee.decaf;
+                        self.mark_changed();
+                    }
+                    Some(Detail::RoastLevel) => {
+                        let coffee = &mut self.data.coffees[i];
+                        coffee.roast_level = coffee.roast_level.next();
+                        self.mark_changed();
+                    }
+                    Some(&detail) => {
+                        let today = Local::now().date_naive();
+                        let value = match (kind, detail) {
+                            (Kind::Brewer, _) if detail.task().is_some() => day_text(Some(today)),
+                            (Kind::Coffee, Detail::Frozen) => {
+                                day_text(Some(self.data.coffees[i].frozen_date.unwrap_or(today)))
+                            }
+                            (Kind::Coffee, Detail::Thawed) => {
+                                day_text(Some(self.data.coffees[i].thawed_date.unwrap_or(today)))
+                            }
+                            _ => self.catalog_detail(kind, i, detail),
+                        };
+                        if let Mode::Details(details) = &mut self.state.catalog.mode {
+                            details.input = Some(Input::new(value));
+                        }
+                    }
+                    None => {}
+                },
+                _ => {}
+            },
+            Some(input) => match key_event.code {
+                KeyCode::Esc => details.input = None,
+                KeyCode::Enter => {
+                    let value = input.value().to_string();
+                    if let Some(&detail) = selected
+                        && self.set_catalog_detail(kind, i, detail, &value)
+                        && let Mode::Details(details) = &mut self.state.catalog.mode
+                    {
+                        details.input = None;
+                    }
+                }
+                _ => {
+                    input.handle_event(&Event::Key(key_event));
+                }
+            },
+        }
+    }
+
+    pub(super) fn render_details(&mut self, kind: Kind, i: usize, area: Rect, buf: &mut Buffer) {
+        let title = format!(" Coffee Tracking - {} ", self.catalog_names(kind)[i]);
+        let block = Block::bordered().title(title).border_set(border::ROUNDED);
+        let Mode::Details(details) = &self.state.catalog.mode else {
+            return;
+        };
+        let selected = details.list_state.selected();
+        let items: Vec<Line> = Detail::of(kind)
+            .iter()
+            .enumerate()
+            .map(|(d, &detail)| match &details.input {
+                Some(input) if Some(d) == selected => Line::from(vec![
+                    Span::from(format!("  {}: ", detail.label())),
+                    Span::styled(input.value().to_string(), self.theme.selected),
+                ]),
+                _ => Line::from(format!(
+                    "  {}: {}",
+                    detail.label(),
+                    self.catalog_detail(kind, i, detail)
+                )),
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(self.theme.selected)
+            .highlight_symbol(SELECTED_SYMBOL)
+            .block(block);
+        if let Mode::Details(details) = &mut self.state.catalog.mode {
+            StatefulWidget::render(list, area, buf, &mut details.list_state);
+        }
+    }
+
+    pub(super) fn details_controls(&self) -> Line<'static> {
+        match &self.state.catalog.mode {
+            Mode::Details(Details { input: Some(_), .. }) => Line::from(vec![
+                " Controls:".into(),
+                " Save ".into(),
+                "<Enter>".fg(self.theme.accent).bold(),
+                " | Cancel ".into(),
+                "<Esc> ".fg(self.theme.accent).bold(),
+            ]),
+            _ => Line::from(vec![
+                " Controls:".into(),
+                " Next ".into(),
+                "<j>".fg(self.theme.accent).bold(),
+                " | Previous ".into(),
+                "<k>".fg(self.theme.accent).bold(),
+                " | Edit ".into(),
+                "<e>".fg(self.theme.accent).bold(),
+                " | Back ".into(),
+                "<q> ".fg(self.theme.accent).bold(),
+            ]),
+        }
+    }
+}
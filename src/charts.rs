@@ -0,0 +1,276 @@
+//! Charts drawn under the entry list, with the selected entry picked out. `C` in the list goes
+//! through them in turn, or `:chart <name>` picks one and `:chart off` hides it again:
+//!
+//! - `ratio` plots the brew ratio of the listed entries over time, to show how a coffee drifts as
+//!   it ages
+//! - `grind` plots grind setting against shot time for every shot of the selected entry's coffee
+//!   on its grinder, the thing to look at when dialing in a new bag
+//! - `flow` plots the flow rate, grams out a second, of the selected entry's coffee over time; a
+//!   steady flow says more about whether a coffee is dialed in than the time alone
+
+use chrono::DateTime;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    symbols::{border, Marker},
+    text::Line,
+    widgets::{Axis, Block, Chart, Dataset, GraphType, Widget},
+};
+
+use crate::{freshness::DAY_FMT, App, Entry};
+
+/// how many rows a chart takes from the bottom of the list
+pub const CHART_HEIGHT: u16 = 14;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartKind {
+    Ratio,
+    Grind,
+    Flow,
+}
+
+impl ChartKind {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "ratio" => Some(ChartKind::Ratio),
+            "grind" => Some(ChartKind::Grind),
+            "flow" => Some(ChartKind::Flow),
+            _ => None,
+        }
+    }
+}
+
+/// the lowest and highest of `values` with a little room either side, so points at the ends
+/// aren't drawn over the axes, and pulled apart when they're the same so the axis has a span
+fn bounds(values: impl Iterator<Item = f64>) -> [f64; 2] {
+    let [low, high] = values.fold([f64::INFINITY, f64::NEG_INFINITY], |[low, high], v| {
+        [low.min(v), high.max(v)]
+    });
+    if low > high {
+        [0.0, 1.0]
+    } else if low == high {
+        [low - 0.5, high + 0.5]
+    } else {
+        let room = (high - low) * 0.05;
+        [low - room, high + room]
+    }
+}
+
+/// the day of `x`, in days since the Unix epoch
+fn day_label(x: f64) -> String {
+    DateTime::from_timestamp((x * 86_400.0) as i64, 0)
+        .map(|dt| dt.format(DAY_FMT).to_string())
+        .unwrap_or_default()
+}
+
+impl App {
+    /// handles `:chart ratio|grind|flow|off`
+    pub(crate) fn chart_command(&mut self, name: Option<&str>) {
+        match name {
+            None | Some("off") => self.state.chart = None,
+            Some(name) => {
+                if let Some(kind) = ChartKind::parse(name) {
+                    self.state.chart = Some(kind);
+                }
+            }
+        }
+    }
+
+    /// shows the next chart, or none after the last
+    pub(crate) fn next_chart(&mut self) {
+        self.state.chart = match self.state.chart {
+            None => Some(ChartKind::Ratio),
+            Some(ChartKind::Ratio) => Some(ChartKind::Grind),
+            Some(ChartKind::Grind) => Some(ChartKind::Flow),
+            Some(ChartKind::Flow) => None,
+        };
+    }
+
+    pub(crate) fn render_chart(&self, area: Rect, buf: &mut Buffer) {
+        match self.state.chart {
+            Some(ChartKind::Ratio) => self.render_ratio_chart(area, buf),
+            Some(ChartKind::Grind) => self.render_grind_chart(area, buf),
+            Some(ChartKind::Flow) => self.render_flow_chart(area, buf),
+            None => {}
+        }
+    }
+
+    /// ratio against the day brewed, in days since the Unix epoch, for the listed entries
+    fn render_ratio_chart(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title(" Ratio over time ")
+            .border_set(border::ROUNDED);
+        let point = |i: usize| {
+            let entry = &self.data.entries[i];
+            let day = entry.dt_taken.timestamp() as f64 / 86_400.0;
+            (entry.dose > 0.0).then(|| (day, entry.output / entry.dose))
+        };
+        let mut rows = self.listed_entries();
+        rows.sort_by_key(|&i| self.data.entries[i].dt_taken);
+        let points: Vec<(f64, f64)> = rows.iter().filter_map(|&i| point(i)).collect();
+        if points.is_empty() {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            Line::from("  No ratios to plot: the listed entries have no dose.")
+                .dark_gray()
+                .render(inner, buf);
+            return;
+        }
+        let selected: Vec<(f64, f64)> = self.selected_entry().and_then(point).into_iter().collect();
+        let x = bounds(points.iter().map(|p| p.0));
+        let y = bounds(points.iter().map(|p| p.1));
+        let datasets = vec![
+            Dataset::default()
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::new().fg(self.theme.accent))
+                .data(&points),
+            Dataset::default()
+                .marker(Marker::Block)
+                .graph_type(GraphType::Scatter)
+                .style(Style::new().yellow())
+                .data(&selected),
+        ];
+        Chart::new(datasets)
+            .block(block)
+            .x_axis(
+                Axis::default()
+                    .bounds(x)
+                    .labels([day_label(x[0]), day_label(x[1])])
+                    .style(Style::new().dark_gray()),
+            )
+            .y_axis(
+                Axis::default()
+                    .bounds(y)
+                    .labels([format!("{:.1}", y[0]), format!("{:.1}", y[1])])
+                    .style(Style::new().dark_gray()),
+            )
+            .render(area, buf);
+    }
+
+    /// grind setting against shot time, for the shots of the selected entry's coffee and grinder
+    fn render_grind_chart(&self, area: Rect, buf: &mut Buffer) {
+        let Some(selected) = self.selected_entry().map(|i| &self.data.entries[i]) else {
+            let block = Block::bordered()
+                .title(" Grind vs time ")
+                .border_set(border::ROUNDED);
+            let inner = block.inner(area);
+            block.render(area, buf);
+            Line::from("  Select an entry to plot the shots of its coffee and grinder.")
+                .dark_gray()
+                .render(inner, buf);
+            return;
+        };
+        let block = Block::bordered()
+            .title(format!(
+                " Grind vs time: {} on {} ",
+                self.coffee_name(selected),
+                self.grinder_name(selected)
+            ))
+            .border_set(border::ROUNDED);
+        let points: Vec<(f64, f64)> = self
+            .data
+            .entries
+            .iter()
+            .filter(|e| e.coffee_id == selected.coffee_id && e.grinder_id == selected.grinder_id)
+            .map(|e| (e.grind_setting, e.duration))
+            .collect();
+        let picked = [(selected.grind_setting, selected.duration)];
+        let x = bounds(points.iter().map(|p| p.0));
+        let y = bounds(points.iter().map(|p| p.1));
+        let datasets = vec![
+            Dataset::default()
+                .marker(Marker::Dot)
+                .graph_type(GraphType::Scatter)
+                .style(Style::new().fg(self.theme.accent))
+                .data(&points),
+            Dataset::default()
+                .marker(Marker::Block)
+                .graph_type(GraphType::Scatter)
+                .style(Style::new().yellow())
+                .data(&picked),
+        ];
+        Chart::new(datasets)
+            .block(block)
+            .x_axis(
+                Axis::default()
+                    .title("grind")
+                    .bounds(x)
+                    .labels([format!("{:.1}", x[0]), format!("{:.1}", x[1])])
+                    .style(Style::new().dark_gray()),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("sec")
+                    .bounds(y)
+                    .labels([format!("{:.0}", y[0]), format!("{:.0}", y[1])])
+                    .style(Style::new().dark_gray()),
+            )
+            .render(area, buf);
+    }
+
+    /// flow rate against the day brewed, for the timed shots of the selected entry's coffee
+    fn render_flow_chart(&self, area: Rect, buf: &mut Buffer) {
+        let selected = self.selected_entry().map(|i| &self.data.entries[i]);
+        let title = match selected {
+            Some(entry) => format!(" Flow rate over time: {} ", self.coffee_name(entry)),
+            None => String::from(" Flow rate over time "),
+        };
+        let block = Block::bordered().title(title).border_set(border::ROUNDED);
+        let point = |entry: &Entry| {
+            let day = entry.dt_taken.timestamp() as f64 / 86_400.0;
+            entry.flow_rate().map(|flow| (day, flow))
+        };
+        let mut shots: Vec<&Entry> = selected
+            .map(|s| {
+                self.data
+                    .entries
+                    .iter()
+                    .filter(|e| e.coffee_id == s.coffee_id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        shots.sort_by_key(|e| e.dt_taken);
+        let points: Vec<(f64, f64)> = shots.into_iter().filter_map(point).collect();
+        if points.is_empty() {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            Line::from("  No flow to plot: select an entry of a coffee with timed shots.")
+                .dark_gray()
+                .render(inner, buf);
+            return;
+        }
+        let picked: Vec<(f64, f64)> = selected.and_then(point).into_iter().collect();
+        let x = bounds(points.iter().map(|p| p.0));
+        let y = bounds(points.iter().map(|p| p.1));
+        let datasets = vec![
+            Dataset::default()
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::new().fg(self.theme.accent))
+                .data(&points),
+            Dataset::default()
+                .marker(Marker::Block)
+                .graph_type(GraphType::Scatter)
+                .style(Style::new().yellow())
+                .data(&picked),
+        ];
+        Chart::new(datasets)
+            .block(block)
+            .x_axis(
+                Axis::default()
+                    .bounds(x)
+                    .labels([day_label(x[0]), day_label(x[1])])
+                    .style(Style::new().dark_gray()),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("g/s")
+                    .bounds(y)
+                    .labels([format!("{:.1}", y[0]), format!("{:.1}", y[1])])
+                    .style(Style::new().dark_gray()),
+            )
+            .render(area, buf);
+    }
+}
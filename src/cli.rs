@@ -0,0 +1,110 @@
+//! Command line arguments.
+
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use ratatui::crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    terminal,
+};
+
+pub const USAGE: &str = "\
+usage: coffee-tracking [options]
+
+options:
+  --data-dir <dir>  keep the data file in <dir> instead of the default data directory
+  --profile <name>  use a separate data file for profile <name>
+  --sqlite          store the data in an SQLite database (needs the sqlite3 shell)
+  --encrypt         encrypt the data file with a passphrase asked for at startup
+  -h, --help        print this message
+";
+
+#[derive(Debug, Default)]
+pub struct Args {
+    pub data_dir: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub sqlite: bool,
+    pub encrypt: bool,
+    pub help: bool,
+}
+
+impl Args {
+    /// on error, returns a message that already includes the usage text
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        while let Some(arg) = args.next() {
+            // accept both `--flag value` and `--flag=value`
+            let (flag, inline_val) = match arg.split_once('=') {
+                Some((flag, val)) if flag.starts_with("--") => {
+                    (flag.to_string(), Some(val.to_string()))
+                }
+                _ => (arg, None),
+            };
+            let mut value = || {
+                inline_val
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| usage_error(format!("`{flag}` needs a value")))
+            };
+            match flag.as_str() {
+                "--data-dir" => parsed.data_dir = Some(PathBuf::from(value()?)),
+                "--profile" => parsed.profile = Some(value()?),
+                "--sqlite" => parsed.sqlite = true,
+                "--encrypt" => parsed.encrypt = true,
+                "-h" | "--help" => parsed.help = true,
+                _ => return Err(usage_error(format!("unknown argument `{flag}`"))),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+fn usage_error(msg: String) -> String {
+    format!("{msg}\n\n{USAGE}")
+}
+
+/// asks for the data file passphrase on the terminal without echoing it, and a second time to
+/// confirm if the passphrase is new
+pub fn read_passphrase(new: bool) -> io::Result<String> {
+    let passphrase = read_hidden("Passphrase: ")?;
+    if passphrase.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the passphrase can't be empty",
+        ));
+    }
+    if new && read_hidden("Confirm passphrase: ")? != passphrase {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the passphrases don't match",
+        ));
+    }
+    Ok(passphrase)
+}
+
+fn read_hidden(prompt: &str) -> io::Result<String> {
+    eprint!("{prompt}");
+    io::stderr().flush()?;
+    terminal::enable_raw_mode()?;
+    let mut input = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => break Ok(input),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled"))
+                }
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => _ = input.pop(),
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => break Err(e),
+        }
+    };
+    terminal::disable_raw_mode()?;
+    eprintln!();
+    result
+}
@@ -0,0 +1,60 @@
+//! Copying text to the system clipboard. Uses the platform's clipboard command where one is
+//! installed, and otherwise asks the terminal to do it with an OSC 52 escape sequence, which also
+//! works over SSH in most terminals.
+
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+/// tried in order, the first one that runs successfully wins
+const COMMANDS: &[&[&str]] = &[
+    &["pbcopy"],
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+    &["clip.exe"],
+];
+
+pub fn copy(text: &str) -> io::Result<()> {
+    if COMMANDS.iter().any(|cmd| pipe_to(cmd, text).is_ok()) {
+        return Ok(());
+    }
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()
+}
+
+fn pipe_to(cmd: &[&str], text: &str) -> io::Result<()> {
+    let mut child = Command::new(cmd[0])
+        .args(&cmd[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    child.stdin.take().unwrap().write_all(text.as_bytes())?;
+    if child.wait()?.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("{} failed", cmd[0])))
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | u32::from(*b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
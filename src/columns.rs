@@ -0,0 +1,243 @@
+//! The columns of the entry list. Which ones are shown, in what order and how wide comes from the
+//! config's `columns`, e.g. `["date", {"column": "coffee", "width": 30}, "ratio"]`; without it the
+//! list shows [`DEFAULT_COLUMNS`]. A column without a width takes its usual one, and the coffee
+//! column takes whatever room is left. Entries matching a search, see [`crate::search`], are
+//! highlighted.
+
+use std::io;
+
+use ratatui::{
+    layout::Constraint,
+    style::Stylize,
+    text::Line,
+    widgets::{Cell, Row, Table},
+};
+
+use crate::{
+    fields::Field,
+    freshness,
+    json::{invalid, Value},
+    search::MATCH_STYLE,
+    tasting::{self, MAX_RATING},
+    App, Entry, SELECTED_SYMBOL,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Column {
+    Favorite,
+    Date,
+    Coffee,
+    Grinder,
+    Brewer,
+    Method,
+    Dose,
+    Yield,
+    Ratio,
+    Time,
+    Flow,
+    Rating,
+    RoastAge,
+}
+
+const COLUMNS: [Column; 13] = [
+    Column::Favorite,
+    Column::Date,
+    Column::Coffee,
+    Column::Grinder,
+    Column::Brewer,
+    Column::Method,
+    Column::Dose,
+    Column::Yield,
+    Column::Ratio,
+    Column::Time,
+    Column::Flow,
+    Column::Rating,
+    Column::RoastAge,
+];
+
+/// shown when the config doesn't list any
+const DEFAULT_COLUMNS: [Column; 10] = [
+    Column::Favorite,
+    Column::Date,
+    Column::Coffee,
+    Column::Grinder,
+    Column::Dose,
+    Column::Yield,
+    Column::Ratio,
+    Column::Time,
+    Column::Rating,
+    Column::RoastAge,
+];
+
+impl Column {
+    /// how the column is named in the config
+    fn key(self) -> &'static str {
+        match self {
+            Column::Favorite => "favorite",
+            Column::Date => "date",
+            Column::Coffee => "coffee",
+            Column::Grinder => "grinder",
+            Column::Brewer => "brewer",
+            Column::Method => "method",
+            Column::Dose => "dose",
+            Column::Yield => "yield",
+            Column::Ratio => "ratio",
+            Column::Time => "time",
+            Column::Flow => "flow",
+            Column::Rating => "rating",
+            Column::RoastAge => "roast_age",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        COLUMNS.into_iter().find(|c| c.key() == key)
+    }
+
+    fn header(self) -> &'static str {
+        match self {
+            Column::Favorite => "",
+            Column::Date => "Date",
+            Column::Coffee => "Coffee",
+            Column::Grinder => "Grinder",
+            Column::Brewer => "Brewer",
+            Column::Method => "Method",
+            Column::Dose => "Dose",
+            Column::Yield => "Yield",
+            Column::Ratio => "Ratio",
+            Column::Time => "Time",
+            Column::Flow => "Flow",
+            Column::Rating => "★",
+            Column::RoastAge => "Off roast",
+        }
+    }
+
+    fn default_width(self) -> Constraint {
+        match self {
+            Column::Favorite => Constraint::Length(1),
+            Column::Date => Constraint::Length(16),
+            Column::Coffee => Constraint::Min(12),
+            Column::Grinder | Column::Brewer => Constraint::Length(14),
+            Column::Method => Constraint::Length(10),
+            Column::Dose | Column::Yield | Column::Ratio | Column::Time => Constraint::Length(7),
+            Column::Flow => Constraint::Length(8),
+            Column::Rating => Constraint::Length(MAX_RATING.into()),
+            Column::RoastAge => Constraint::Length(9),
+        }
+    }
+}
+
+/// one item of the config's `columns`
+#[derive(Debug, Clone, Copy)]
+pub struct ShownColumn {
+    pub column: Column,
+    /// in characters, instead of the column's usual width
+    pub width: Option<u16>,
+}
+
+impl ShownColumn {
+    /// reads either a column's name or a `{"column": name, "width": n}` object
+    pub fn from_config(value: &Value) -> io::Result<Self> {
+        let (key, width) = match value {
+            Value::String(key) => (key.as_str(), None),
+            _ => {
+                let key = value
+                    .get("column")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| invalid("columns need a `column` name"))?;
+                let width = match value.get("width") {
+                    None | Some(Value::Null) => None,
+                    Some(Value::Number(n)) if *n >= 0.0 && *n <= f64::from(u16::MAX) => {
+                        Some(*n as u16)
+                    }
+                    Some(_) => {
+                        return Err(invalid(format!(
+                            "the `width` of column `{key}` should be a number of characters"
+                        )));
+                    }
+                };
+                (key, width)
+            }
+        };
+        let column = Column::from_key(key).ok_or_else(|| {
+            let known: Vec<&str> = COLUMNS.iter().map(|c| c.key()).collect();
+            invalid(format!(
+                "unknown column `{key}`, it should be one of {}",
+                known.join(", ")
+            ))
+        })?;
+        Ok(Self { column, width })
+    }
+
+    fn constraint(self) -> Constraint {
+        self.width
+            .map_or_else(|| self.column.default_width(), Constraint::Length)
+    }
+}
+
+impl App {
+    fn shown_columns(&self) -> Vec<ShownColumn> {
+        if !self.config.columns.is_empty() {
+            return self.config.columns.clone();
+        }
+        DEFAULT_COLUMNS
+            .into_iter()
+            .map(|column| ShownColumn {
+                column,
+                width: None,
+            })
+            .collect()
+    }
+
+    fn entry_cell(&self, entry: &Entry, column: Column) -> Cell<'static> {
+        match column {
+            Column::Favorite => Cell::from(
+                if entry.favorite { "*" } else { " " }
+                    .bold()
+                    .fg(self.theme.accent),
+            ),
+            Column::Date => Cell::from(self.date_text(entry.dt_taken)),
+            Column::Coffee => Cell::from(self.coffee_name(entry).to_string()),
+            Column::Grinder => Cell::from(self.grinder_name(entry).to_string()),
+            Column::Brewer => Cell::from(self.brewer_name(entry).to_string()),
+            Column::Method => Cell::from(entry.method.label()),
+            Column::Dose => Cell::from(self.weight(entry.dose)),
+            Column::Yield => Cell::from(self.weight(entry.output)),
+            Column::Ratio if entry.dose > 0.0 => {
+                Cell::from(format!("{:.1} / 1", entry.output / entry.dose))
+            }
+            Column::Ratio => Cell::from("-"),
+            Column::Time => Cell::from(format!("{:.0}{}", entry.duration, Field::Duration.unit())),
+            Column::Flow => Cell::from(
+                entry
+                    .flow_rate()
+                    .map_or(String::from("-"), |flow| format!("{flow:.1} g/s")),
+            ),
+            Column::Rating => Cell::from(tasting::stars(entry.rating, "").yellow()),
+            Column::RoastAge => match self.days_off_roast(entry) {
+                Some(days) => Cell::from(Line::from(freshness::roast_days_span(days))),
+                None => Cell::from(""),
+            },
+        }
+    }
+
+    /// the entries at `rows`, under a header of the shown columns
+    pub(crate) fn entry_table(&self, rows: &[usize]) -> Table<'static> {
+        let columns = self.shown_columns();
+        let header = Row::new(columns.iter().map(|c| Cell::from(c.column.header()))).bold();
+        let rows = rows.iter().map(|&i| {
+            let entry = &self.data.entries[i];
+            let row = Row::new(columns.iter().map(|c| self.entry_cell(entry, c.column)));
+            if self.search_match(entry) {
+                row.style(MATCH_STYLE)
+            } else if self.state.compare_marks.contains(&i) {
+                row.fg(self.theme.accent).italic()
+            } else {
+                row
+            }
+        });
+        Table::new(rows, columns.iter().map(|c| c.constraint()))
+            .header(header)
+            .row_highlight_style(self.theme.selected)
+            .highlight_symbol(SELECTED_SYMBOL)
+    }
+}
@@ -0,0 +1,209 @@
+//! Reading what's typed after `:`. A command is its name and then its arguments, split at spaces
+//! outside of double quotes: `:view save "dialing in"` has the two arguments `save` and
+//! `dialing in`. A quote can start partway through a word, as in `coffee:"La Esperanza"`, and
+//! the quotes themselves are dropped.
+//!
+//! Each command takes at most so many arguments, and saying more is an error rather than the
+//! rest being ignored. A few, like `:brew` and `:filter`, read the text after their name
+//! themselves.
+//!
+//! Before the name can come a range of the entry list, as in vi: `:1,5 delete`, `:.,$ delete` or
+//! `:%export csv out.csv`, where the rows count from 1 as the list shows them, `.` is the
+//! selected entry, `$` the last and `%` all of them. A range on its own, `:42`, goes to that row.
+//!
+//! The config's aliases are put back as the commands they stand for before the line is read here,
+//! see [`crate::aliases`].
+
+/// what comes after the name of each command, and the most arguments it takes
+pub const COMMANDS: &[(&str, usize)] = &[
+    ("w", 0),
+    ("q", 0),
+    ("wq", 0),
+    ("q!", 0),
+    ("new", usize::MAX),
+    ("wizard", 0),
+    ("sync", 1),
+    ("profile", 1),
+    ("import", usize::MAX),
+    ("export", usize::MAX),
+    ("report", usize::MAX),
+    ("filter", usize::MAX),
+    ("tag", 1),
+    ("sort", 2),
+    ("view", 2),
+    ("period", 2),
+    ("chart", 1),
+    ("suggest", 0),
+    ("brew", usize::MAX),
+    ("scale", 1),
+    ("upload", 0),
+    ("coffees", 0),
+    ("grinders", 0),
+    ("brewers", 0),
+    ("roasters", 0),
+    ("waters", 0),
+    ("baskets", 0),
+    ("stats", 0),
+    ("dialin", 0),
+    ("theme", 1),
+    ("restore", 1),
+    ("delete", 0),
+    ("set", usize::MAX),
+];
+
+/// the commands that work on a range of the list
+const RANGED: &[&str] = &["delete", "export"];
+
+/// one end of a range of the entry list
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Address {
+    /// counting from 1, as the list shows them
+    Row(usize),
+    /// `.`
+    Selected,
+    /// `$`
+    Last,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range {
+    pub from: Address,
+    pub to: Address,
+}
+
+/// the address at the start of `text`, and what follows it
+fn address(text: &str) -> Option<(Address, &str)> {
+    if let Some(rest) = text.strip_prefix('.') {
+        return Some((Address::Selected, rest));
+    }
+    if let Some(rest) = text.strip_prefix('$') {
+        return Some((Address::Last, rest));
+    }
+    let digits = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let row = text[..digits].parse().ok()?;
+    Some((Address::Row(row), &text[digits..]))
+}
+
+/// the range at the start of `text`, if there is one, and what follows it
+fn range(text: &str) -> Result<(Option<Range>, &str), String> {
+    if let Some(rest) = text.strip_prefix('%') {
+        let all = Range {
+            from: Address::Row(1),
+            to: Address::Last,
+        };
+        return Ok((Some(all), rest));
+    }
+    let Some((from, rest)) = address(text) else {
+        return Ok((None, text));
+    };
+    let Some(rest) = rest.strip_prefix(',') else {
+        return Ok((Some(Range { from, to: from }), rest));
+    };
+    match address(rest) {
+        Some((to, rest)) => Ok((Some(Range { from, to }), rest)),
+        None => Err(String::from("the range needs a row after the comma")),
+    }
+}
+
+/// how much of `line`, without its `:`, is a range, so completion can start after it
+pub fn range_len(line: &str) -> usize {
+    line.find(|c: char| !(c.is_ascii_digit() || ".,$%".contains(c)))
+        .unwrap_or(line.len())
+}
+
+#[derive(Debug)]
+pub struct Command<'a> {
+    /// without the `:`, empty for a range on its own
+    pub name: &'a str,
+    pub range: Option<Range>,
+    args: Vec<String>,
+    /// everything after the name, as it was typed
+    rest: &'a str,
+}
+
+impl<'a> Command<'a> {
+    /// reads a command line like `:sort dose desc`, `None` if there's nothing on it
+    pub fn parse(line: &'a str) -> Result<Option<Self>, String> {
+        let line = line.trim();
+        let line = line.strip_prefix(':').unwrap_or(line);
+        let (range, line) = range(line)?;
+        let line = line.trim_start();
+        let (name, rest) = match line.split_once(char::is_whitespace) {
+            Some((name, rest)) => (name, rest.trim()),
+            None => (line, ""),
+        };
+        if name.is_empty() {
+            return Ok(range.map(|range| Self {
+                name,
+                range: Some(range),
+                args: Vec::new(),
+                rest,
+            }));
+        }
+        let Some(&(_, most)) = COMMANDS.iter().find(|(known, _)| *known == name) else {
+            return Err(format!("Unknown command :{name}"));
+        };
+        if range.is_some() && !RANGED.contains(&name) {
+            return Err(format!(":{name} doesn't take a range"));
+        }
+        let args = split(rest)?;
+        match most {
+            _ if args.len() <= most => {}
+            0 => return Err(format!(":{name} doesn't take arguments")),
+            1 => return Err(format!(":{name} takes one argument")),
+            most => return Err(format!(":{name} takes at most {most} arguments")),
+        }
+        Ok(Some(Self {
+            name,
+            range,
+            args,
+            rest,
+        }))
+    }
+
+    /// argument `i`, counting from 0
+    pub fn arg(&self, i: usize) -> Option<&str> {
+        self.args.get(i).map(String::as_str)
+    }
+
+    pub fn args(&self) -> Vec<&str> {
+        self.args.iter().map(String::as_str).collect()
+    }
+
+    /// the arguments from `i` on put back together, like a path with spaces typed without quotes
+    pub fn joined_from(&self, i: usize) -> String {
+        self.args.get(i..).unwrap_or_default().join(" ")
+    }
+
+    /// the text after the name, for commands that read it themselves
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+}
+
+/// splits `text` into words at spaces outside of quotes, dropping the quotes
+pub fn split(text: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    for c in text.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if quoted {
+        return Err(String::from("a quote isn't closed"));
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    Ok(words)
+}
@@ -0,0 +1,235 @@
+//! The commands typed after `:`, kept in the state file, see [`crate::session`], so they're
+//! there again next session. While typing a command Up goes back through the ones before it and
+//! Down forward again, only the ones starting with what was typed when Up was first pressed, and
+//! Ctrl-r searches back through them for some text, like a shell's reverse search: Ctrl-r again
+//! finds the one before, Enter runs it and Esc goes back to what was typed.
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::{Margin, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::{json::Value, session, App};
+
+/// the most commands kept, the oldest go first
+const MAX_COMMANDS: usize = 200;
+const KEY: &str = "command_history";
+
+#[derive(Debug, Default)]
+pub struct CommandHistory {
+    /// oldest first
+    commands: Vec<String>,
+    /// the command Up has got to, and what had been typed before it
+    recalled: Option<(usize, String)>,
+    search: Option<Search>,
+}
+
+#[derive(Debug)]
+struct Search {
+    text: String,
+    /// the last command found with the text
+    found: Option<usize>,
+    /// whether nothing has the text as it is now
+    failing: bool,
+    /// what had been typed before searching, for Esc to put back
+    typed: String,
+}
+
+impl CommandHistory {
+    /// the commands of the sessions before; a missing or unreadable state file means none
+    pub fn load() -> Self {
+        let commands = session::load(KEY)
+            .and_then(|commands| {
+                let commands = commands.as_array()?;
+                Some(
+                    commands
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(String::from)
+                        .collect(),
+                )
+            })
+            .unwrap_or_default();
+        Self {
+            commands,
+            ..Self::default()
+        }
+    }
+
+    /// the newest command before `before` with `text` in it, ignoring case
+    fn find(&self, text: &str, before: usize) -> Option<usize> {
+        if text.is_empty() {
+            return None;
+        }
+        let text = text.to_lowercase();
+        self.commands[..before]
+            .iter()
+            .rposition(|command| command.to_lowercase().contains(&text))
+    }
+
+    pub fn searching(&self) -> bool {
+        self.search.is_some()
+    }
+}
+
+impl App {
+    /// remembers a command that was run, moving it to the end if it was run before
+    pub(crate) fn record_command(&mut self, command: &str) {
+        let history = &mut self.command_history;
+        history.recalled = None;
+        history.search = None;
+        if command.trim() == ":" || !command.starts_with(':') {
+            return;
+        }
+        history.commands.retain(|c| c != command);
+        history.commands.push(command.to_string());
+        if history.commands.len() > MAX_COMMANDS {
+            history.commands.remove(0);
+        }
+        let commands = history.commands.iter().map(|c| Value::from(c.as_str()));
+        if let Err(e) = session::save(KEY, Value::Array(commands.collect())) {
+            self.warn(format!("Couldn't keep the command for next time: {e}"));
+        }
+    }
+
+    /// Up when going `back`, Down otherwise: the command before or after the one recalled
+    /// starting with what was typed, or what was typed once past the newest
+    pub(crate) fn recall_command(&mut self, back: bool) {
+        let history = &mut self.command_history;
+        let buffer = &mut self.state.command.buffer;
+        let (at, typed) = match history.recalled.take() {
+            // unless the recalled command has been changed since
+            Some((at, typed)) if history.commands.get(at) == Some(buffer) => (at, typed),
+            _ if back => (history.commands.len(), buffer.clone()),
+            _ => return,
+        };
+        let starts = |c: &String| c.starts_with(typed.as_str()) && c != &typed;
+        let found = if back {
+            history.commands[..at].iter().rposition(starts)
+        } else {
+            history.commands[at + 1..]
+                .iter()
+                .position(starts)
+                .map(|i| at + 1 + i)
+        };
+        match found {
+            Some(i) => {
+                *buffer = history.commands[i].clone();
+                history.recalled = Some((i, typed));
+            }
+            // past the newest, back to what was typed
+            None if !back => *buffer = typed,
+            // already at the oldest
+            None => history.recalled = Some((at, typed)).filter(|_| at < history.commands.len()),
+        }
+    }
+
+    /// Ctrl-r while typing a command
+    pub(crate) fn start_history_search(&mut self) {
+        self.command_history.recalled = None;
+        self.command_history.search = Some(Search {
+            text: String::new(),
+            found: None,
+            failing: false,
+            typed: self.state.command.buffer.clone(),
+        });
+    }
+
+    pub(crate) fn handle_key_events_history_search(&mut self, key_event: KeyEvent) {
+        let Some(search) = &mut self.command_history.search else {
+            return;
+        };
+        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        let from = match key_event.code {
+            // the one before the one found
+            KeyCode::Char('r') if ctrl => {
+                search.found.unwrap_or(self.command_history.commands.len())
+            }
+            KeyCode::Char('g') if ctrl => {
+                self.state.command.buffer = std::mem::take(&mut search.typed);
+                self.command_history.search = None;
+                return;
+            }
+            KeyCode::Char(c) if !ctrl => {
+                search.text.push(c);
+                // the newest with the longer text could be the one found, but none newer
+                search
+                    .found
+                    .map_or(self.command_history.commands.len(), |i| i + 1)
+            }
+            KeyCode::Backspace => {
+                search.text.pop();
+                self.command_history.commands.len()
+            }
+            KeyCode::Esc => {
+                self.state.command.buffer = std::mem::take(&mut search.typed);
+                self.command_history.search = None;
+                return;
+            }
+            KeyCode::Enter => {
+                self.command_history.search = None;
+                let command = self.state.command.buffer.clone();
+                self.run_typed_command(command);
+                return;
+            }
+            // anything else keeps the command found, to go on typing it
+            _ => {
+                self.command_history.search = None;
+                return;
+            }
+        };
+        let text = search.text.clone();
+        let found = self.command_history.find(&text, from);
+        let Some(search) = &mut self.command_history.search else {
+            return;
+        };
+        if text.is_empty() {
+            search.found = None;
+            search.failing = false;
+            self.state.command.buffer = search.typed.clone();
+            return;
+        }
+        // a failed search keeps showing the last command found
+        match found {
+            Some(i) => {
+                search.found = Some(i);
+                search.failing = false;
+                self.state.command.buffer = self.command_history.commands[i].clone();
+            }
+            None => search.failing = true,
+        }
+    }
+
+    /// the search's prompt over the bottom left of `area`, above the command it found
+    pub(crate) fn render_history_search(&self, area: Rect, buf: &mut Buffer) {
+        let Some(search) = &self.command_history.search else {
+            return;
+        };
+        let area = area.inner(Margin::new(1, 1));
+        let mut line = Line::from(vec![" Search back: ".into(), search.text.clone().bold()]);
+        if search.failing {
+            line.push_span("  no match".red());
+        }
+        line.push_span(" ");
+        let width = (line.width() as u16 + 2).min(area.width);
+        let popup = Rect::new(
+            area.x,
+            area.bottom().saturating_sub(3),
+            width,
+            3.min(area.height),
+        );
+        let block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .title_bottom(Line::from(" Ctrl-r ").fg(self.theme.accent));
+        Clear.render(popup, buf);
+        Paragraph::new(line)
+            .style(self.theme.base)
+            .block(block)
+            .render(popup, buf);
+    }
+}
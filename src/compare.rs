@@ -0,0 +1,199 @@
+//! Comparing two shots side by side. `m` in the entry list marks the selected entry, and marking a
+//! second one opens the comparison: every field of the two entries in a row, the rows where they
+//! differ highlighted and, for numbers, by how much the second differs from the first. `c` shows
+//! only the rows that differ, and q goes back to the list.
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Rect},
+    style::{Style, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Block, Cell, Paragraph, Row, StatefulWidget, Table, TableState, Widget},
+};
+
+use crate::{fields::Field, keymap::COMPARE_KEYS, scroll, App, Entry, Phase, SELECTED_SYMBOL};
+
+#[derive(Debug, Default)]
+pub struct CompareView {
+    rows: TableState,
+    /// whether the rows where the entries agree are left out
+    changed_only: bool,
+}
+
+/// one field of both entries
+struct FieldRow {
+    label: String,
+    values: [String; 2],
+    /// how far the second entry's number is from the first's, when the field is a number
+    change: Option<String>,
+}
+
+impl FieldRow {
+    fn changed(&self) -> bool {
+        self.values[0] != self.values[1]
+    }
+}
+
+/// the label and value of a field's line in the edit view, "  Dose: 18.0 g"
+fn split_line(line: &Line) -> (String, String) {
+    let text = line.to_string();
+    match text.trim_start().split_once(": ") {
+        Some((label, value)) => (label.to_string(), value.to_string()),
+        None => (text.trim().to_string(), String::new()),
+    }
+}
+
+impl App {
+    /// marks the selected entry for comparing, or takes the mark off it, and compares the two
+    /// entries once there are two
+    pub(crate) fn mark_for_compare(&mut self) {
+        let Some(i) = self.selected_entry() else {
+            return;
+        };
+        let marks = &mut self.state.compare_marks;
+        if let Some(at) = marks.iter().position(|&m| m == i) {
+            marks.remove(at);
+            return;
+        }
+        marks.push(i);
+        if let [a, b] = marks[..] {
+            marks.clear();
+            self.state.compare = CompareView::default();
+            self.state.compare.rows.select_first();
+            self.phase = Phase::Compare(a, b);
+        }
+    }
+
+    /// the rows of the comparison: the first entry's fields, then any only the second has
+    fn compare_rows(&self, a: &Entry, b: &Entry) -> Vec<FieldRow> {
+        let mut fields = self.entry_fields(a);
+        for field in self.entry_fields(b) {
+            if !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+        let photos = a.photos.len().max(b.photos.len());
+        fields
+            .into_iter()
+            // the empty row for adding a photo
+            .filter(|field| !matches!(field, Field::Photo(i) if *i >= photos))
+            .map(|field| {
+                let (label, first) = split_line(&self.field_line(a, field));
+                let (_, second) = split_line(&self.field_line(b, field));
+                // a temperature's number is in Celsius whatever unit it's shown in
+                let unit = self.config.weight_unit;
+                let change = match (field, field.number(a), field.number(b)) {
+                    (Field::Temperature, _, _) => None,
+                    (Field::Dose | Field::Output, Some(x), Some(y)) if x != y => Some(format!(
+                        "{:+.2$} {}",
+                        unit.in_unit(y - x),
+                        unit.symbol(),
+                        unit.decimals()
+                    )),
+                    (_, Some(x), Some(y)) if x != y => {
+                        Some(format!("{:+.1}{}", y - x, field.unit()))
+                    }
+                    _ => None,
+                };
+                FieldRow {
+                    label,
+                    values: [first, second],
+                    change,
+                }
+            })
+            .filter(|row| !self.state.compare.changed_only || row.changed())
+            .collect()
+    }
+
+    pub(crate) fn handle_key_events_compare(&mut self, key_event: KeyEvent) {
+        let rows = &mut self.state.compare.rows;
+        if let Some(by) = scroll::page_move(key_event, self.state.page_rows) {
+            if by > 0 {
+                rows.scroll_down_by(by.unsigned_abs() as u16);
+            } else {
+                rows.scroll_up_by(by.unsigned_abs() as u16);
+            }
+            return;
+        }
+        match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => rows.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => rows.select_previous(),
+            KeyCode::Char('c') => {
+                self.state.compare.changed_only = !self.state.compare.changed_only;
+                self.state.compare.rows.select_first();
+            }
+            KeyCode::Char('q') | KeyCode::Esc => self.phase = Phase::ListView,
+            _ => {}
+        }
+    }
+
+    pub(crate) fn render_compare(&mut self, a: usize, b: usize, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title(self.title())
+            .border_set(border::ROUNDED);
+        let (Some(first), Some(second)) = (self.data.entries.get(a), self.data.entries.get(b))
+        else {
+            Paragraph::new("  One of the entries is gone.")
+                .block(block)
+                .render(area, buf);
+            return;
+        };
+        let rows = self.compare_rows(first, second);
+        let changed = rows.iter().filter(|r| r.changed()).count();
+        let header = Row::new([
+            Cell::from("Field"),
+            Cell::from(self.date_text(first.dt_taken)),
+            Cell::from(self.date_text(second.dt_taken)),
+            Cell::from("Change"),
+        ])
+        .bold();
+        let total = rows.len();
+        let rows = rows.into_iter().map(|row| {
+            let style = if row.changed() {
+                Style::new().fg(self.theme.accent).bold()
+            } else {
+                Style::new()
+            };
+            let [first, second] = row.values;
+            Row::new([
+                Cell::from(row.label),
+                Cell::from(first),
+                Cell::from(second),
+                Cell::from(row.change.unwrap_or_default()),
+            ])
+            .style(style)
+        });
+        let bottom = format!(" {changed} of the fields differ ");
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(18),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Length(12),
+            ],
+        )
+        .header(header)
+        .column_spacing(2)
+        .row_highlight_style(self.theme.selected)
+        .highlight_symbol(SELECTED_SYMBOL)
+        .block(block.title_bottom(Line::from(bottom).right_aligned()));
+        self.state.page_rows = scroll::visible_rows(area, 1);
+        StatefulWidget::render(table, area, buf, &mut self.state.compare.rows);
+        let selected = self.state.compare.rows.selected();
+        let rows_area = Rect {
+            y: area.y + 1,
+            height: area.height.saturating_sub(1),
+            ..area
+        };
+        self.render_scrollbar(rows_area, buf, total, self.state.page_rows, selected);
+    }
+
+    pub(crate) fn render_footer_compare(&self, area: Rect, buf: &mut Buffer) {
+        let controls = self.controls(COMPARE_KEYS, area.width);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
@@ -0,0 +1,295 @@
+//! Completing commands as they're typed, like a shell. Once part of a word is typed after `:`, a
+//! popup above the footer lists what it could be: the commands themselves and the config's
+//! aliases, and then the values each command takes, like the fields `:sort` sorts by, the
+//! coffees, grinders and brewers `:filter` knows, or the saved views. Tab puts the first of them
+//! in and goes on to the next, Shift-Tab goes back, and when there's only one it's put in straight
+//! away. Names with spaces in them are put in quoted, the way [`crate::command`] reads them.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Margin, Rect, Size},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, List, ListState, Padding, StatefulWidget, Widget},
+};
+
+use crate::{
+    command::{range_len, split, COMMANDS},
+    fields::METHODS,
+    presets::{preset_kind, PRESETS},
+    sort::SORT_KEYS,
+    theme::THEMES,
+    App, InputMode,
+};
+
+/// the most completions the popup shows at once, the rest scroll
+const MAX_ROWS: usize = 8;
+
+/// the completions being gone through with Tab
+#[derive(Debug)]
+pub struct Completion {
+    /// where the completed word starts in the command
+    start: usize,
+    candidates: Vec<String>,
+    selected: usize,
+}
+
+/// where the word being typed starts, outside of quotes, and the words before it
+fn last_word(line: &str) -> (usize, Vec<String>) {
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => start = i + c.len_utf8(),
+            _ => {}
+        }
+    }
+    let before = split(&line[..start]).unwrap_or_default();
+    (start, before)
+}
+
+/// `name` as an argument, quoted if it has spaces in it
+fn quote(name: &str) -> String {
+    if name.contains(char::is_whitespace) {
+        format!("\"{name}\"")
+    } else {
+        name.to_string()
+    }
+}
+
+fn words(words: &[&str]) -> Vec<String> {
+    words.iter().map(|w| w.to_string()).collect()
+}
+
+impl App {
+    /// what the word being typed in `line` could be, and where that word starts
+    fn completions(&self, line: &str) -> (usize, Vec<String>) {
+        // past the `:` and any range before the name
+        let skip = line.strip_prefix(':').map_or(0, |after| {
+            line.len() - after[range_len(after)..].trim_start().len()
+        });
+        let (start, before) = last_word(&line[skip..]);
+        let start = skip + start;
+        let typed = line[start..].replace('"', "").to_lowercase();
+        let candidates = match before.split_first() {
+            None => {
+                let aliases = self.config.aliases.iter().map(|(alias, _)| alias.as_str());
+                let names = COMMANDS
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .chain(aliases)
+                    .filter(|name| name.starts_with(&typed))
+                    .map(String::from)
+                    .collect();
+                return (skip, names);
+            }
+            Some((name, args)) => match self.config.aliases.iter().find(|(a, _)| a == name) {
+                // the values of the command the alias stands for, after its own arguments
+                Some((_, command)) => {
+                    let mut words = split(command).unwrap_or_default();
+                    words.extend_from_slice(args);
+                    match words.split_first() {
+                        Some((name, args)) => self.argument_values(name, args, &typed),
+                        None => Vec::new(),
+                    }
+                }
+                None => self.argument_values(name, args, &typed),
+            },
+        };
+        let candidates = candidates
+            .into_iter()
+            .filter(|c| c.replace('"', "").to_lowercase().starts_with(&typed))
+            .collect();
+        (start, candidates)
+    }
+
+    /// the values `:name` takes after `args`, given the start of one being typed
+    fn argument_values(&self, name: &str, args: &[String], typed: &str) -> Vec<String> {
+        let views = || self.data.views.iter().map(|v| quote(&v.name)).collect();
+        match (name, args.len()) {
+            ("sync", 0) => words(&["pull", "push"]),
+            ("import", 0) => words(&["csv", "beanconqueror"]),
+            ("export", 0) => words(&["csv", "markdown"]),
+            ("report", 0) => words(&["week"]),
+            ("filter", _) => self.filter_terms(typed, args.is_empty()),
+            ("new", _) => self.preset_terms(typed),
+            ("tag", 0) => self.known_tags(),
+            ("sort", 0) => SORT_KEYS.iter().map(|k| k.key().to_string()).collect(),
+            ("sort", 1) => words(&["asc", "desc"]),
+            ("view", 0) => {
+                let mut values: Vec<String> = words(&["save", "delete"]);
+                values.extend(views());
+                values
+            }
+            ("view", 1) if matches!(args[0].as_str(), "save" | "delete") => views(),
+            ("period", 0) => words(&["week", "7d", "30d", "month", "year", "pick", "clear"]),
+            ("chart", 0) => words(&["ratio", "grind", "flow", "off"]),
+            ("scale", 0) => words(&["off"]),
+            ("theme", 0) => words(&THEMES),
+            ("set", 0) => words(&[
+                "units=g",
+                "units=oz",
+                "temperature=c",
+                "temperature=f",
+                "dateformat=",
+                "relative-dates",
+                "norelative-dates",
+            ]),
+            _ => Vec::new(),
+        }
+    }
+
+    /// the terms of `:filter`: the names after `coffee:` and the like once one's typed, and
+    /// otherwise the fields it filters by
+    fn filter_terms(&self, typed: &str, first: bool) -> Vec<String> {
+        let names: Vec<String> = match typed.split_once(':').map(|(field, _)| field) {
+            Some("coffee") => self.data.coffees.iter().map(|c| c.name.clone()).collect(),
+            Some("grinder") => self.data.grinders.iter().map(|g| g.name.clone()).collect(),
+            Some("brewer") => self.data.brewers.iter().map(|b| b.name.clone()).collect(),
+            Some("method") => METHODS.iter().map(|m| m.label().to_lowercase()).collect(),
+            Some("tag") => self.known_tags(),
+            Some(_) => Vec::new(),
+            None => {
+                let mut terms = words(&[
+                    "coffee:", "grinder:", "brewer:", "method:", "tag:", "favorite", "dose",
+                    "yield", "ratio", "time", "rating",
+                ]);
+                if first {
+                    terms.push(String::from("clear"));
+                }
+                return terms;
+            }
+        };
+        let field = typed.split_once(':').map_or("", |(field, _)| field);
+        names
+            .iter()
+            .map(|name| format!("{field}:{}", quote(name)))
+            .collect()
+    }
+
+    /// the `field=value`s of `:new`: the names after `coffee=` and the like once one's typed, and
+    /// otherwise the fields it fills in
+    fn preset_terms(&self, typed: &str) -> Vec<String> {
+        let Some((field, _)) = typed.split_once('=') else {
+            return PRESETS.iter().map(|field| format!("{field}=")).collect();
+        };
+        let names: Vec<String> = match preset_kind(field) {
+            Some(kind) => self.catalog_names(kind).into_iter().map(quote).collect(),
+            None if field == "method" => METHODS.iter().map(|m| m.key().to_string()).collect(),
+            None => Vec::new(),
+        };
+        names
+            .into_iter()
+            .map(|name| format!("{field}={name}"))
+            .collect()
+    }
+
+    /// every tag on an entry, in the order they were first used
+    fn known_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.data.entries.iter().flat_map(|e| &e.tags) {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        tags
+    }
+
+    /// Tab, or Shift-Tab going `back`: puts the next completion in place of the word being typed
+    pub(crate) fn complete_command(&mut self, back: bool) {
+        let command = &mut self.state.command;
+        if let Some(completion) = &mut command.completion {
+            let count = completion.candidates.len();
+            completion.selected = if back {
+                (completion.selected + count - 1) % count
+            } else {
+                (completion.selected + 1) % count
+            };
+            command.buffer.truncate(completion.start);
+            command
+                .buffer
+                .push_str(&completion.candidates[completion.selected]);
+            return;
+        }
+        if !command.buffer.starts_with(':') {
+            return;
+        }
+        let (start, candidates) = self.completions(&self.state.command.buffer);
+        let command = &mut self.state.command;
+        match &candidates[..] {
+            [] => {}
+            [only] => {
+                command.buffer.truncate(start);
+                command.buffer.push_str(only);
+                // straight on to the value after `coffee:` or `dose=`
+                if !only.ends_with([':', '=']) {
+                    command.buffer.push(' ');
+                }
+            }
+            _ => {
+                let selected = if back { candidates.len() - 1 } else { 0 };
+                command.buffer.truncate(start);
+                command.buffer.push_str(&candidates[selected]);
+                command.completion = Some(Completion {
+                    start,
+                    candidates,
+                    selected,
+                });
+            }
+        }
+    }
+
+    /// the popup of completions over the bottom of `area`, under the word they'd complete
+    pub(crate) fn render_completions(&self, area: Rect, buf: &mut Buffer) {
+        let command = &self.state.command;
+        if !matches!(command.input_mode, InputMode::Editing) || !command.buffer.starts_with(':') {
+            return;
+        }
+        let (start, candidates, selected) = match &command.completion {
+            Some(c) => (c.start, c.candidates.clone(), Some(c.selected)),
+            None => {
+                let (start, candidates) = self.completions(&command.buffer);
+                // nothing typed yet, or nothing left to add
+                if start == command.buffer.len() || candidates == [&command.buffer[start..]] {
+                    return;
+                }
+                (start, candidates, None)
+            }
+        };
+        if candidates.is_empty() {
+            return;
+        }
+        // inside the screen's border
+        let inner = area.inner(Margin::new(1, 1));
+        let widest = candidates
+            .iter()
+            .map(|c| c.chars().count())
+            .max()
+            .unwrap_or(0);
+        let size = Size::new(
+            (widest as u16 + 4).min(inner.width),
+            (candidates.len().min(MAX_ROWS) as u16 + 2).min(inner.height),
+        );
+        // the completions lined up with the word, which the footer shows from its first column,
+        // past the popup's border and padding
+        let column = area.x + command.buffer[..start].chars().count() as u16;
+        let x = column
+            .saturating_sub(2)
+            .clamp(inner.x, inner.right() - size.width);
+        let popup = Rect::new(x, inner.bottom() - size.height, size.width, size.height);
+        let items: Vec<Line> = candidates.into_iter().map(Line::from).collect();
+        let block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .padding(Padding::horizontal(1))
+            .title_bottom(Line::from(" Tab ").fg(self.theme.accent));
+        let list = List::new(items)
+            .style(self.theme.base)
+            .highlight_style(self.theme.selected)
+            .block(block);
+        let mut state = ListState::default().with_selected(selected);
+        Clear.render(popup, buf);
+        StatefulWidget::render(list, popup, buf, &mut state);
+    }
+}
@@ -0,0 +1,181 @@
+//! User settings read from `config.json` in the config directory. Every setting is optional and
+//! a missing file is the same as an empty one. `:set` changes a few of them while running and
+//! writes them back, see [`crate::settings`].
+
+use std::{fs, io, path::PathBuf};
+
+use crate::{
+    aliases::aliases_from_config,
+    columns::ShownColumn,
+    custom::CustomField,
+    json::{self, invalid, FromJson, Value},
+    paths,
+    pour::{self, PourSchedule},
+    settings::valid_date_format,
+    temperature::TemperatureUnit,
+    theme::Theme,
+    weight::WeightUnit,
+};
+
+#[derive(Debug, Default)]
+pub struct Config {
+    /// where to keep the data file instead of the platform default, e.g. a synced folder
+    pub data_dir: Option<PathBuf>,
+    /// same as passing `--encrypt`
+    pub encrypt: bool,
+    /// API token for uploading shots to visualizer.coffee
+    pub visualizer_token: Option<String>,
+    /// keep the data directory in a git repository, see [`crate::sync`]
+    pub git_sync: bool,
+    /// where `:sync` pulls from and pushes to
+    pub git_remote: Option<String>,
+    /// WebDAV folder `:sync` keeps a copy of the data file in, when not syncing with git
+    pub webdav_url: Option<String>,
+    pub webdav_user: Option<String>,
+    pub webdav_password: Option<String>,
+    /// grams left in a bag below which it is shown as running low
+    pub low_stock_g: Option<f64>,
+    /// milligrams of caffeine a day above which it's flagged, see [`crate::caffeine`]
+    pub caffeine_limit_mg: Option<f64>,
+    /// what brew temperatures are shown and typed in
+    pub temperature_unit: TemperatureUnit,
+    /// what doses and outputs are shown and typed in
+    pub weight_unit: WeightUnit,
+    /// how dates are shown, a chrono format like "%d %b %H:%M", instead of [`crate::DATE_FMT`]
+    pub date_format: Option<String>,
+    /// show recent dates as "3 h ago" or "yesterday 08:12"
+    pub relative_dates: bool,
+    /// put in front of prices, e.g. "$" or "€"
+    pub currency: String,
+    /// seconds the shot timer counts the pre-infusion down for, see [`crate::timer`]
+    pub preinfusion_countdown_s: Option<f64>,
+    /// what's meant to be spent on coffee a month, months over it are flagged, see [`crate::cost`]
+    pub monthly_budget: Option<f64>,
+    /// extra fields for entries, see [`crate::custom`]
+    pub custom_fields: Vec<CustomField>,
+    /// the entry list's columns, see [`crate::columns`]
+    pub columns: Vec<ShownColumn>,
+    /// pours for `:brew` by name, see [`crate::pour`]
+    pub pour_schedules: Vec<(String, PourSchedule)>,
+    /// the program printing a smart scale's readings, see [`crate::scale`]
+    pub scale_command: Option<String>,
+    /// the colors, see [`crate::theme`]
+    pub theme: Theme,
+    /// commands of one's own and what they stand for, see [`crate::aliases`]
+    pub aliases: Vec<(String, String)>,
+}
+
+impl Config {
+    pub fn load() -> io::Result<Self> {
+        let path = paths::config_file();
+        match fs::read_to_string(&path) {
+            Ok(contents) => Self::from_json(&json::parse(&contents)?)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display()))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// writes `value` under `key` into the config file, leaving the rest of it as it was
+pub fn save_setting(key: &str, value: Value) -> io::Result<()> {
+    let path = paths::config_file();
+    let mut config = match fs::read_to_string(&path) {
+        Ok(contents) => json::parse(&contents)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Value::Object(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    config.set(key, value);
+    fs::create_dir_all(paths::config_dir())?;
+    fs::write(path, config.to_string_pretty())
+}
+
+fn opt_str<'a>(value: &'a Value, key: &str) -> io::Result<Option<&'a str>> {
+    match value.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(_) => Err(invalid(format!("`{key}` should be a string"))),
+    }
+}
+
+fn opt_bool(value: &Value, key: &str) -> io::Result<Option<bool>> {
+    match value.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Bool(b)) => Ok(Some(*b)),
+        Some(_) => Err(invalid(format!("`{key}` should be true or false"))),
+    }
+}
+
+fn opt_f64(value: &Value, key: &str) -> io::Result<Option<f64>> {
+    match value.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(n)) => Ok(Some(*n)),
+        Some(_) => Err(invalid(format!("`{key}` should be a number"))),
+    }
+}
+
+impl FromJson for Config {
+    fn from_json(value: &Value) -> io::Result<Self> {
+        Ok(Self {
+            data_dir: opt_str(value, "data_dir")?.map(|dir| paths::expand_home(dir.as_ref())),
+            encrypt: opt_bool(value, "encrypt")?.unwrap_or(false),
+            visualizer_token: opt_str(value, "visualizer_token")?.map(String::from),
+            git_sync: opt_bool(value, "git_sync")?.unwrap_or(false),
+            git_remote: opt_str(value, "git_remote")?.map(String::from),
+            webdav_url: opt_str(value, "webdav_url")?.map(String::from),
+            webdav_user: opt_str(value, "webdav_user")?.map(String::from),
+            webdav_password: opt_str(value, "webdav_password")?.map(String::from),
+            low_stock_g: opt_f64(value, "low_stock_g")?,
+            caffeine_limit_mg: opt_f64(value, "caffeine_limit_mg")?,
+            monthly_budget: opt_f64(value, "monthly_budget")?,
+            preinfusion_countdown_s: opt_f64(value, "preinfusion_countdown_s")?,
+            custom_fields: match value.get("custom_fields") {
+                None | Some(Value::Null) => Vec::new(),
+                Some(Value::Array(fields)) => fields
+                    .iter()
+                    .map(CustomField::from_config)
+                    .collect::<io::Result<_>>()?,
+                Some(_) => return Err(invalid("`custom_fields` should be an array")),
+            },
+            columns: match value.get("columns") {
+                None | Some(Value::Null) => Vec::new(),
+                Some(Value::Array(columns)) => columns
+                    .iter()
+                    .map(ShownColumn::from_config)
+                    .collect::<io::Result<_>>()?,
+                Some(_) => return Err(invalid("`columns` should be an array")),
+            },
+            pour_schedules: match value.get("pour_schedules") {
+                None => Vec::new(),
+                Some(schedules) => pour::schedules_from_config(schedules)?,
+            },
+            scale_command: opt_str(value, "scale_command")?.map(String::from),
+            theme: match value.get("theme") {
+                None => Theme::default(),
+                Some(theme) => Theme::from_config(theme)?,
+            },
+            currency: opt_str(value, "currency")?.unwrap_or_default().to_string(),
+            temperature_unit: match opt_str(value, "temperature_unit")? {
+                None => TemperatureUnit::default(),
+                Some(key) => TemperatureUnit::from_key(key).ok_or_else(|| {
+                    invalid("`temperature_unit` should be \"celsius\" or \"fahrenheit\"")
+                })?,
+            },
+            weight_unit: match opt_str(value, "weight_unit")? {
+                None => WeightUnit::default(),
+                Some(key) => WeightUnit::from_key(key)
+                    .ok_or_else(|| invalid("`weight_unit` should be \"grams\" or \"ounces\""))?,
+            },
+            date_format: match opt_str(value, "date_format")? {
+                None => None,
+                Some(format) if valid_date_format(format) => Some(format.to_string()),
+                Some(_) => return Err(invalid("`date_format` isn't a date format like \"%d %b\"")),
+            },
+            relative_dates: opt_bool(value, "relative_dates")?.unwrap_or(false),
+            aliases: match value.get("aliases") {
+                None => Vec::new(),
+                Some(aliases) => aliases_from_config(aliases)?,
+            },
+        })
+    }
+}
@@ -0,0 +1,100 @@
+//! How consistent each grinder is: the spread of shot times between shots pulled at the same grind
+//! setting, as a standard deviation in seconds. Only shots of the same coffee are compared with
+//! each other, since a different bean at the same setting runs differently anyway; the spreads of
+//! every coffee and setting are then pooled into one figure for the grinder.
+
+use std::collections::HashMap;
+
+use ratatui::{style::Stylize, text::Line};
+use uuid::Uuid;
+
+use crate::App;
+
+/// how one grinder's shots spread out
+struct Consistency {
+    grinder_id: Uuid,
+    /// pooled standard deviation of shot time, in seconds
+    spread: f64,
+    /// shots that had another at the same coffee and setting to compare with
+    shots: usize,
+    /// coffee and setting pairs those shots were pulled at
+    settings: usize,
+}
+
+impl App {
+    fn grinder_consistency(&self) -> Vec<Consistency> {
+        // settings are compared by their bits, which is fine since they're typed in, not worked out
+        let mut groups: HashMap<(Uuid, Uuid, u64), Vec<f64>> = HashMap::new();
+        // shots left at zero seconds weren't timed
+        for entry in self
+            .data
+            .entries
+            .iter()
+            .filter(|e| e.duration > 0.0 && self.in_period(e))
+        {
+            let key = (
+                entry.grinder_id,
+                entry.coffee_id,
+                entry.grind_setting.to_bits(),
+            );
+            groups.entry(key).or_default().push(entry.duration);
+        }
+        // (sum of squared deviations, degrees of freedom, shots, settings) for each grinder
+        let mut pooled: HashMap<Uuid, (f64, usize, usize, usize)> = HashMap::new();
+        for ((grinder_id, _, _), times) in groups {
+            if times.len() < 2 {
+                continue;
+            }
+            let mean = times.iter().sum::<f64>() / times.len() as f64;
+            let squares: f64 = times.iter().map(|t| (t - mean).powi(2)).sum();
+            let (sum, freedom, shots, settings) = pooled.entry(grinder_id).or_default();
+            *sum += squares;
+            *freedom += times.len() - 1;
+            *shots += times.len();
+            *settings += 1;
+        }
+        let mut consistency: Vec<Consistency> = pooled
+            .into_iter()
+            .map(
+                |(grinder_id, (sum, freedom, shots, settings))| Consistency {
+                    grinder_id,
+                    spread: (sum / freedom as f64).sqrt(),
+                    shots,
+                    settings,
+                },
+            )
+            .collect();
+        consistency.sort_by(|a, b| a.spread.total_cmp(&b.spread));
+        consistency
+    }
+
+    /// the grinder section of the stats screen, most consistent first
+    pub(crate) fn consistency_lines(&self) -> Vec<Line<'static>> {
+        let consistency = self.grinder_consistency();
+        if consistency.is_empty() {
+            return Vec::new();
+        }
+        let mut lines = vec![Line::from("  Shot time spread by grinder").bold()];
+        for c in consistency {
+            let name = self
+                .data
+                .grinders
+                .iter()
+                .find(|g| g.uuid == c.grinder_id)
+                .map_or("Unknown grinder", |g| g.name.as_str());
+            lines.push(Line::from(format!(
+                "    {name:<16} {:>6.1} sec  ({} shots at {} {})",
+                c.spread,
+                c.shots,
+                c.settings,
+                if c.settings == 1 {
+                    "setting"
+                } else {
+                    "settings"
+                },
+            )));
+        }
+        lines.push(Line::from(""));
+        lines
+    }
+}
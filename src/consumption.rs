@@ -0,0 +1,123 @@
+//! How fast the coffee goes: grams used a week and bags used a month, worked out from the doses of
+//! the entries and the bag sizes of their coffees, and when the bag being brewed from will run out
+//! at the pace of the last couple of weeks.
+
+use chrono::{Datelike, Days, Local, Months, NaiveDate};
+use ratatui::{style::Stylize, text::Line};
+
+use crate::{freshness::DAY_FMT, App, Coffee};
+
+/// how many weeks back the stats screen goes, this one included
+const WEEKS_SHOWN: u64 = 4;
+/// and how many months
+const MONTHS_SHOWN: u32 = 3;
+/// how many days back the pace a bag is used at is taken from
+const PACE_DAYS: u64 = 14;
+
+impl App {
+    /// grams used from `from` to `to`, both included, of the coffees `of` picks
+    fn grams_used(&self, from: NaiveDate, to: NaiveDate, of: impl Fn(&Coffee) -> bool) -> f64 {
+        self.data
+            .entries
+            .iter()
+            .filter(|e| {
+                let day = e.dt_taken.date_naive();
+                from <= day && day <= to
+            })
+            .filter(|e| {
+                self.data
+                    .coffees
+                    .iter()
+                    .find(|c| c.uuid == e.coffee_id)
+                    .is_some_and(&of)
+            })
+            .fold(0.0, |total, e| total + e.dose)
+    }
+
+    /// bags used from `from` to `to`: each coffee's grams over its bag size, for the coffees with
+    /// one
+    fn bags_used(&self, from: NaiveDate, to: NaiveDate) -> f64 {
+        self.data
+            .coffees
+            .iter()
+            .filter_map(|coffee| {
+                let size = coffee.bag_size_g.filter(|&g| g > 0.0)?;
+                Some(self.grams_used(from, to, |c| c.uuid == coffee.uuid) / size)
+            })
+            .fold(0.0, |total, bags| total + bags)
+    }
+
+    /// the coffee of the latest entry, if its bag is tracked and there's some left
+    pub(crate) fn open_bag(&self) -> Option<&Coffee> {
+        let last = self.data.entries.iter().max_by_key(|e| e.dt_taken)?;
+        self.data
+            .coffees
+            .iter()
+            .find(|c| c.uuid == last.coffee_id)
+            .filter(|c| c.bag_size_g.is_some() && c.remaining_g > 0.0)
+    }
+
+    /// the day the coffee's bag runs out at the pace of the last `PACE_DAYS`, if it's been brewed
+    /// in them
+    pub(crate) fn bag_empty_day(&self, coffee: &Coffee) -> Option<NaiveDate> {
+        let today = Local::now().date_naive();
+        let from = today.checked_sub_days(Days::new(PACE_DAYS - 1))?;
+        let per_day = self.grams_used(from, today, |c| c.uuid == coffee.uuid) / PACE_DAYS as f64;
+        if per_day <= 0.0 {
+            return None;
+        }
+        today.checked_add_days(Days::new((coffee.remaining_g / per_day).ceil() as u64))
+    }
+
+    /// the consumption section of the stats screen
+    pub(crate) fn consumption_lines(&self) -> Vec<Line<'static>> {
+        let today = Local::now().date_naive();
+        let mut lines = vec![Line::from("  Consumption").bold()];
+        let monday =
+            today.checked_sub_days(Days::new(today.weekday().num_days_from_monday().into()));
+        for back in 0..WEEKS_SHOWN {
+            let Some(from) = monday.and_then(|m| m.checked_sub_days(Days::new(7 * back))) else {
+                break;
+            };
+            let to = from
+                .checked_add_days(Days::new(6))
+                .unwrap_or(from)
+                .min(today);
+            let label = match back {
+                0 => String::from("This week"),
+                _ => format!("Week of {}", from.format("%b %-d")),
+            };
+            let grams = self.grams_used(from, to, |_| true);
+            lines.push(Line::from(format!("    {label:<16} {grams:>7.0} g")));
+        }
+        let first = today.with_day(1).unwrap_or(today);
+        for back in 0..MONTHS_SHOWN {
+            let Some(from) = first.checked_sub_months(Months::new(back)) else {
+                break;
+            };
+            let to = from
+                .checked_add_months(Months::new(1))
+                .and_then(|next| next.pred_opt())
+                .unwrap_or(from)
+                .min(today);
+            let bags = self.bags_used(from, to);
+            let label = from.format("%B %Y").to_string();
+            lines.push(Line::from(format!("    {label:<16} {bags:>7.1} bags")));
+        }
+        if let Some(coffee) = self.open_bag() {
+            lines.push(Line::from(match self.bag_empty_day(coffee) {
+                Some(day) => format!(
+                    "    {} runs out around {}",
+                    coffee.name,
+                    day.format(DAY_FMT)
+                ),
+                None => format!(
+                    "    {} hasn't been brewed in the last {PACE_DAYS} days",
+                    coffee.name
+                ),
+            }));
+        }
+        lines.push(Line::from(""));
+        lines
+    }
+}
@@ -0,0 +1,122 @@
+//! How the rating of the listed entries goes along with their ratio, shot time and days off roast,
+//! as Pearson correlations, on a stats sub-view opened with `c`. Only rated entries count, and
+//! for each parameter only the ones that have it.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::{App, Entry};
+
+/// fewer shots than this don't say anything
+const MIN_SHOTS: usize = 3;
+
+/// the Pearson correlation of the pairs, if there are enough and both sides vary
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < MIN_SHOTS {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let (mean_x, mean_y) = pairs
+        .iter()
+        .fold((0.0, 0.0), |(x, y), p| (x + p.0 / n, y + p.1 / n));
+    let (mut xy, mut xx, mut yy) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        let (dx, dy) = (x - mean_x, y - mean_y);
+        xy += dx * dy;
+        xx += dx * dx;
+        yy += dy * dy;
+    }
+    (xx > 0.0 && yy > 0.0).then(|| xy / (xx * yy).sqrt())
+}
+
+/// "weak", "moderate"… by the usual rules of thumb, with as many of five blocks filled
+fn strength(r: f64) -> (&'static str, String) {
+    let label = match r.abs() {
+        a if a < 0.1 => "none",
+        a if a < 0.3 => "weak",
+        a if a < 0.5 => "moderate",
+        _ => "strong",
+    };
+    let filled = (r.abs() * 5.0).round() as usize;
+    (
+        label,
+        format!("{}{}", "▰".repeat(filled), "▱".repeat(5 - filled)),
+    )
+}
+
+impl App {
+    /// each parameter compared with the rating, with the number of shots that have both and the
+    /// correlation, if there's one
+    fn correlations(&self) -> Vec<(&'static str, usize, Option<f64>)> {
+        let rated: Vec<&Entry> = self
+            .data
+            .entries
+            .iter()
+            .filter(|e| e.rating.is_some() && self.listed(e))
+            .collect();
+        let pairs = |x: &dyn Fn(&Entry) -> Option<f64>| -> Vec<(f64, f64)> {
+            rated
+                .iter()
+                .filter_map(|e| Some((x(e)?, f64::from(e.rating?))))
+                .collect()
+        };
+        let parameters: [(&str, Vec<(f64, f64)>); 3] = [
+            (
+                "Ratio",
+                pairs(&|e| (e.dose > 0.0).then(|| e.output / e.dose)),
+            ),
+            (
+                "Shot time",
+                pairs(&|e| (e.duration > 0.0).then_some(e.duration)),
+            ),
+            (
+                "Days off roast",
+                pairs(&|e| self.days_off_roast(e).map(|d| d as f64)),
+            ),
+        ];
+        parameters
+            .into_iter()
+            .map(|(label, pairs)| (label, pairs.len(), pearson(&pairs)))
+            .collect()
+    }
+
+    pub(crate) fn render_correlations(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title(format!("{}- what goes with a good rating ", self.title()))
+            .border_set(border::ROUNDED);
+        let mut lines = vec![Line::from("")];
+        for (label, shots, r) in self.correlations() {
+            let label = format!("  {label} vs rating");
+            lines.push(match r {
+                Some(r) => {
+                    let (word, blocks) = strength(r);
+                    Line::from(vec![
+                        format!("{label:<28} {r:>+5.2}  ").into(),
+                        if r < 0.0 {
+                            blocks.red()
+                        } else {
+                            blocks.green()
+                        },
+                        format!(" {word:<9}({shots} shots)").into(),
+                    ])
+                }
+                None => Line::from(format!(
+                    "{label:<28}     -  too few rated shots to tell ({shots})"
+                ))
+                .dark_gray(),
+            });
+        }
+        lines.push(Line::from(""));
+        lines.push(
+            Line::from("  Of the listed entries; narrow them with :filter or a period first.")
+                .dark_gray(),
+        );
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
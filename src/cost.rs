@@ -0,0 +1,178 @@
+//! What the coffee costs. A coffee can have the price of its bag, and a shot costs its share of
+//! that: the price over the bag size, times the dose. Shots are priced at the current bag's price,
+//! so a new price reprices the earlier shots of that coffee too.
+//!
+//! The stats screen's `m` breaks the spending down by month, coffee and roaster, and holds each
+//! month up against the `monthly_budget` setting when there is one.
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::{App, Entry};
+
+/// a name with the cost of its shots and how many there were
+struct Spend {
+    name: String,
+    total: f64,
+    shots: usize,
+}
+
+/// what was spent in one month
+pub struct MonthSpend {
+    /// the first day of the month
+    pub month: NaiveDate,
+    pub total: f64,
+    /// how many of the month's shots had a known cost
+    pub shots: usize,
+}
+
+impl App {
+    /// what the entry's dose cost, if its coffee has both a price and a bag size
+    pub(crate) fn shot_cost(&self, entry: &Entry) -> Option<f64> {
+        let coffee = self
+            .data
+            .coffees
+            .iter()
+            .find(|c| c.uuid == entry.coffee_id)?;
+        let price = coffee.bag_price?;
+        let grams = coffee.bag_size_g.filter(|g| *g > 0.0)?;
+        Some(price / grams * entry.dose)
+    }
+
+    /// e.g. "$0.62", with the `currency` from the config
+    pub(crate) fn money(&self, amount: f64) -> String {
+        format!("{}{amount:.2}", self.config.currency)
+    }
+
+    /// the cost of the shots with a known cost in the period, totalled by month, oldest first
+    pub(crate) fn monthly_spend(&self) -> Vec<MonthSpend> {
+        let mut months: BTreeMap<NaiveDate, (f64, usize)> = BTreeMap::new();
+        for entry in self.data.entries.iter().filter(|e| self.in_period(e)) {
+            let Some(cost) = self.shot_cost(entry) else {
+                continue;
+            };
+            let day = entry.dt_taken.date_naive();
+            let month = day.with_day(1).unwrap_or(day);
+            let (total, shots) = months.entry(month).or_default();
+            *total += cost;
+            *shots += 1;
+        }
+        months
+            .into_iter()
+            .map(|(month, (total, shots))| MonthSpend {
+                month,
+                total,
+                shots,
+            })
+            .collect()
+    }
+
+    /// the cost of the shots with a known cost in the period, totalled by the name `by` gives
+    /// their entry, most spent first
+    fn spend_by(&self, by: impl Fn(&Entry) -> String) -> Vec<Spend> {
+        let mut spend: BTreeMap<String, (f64, usize)> = BTreeMap::new();
+        for entry in self.data.entries.iter().filter(|e| self.in_period(e)) {
+            let Some(cost) = self.shot_cost(entry) else {
+                continue;
+            };
+            let (total, shots) = spend.entry(by(entry)).or_default();
+            *total += cost;
+            *shots += 1;
+        }
+        let mut spend: Vec<Spend> = spend
+            .into_iter()
+            .map(|(name, (total, shots))| Spend { name, total, shots })
+            .collect();
+        spend.sort_by(|a, b| b.total.total_cmp(&a.total));
+        spend
+    }
+
+    fn roaster_name(&self, entry: &Entry) -> String {
+        self.data
+            .coffees
+            .iter()
+            .find(|c| c.uuid == entry.coffee_id)
+            .and_then(|c| c.roaster_id)
+            .and_then(|id| self.data.roasters.iter().find(|r| r.uuid == id))
+            .map_or(String::from("No roaster"), |r| r.name.clone())
+    }
+
+    /// "$12.40  (20 shots, $0.62 each)"
+    fn spend_text(&self, total: f64, shots: usize) -> String {
+        format!(
+            "{:>10}  ({shots} {}, {} each)",
+            self.money(total),
+            if shots == 1 { "shot" } else { "shots" },
+            self.money(total / shots as f64)
+        )
+    }
+
+    pub(crate) fn render_costs(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title(format!("{}- spending ", self.title()))
+            .border_set(border::ROUNDED);
+        let months = self.monthly_spend();
+        if months.is_empty() {
+            let lines = vec![
+                Line::from(""),
+                Line::from("  Give a coffee a bag size and a price to see what its shots cost."),
+            ];
+            Paragraph::new(lines).block(block).render(area, buf);
+            return;
+        }
+        let (total, shots) = months
+            .iter()
+            .fold((0.0, 0), |(t, n), m| (t + m.total, n + m.shots));
+        let mut lines = vec![
+            Line::from(vec![
+                "  Average drink ".bold(),
+                format!("{}  (over {shots} shots)", self.money(total / shots as f64)).into(),
+            ]),
+            Line::from(""),
+            Line::from(match self.config.monthly_budget {
+                Some(budget) => format!("  By month (budget {})", self.money(budget)),
+                None => String::from("  By month"),
+            })
+            .bold(),
+        ];
+        for month in months.iter().rev() {
+            let line = Line::from(format!(
+                "    {:<20} {}",
+                month.month.format("%B %Y").to_string(),
+                self.spend_text(month.total, month.shots)
+            ));
+            lines.push(match self.config.monthly_budget {
+                Some(budget) if month.total > budget => line.red(),
+                _ => line,
+            });
+        }
+        let sections: [(&str, Vec<Spend>); 2] = [
+            (
+                "By coffee",
+                self.spend_by(|e| self.coffee_name(e).to_string()),
+            ),
+            ("By roaster", self.spend_by(|e| self.roaster_name(e))),
+        ];
+        for (title, spend) in sections {
+            lines.push(Line::from(""));
+            lines.push(Line::from(format!("  {title}")).bold());
+            for s in spend {
+                lines.push(Line::from(format!(
+                    "    {:<20} {}",
+                    s.name,
+                    self.spend_text(s.total, s.shots)
+                )));
+            }
+        }
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
@@ -0,0 +1,410 @@
+//! Passphrase-based encryption for the data file: ChaCha20-Poly1305 (RFC 8439) with a key derived
+//! by PBKDF2-HMAC-SHA256. Implemented here because none of our dependencies provide it.
+//!
+//! Encrypted files are laid out as `MAGIC | salt | nonce | ciphertext | tag`, with the magic and
+//! salt authenticated as associated data.
+
+use std::{fmt, io};
+
+const MAGIC: &[u8] = b"CTENC1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + SALT_LEN;
+/// OWASP's current recommendation for PBKDF2-HMAC-SHA256
+const PBKDF2_ROUNDS: u32 = 600_000;
+
+type Salt = [u8; SALT_LEN];
+type Key = [u8; 32];
+
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// the salt stored in an encrypted file, or `None` for anything else
+pub fn salt_of(bytes: &[u8]) -> Option<Salt> {
+    if is_encrypted(bytes) && bytes.len() >= HEADER_LEN {
+        bytes[MAGIC.len()..HEADER_LEN].try_into().ok()
+    } else {
+        None
+    }
+}
+
+/// holds the passphrase and the key derived from it, so the slow derivation only runs once per
+/// salt
+pub struct Cipher {
+    passphrase: String,
+    salt: Salt,
+    key: Key,
+}
+
+impl fmt::Debug for Cipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Cipher { .. }")
+    }
+}
+
+impl Cipher {
+    /// derives the key for `salt`, generating a fresh salt if there is no existing file to match
+    pub fn new(passphrase: String, salt: Option<Salt>) -> io::Result<Self> {
+        let salt = match salt {
+            Some(salt) => salt,
+            None => random()?,
+        };
+        let key = derive_key(&passphrase, &salt);
+        Ok(Self {
+            passphrase,
+            salt,
+            key,
+        })
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> io::Result<Vec<u8>> {
+        let nonce: [u8; NONCE_LEN] = random()?;
+        let mut out = Vec::with_capacity(HEADER_LEN + NONCE_LEN + plaintext.len() + TAG_LEN);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&self.salt);
+        out.extend_from_slice(&nonce);
+        let header_len = out.len();
+        out.extend_from_slice(plaintext);
+        chacha20_xor(&self.key, 1, &nonce, &mut out[header_len..]);
+        let tag = aead_tag(&self.key, &nonce, &out[..HEADER_LEN], &out[header_len..]);
+        out.extend_from_slice(&tag);
+        Ok(out)
+    }
+
+    pub fn decrypt(&self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        let salt = salt_of(bytes).ok_or_else(|| invalid("not an encrypted data file"))?;
+        if bytes.len() < HEADER_LEN + NONCE_LEN + TAG_LEN {
+            return Err(invalid("encrypted data file is truncated"));
+        }
+        // backups may have been written under an older salt
+        let key = if salt == self.salt {
+            self.key
+        } else {
+            derive_key(&self.passphrase, &salt)
+        };
+        let nonce: [u8; NONCE_LEN] = bytes[HEADER_LEN..HEADER_LEN + NONCE_LEN]
+            .try_into()
+            .unwrap();
+        let (ciphertext, tag) = bytes[HEADER_LEN + NONCE_LEN..]
+            .split_at(bytes.len() - HEADER_LEN - NONCE_LEN - TAG_LEN);
+        let expected = aead_tag(&key, &nonce, &bytes[..HEADER_LEN], ciphertext);
+        // compare without bailing out early
+        if expected
+            .iter()
+            .zip(tag)
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            != 0
+        {
+            return Err(invalid("wrong passphrase or corrupted data file"));
+        }
+        let mut plaintext = ciphertext.to_vec();
+        chacha20_xor(&key, 1, &nonce, &mut plaintext);
+        Ok(plaintext)
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn random<const N: usize>() -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    getrandom::fill(&mut buf).map_err(|e| io::Error::other(e.to_string()))?;
+    Ok(buf)
+}
+
+fn derive_key(passphrase: &str, salt: &Salt) -> Key {
+    pbkdf2_sha256(passphrase.as_bytes(), salt, PBKDF2_ROUNDS)
+}
+
+// --- ChaCha20 ---
+
+fn quarter_round(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(16);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(12);
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(8);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(7);
+}
+
+fn chacha20_block(key: &Key, counter: u32, nonce: &[u8; NONCE_LEN]) -> [u8; 64] {
+    let mut init = [0u32; 16];
+    init[..4].copy_from_slice(&[0x61707865, 0x3320646e, 0x79622d32, 0x6b206574]);
+    for i in 0..8 {
+        init[4 + i] = u32::from_le_bytes(key[i * 4..i * 4 + 4].try_into().unwrap());
+    }
+    init[12] = counter;
+    for i in 0..3 {
+        init[13 + i] = u32::from_le_bytes(nonce[i * 4..i * 4 + 4].try_into().unwrap());
+    }
+    let mut s = init;
+    for _ in 0..10 {
+        quarter_round(&mut s, 0, 4, 8, 12);
+        quarter_round(&mut s, 1, 5, 9, 13);
+        quarter_round(&mut s, 2, 6, 10, 14);
+        quarter_round(&mut s, 3, 7, 11, 15);
+        quarter_round(&mut s, 0, 5, 10, 15);
+        quarter_round(&mut s, 1, 6, 11, 12);
+        quarter_round(&mut s, 2, 7, 8, 13);
+        quarter_round(&mut s, 3, 4, 9, 14);
+    }
+    let mut out = [0; 64];
+    for i in 0..16 {
+        out[i * 4..i * 4 + 4].copy_from_slice(&s[i].wrapping_add(init[i]).to_le_bytes());
+    }
+    out
+}
+
+fn chacha20_xor(key: &Key, counter: u32, nonce: &[u8; NONCE_LEN], data: &mut [u8]) {
+    for (i, chunk) in data.chunks_mut(64).enumerate() {
+        let stream = chacha20_block(key, counter.wrapping_add(i as u32), nonce);
+        for (byte, k) in chunk.iter_mut().zip(stream) {
+            *byte ^= k;
+        }
+    }
+}
+
+// --- Poly1305, following poly1305-donna's 26-bit limb layout ---
+
+fn poly1305(key: &[u8; 32], msg: &[u8]) -> [u8; TAG_LEN] {
+    let le32 = |b: &[u8], i: usize| u32::from_le_bytes(b[i..i + 4].try_into().unwrap());
+    const MASK: u32 = 0x3ffffff;
+
+    let r0 = le32(key, 0) & 0x3ffffff;
+    let r1 = (le32(key, 3) >> 2) & 0x3ffff03;
+    let r2 = (le32(key, 6) >> 4) & 0x3ffc0ff;
+    let r3 = (le32(key, 9) >> 6) & 0x3f03fff;
+    let r4 = (le32(key, 12) >> 8) & 0x00fffff;
+    let (s1, s2, s3, s4) = (r1 * 5, r2 * 5, r3 * 5, r4 * 5);
+    let (mut h0, mut h1, mut h2, mut h3, mut h4) = (0u32, 0u32, 0u32, 0u32, 0u32);
+
+    for chunk in msg.chunks(16) {
+        let mut block = [0u8; 17];
+        block[..chunk.len()].copy_from_slice(chunk);
+        // the high bit marks the end of the block; short blocks carry it as an 0x01 byte instead
+        let hibit = if chunk.len() == 16 {
+            1 << 24
+        } else {
+            block[chunk.len()] = 1;
+            0
+        };
+        h0 += le32(&block, 0) & MASK;
+        h1 += (le32(&block, 3) >> 2) & MASK;
+        h2 += (le32(&block, 6) >> 4) & MASK;
+        h3 += (le32(&block, 9) >> 6) & MASK;
+        h4 += (le32(&block, 12) >> 8) | hibit;
+
+        let m = |a: u32, b: u32| u64::from(a) * u64::from(b);
+        let d0 = m(h0, r0) + m(h1, s4) + m(h2, s3) + m(h3, s2) + m(h4, s1);
+        let mut d1 = m(h0, r1) + m(h1, r0) + m(h2, s4) + m(h3, s3) + m(h4, s2);
+        let mut d2 = m(h0, r2) + m(h1, r1) + m(h2, r0) + m(h3, s4) + m(h4, s3);
+        let mut d3 = m(h0, r3) + m(h1, r2) + m(h2, r1) + m(h3, r0) + m(h4, s4);
+        let mut d4 = m(h0, r4) + m(h1, r3) + m(h2, r2) + m(h3, r1) + m(h4, r0);
+
+        h0 = d0 as u32 & MASK;
+        d1 += d0 >> 26;
+        h1 = d1 as u32 & MASK;
+        d2 += d1 >> 26;
+        h2 = d2 as u32 & MASK;
+        d3 += d2 >> 26;
+        h3 = d3 as u32 & MASK;
+        d4 += d3 >> 26;
+        h4 = d4 as u32 & MASK;
+        h0 += (d4 >> 26) as u32 * 5;
+        h1 += h0 >> 26;
+        h0 &= MASK;
+    }
+
+    // fully carry h
+    let mut c = h1 >> 26;
+    h1 &= MASK;
+    h2 += c;
+    c = h2 >> 26;
+    h2 &= MASK;
+    h3 += c;
+    c = h3 >> 26;
+    h3 &= MASK;
+    h4 += c;
+    c = h4 >> 26;
+    h4 &= MASK;
+    h0 += c * 5;
+    c = h0 >> 26;
+    h0 &= MASK;
+    h1 += c;
+
+    // compute h - p and keep it if it didn't underflow
+    let mut g0 = h0 + 5;
+    c = g0 >> 26;
+    g0 &= MASK;
+    let mut g1 = h1 + c;
+    c = g1 >> 26;
+    g1 &= MASK;
+    let mut g2 = h2 + c;
+    c = g2 >> 26;
+    g2 &= MASK;
+    let mut g3 = h3 + c;
+    c = g3 >> 26;
+    g3 &= MASK;
+    let g4 = (h4 + c).wrapping_sub(1 << 26);
+    let select_g = (g4 >> 31).wrapping_sub(1);
+    h0 = (h0 & !select_g) | (g0 & select_g);
+    h1 = (h1 & !select_g) | (g1 & select_g);
+    h2 = (h2 & !select_g) | (g2 & select_g);
+    h3 = (h3 & !select_g) | (g3 & select_g);
+    h4 = (h4 & !select_g) | (g4 & select_g);
+
+    // h + s mod 2^128
+    let words = [
+        h0 | (h1 << 26),
+        (h1 >> 6) | (h2 << 20),
+        (h2 >> 12) | (h3 << 14),
+        (h3 >> 18) | (h4 << 8),
+    ];
+    let mut tag = [0; TAG_LEN];
+    let mut carry = 0u64;
+    for (i, word) in words.into_iter().enumerate() {
+        let sum = u64::from(word) + u64::from(le32(key, 16 + i * 4)) + carry;
+        tag[i * 4..i * 4 + 4].copy_from_slice(&(sum as u32).to_le_bytes());
+        carry = sum >> 32;
+    }
+    tag
+}
+
+fn aead_tag(key: &Key, nonce: &[u8; NONCE_LEN], aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_LEN] {
+    let otk: [u8; 32] = chacha20_block(key, 0, nonce)[..32].try_into().unwrap();
+    let pad16 = |len: usize| (16 - len % 16) % 16;
+    let mut mac_data = Vec::with_capacity(aad.len() + ciphertext.len() + 48);
+    mac_data.extend_from_slice(aad);
+    mac_data.resize(mac_data.len() + pad16(aad.len()), 0);
+    mac_data.extend_from_slice(ciphertext);
+    mac_data.resize(mac_data.len() + pad16(ciphertext.len()), 0);
+    mac_data.extend_from_slice(&(aad.len() as u64).to_le_bytes());
+    mac_data.extend_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+    poly1305(&otk, &mac_data)
+}
+
+// --- SHA-256, HMAC and PBKDF2 ---
+
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+const SHA256_INIT: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+fn sha256_compress(state: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 64];
+    for i in 0..16 {
+        w[i] = u32::from_be_bytes(block[i * 4..i * 4 + 4].try_into().unwrap());
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(SHA256_K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *s = s.wrapping_add(v);
+    }
+}
+
+/// SHA-256 of the concatenation of `parts`
+pub fn sha256(parts: &[&[u8]]) -> [u8; 32] {
+    let mut data: Vec<u8> = parts.concat();
+    let bit_len = (data.len() as u64) * 8;
+    data.push(0x80);
+    while data.len() % 64 != 56 {
+        data.push(0);
+    }
+    data.extend_from_slice(&bit_len.to_be_bytes());
+    let mut state = SHA256_INIT;
+    for block in data.chunks(64) {
+        sha256_compress(&mut state, block);
+    }
+    digest(&state)
+}
+
+/// finishes a hash whose first `prefix_len` bytes (a multiple of 64) are already absorbed into
+/// `state`, when the remaining message is exactly 32 bytes and so fits in one padded block
+fn sha256_finish_32(mut state: [u32; 8], prefix_len: u64, msg: &[u8; 32]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    block[..32].copy_from_slice(msg);
+    block[32] = 0x80;
+    block[56..].copy_from_slice(&((prefix_len + 32) * 8).to_be_bytes());
+    sha256_compress(&mut state, &block);
+    digest(&state)
+}
+
+fn digest(state: &[u32; 8]) -> [u8; 32] {
+    let mut out = [0; 32];
+    for (i, word) in state.iter().enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], rounds: u32) -> Key {
+    let mut key_block = [0u8; 64];
+    if password.len() > 64 {
+        key_block[..32].copy_from_slice(&sha256(&[password]));
+    } else {
+        key_block[..password.len()].copy_from_slice(password);
+    }
+    let ipad = key_block.map(|b| b ^ 0x36);
+    let opad = key_block.map(|b| b ^ 0x5c);
+    // the key pads are exactly one block, so their hash states can be computed once and every
+    // HMAC of a 32 byte message after that costs two compressions
+    let mut inner_state = SHA256_INIT;
+    sha256_compress(&mut inner_state, &ipad);
+    let mut outer_state = SHA256_INIT;
+    sha256_compress(&mut outer_state, &opad);
+    let hmac_32 =
+        |msg: &[u8; 32]| sha256_finish_32(outer_state, 64, &sha256_finish_32(inner_state, 64, msg));
+
+    // a 32 byte key is exactly one PBKDF2 block
+    let first_inner = sha256(&[&ipad, salt, &1u32.to_be_bytes()]);
+    let mut u = sha256_finish_32(outer_state, 64, &first_inner);
+    let mut out = u;
+    for _ in 1..rounds {
+        u = hmac_32(&u);
+        for (o, x) in out.iter_mut().zip(u) {
+            *o ^= x;
+        }
+    }
+    out
+}
@@ -0,0 +1,68 @@
+//! Just enough CSV (RFC 4180 quoting) for reading spreadsheet exports and writing the list out.
+
+/// splits `input` into records. Quoted fields may contain the delimiter, newlines and doubled
+/// quotes. The delimiter is guessed from the first line, since spreadsheets in many locales
+/// export with `;` or tabs rather than commas.
+pub fn parse(input: &str) -> Vec<Vec<String>> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let delimiter = guess_delimiter(input.lines().next().unwrap_or(""));
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                c => field.push(c),
+            }
+        } else {
+            match c {
+                '"' => in_quotes = true,
+                c if c == delimiter => record.push(std::mem::take(&mut field)),
+                '\r' => {}
+                '\n' => end_record(&mut records, &mut record, &mut field),
+                c => field.push(c),
+            }
+        }
+    }
+    end_record(&mut records, &mut record, &mut field);
+    records
+}
+
+fn end_record(records: &mut Vec<Vec<String>>, record: &mut Vec<String>, field: &mut String) {
+    record.push(std::mem::take(field));
+    let record = std::mem::take(record);
+    // blank lines don't make records
+    if record.len() > 1 || !record[0].is_empty() {
+        records.push(record);
+    }
+}
+
+/// one record, the fields quoted where they need to be
+pub fn record(fields: &[String]) -> String {
+    let quoted: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+    quoted.join(",")
+}
+
+fn guess_delimiter(header: &str) -> char {
+    [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|&d| header.matches(d).count())
+        .filter(|&d| header.contains(d))
+        .unwrap_or(',')
+}
@@ -0,0 +1,194 @@
+//! Fields defined by the user in the config's `custom_fields`, for recording whatever the built-in
+//! fields don't cover, e.g.
+//!
+//! ```json
+//! "custom_fields": [
+//!     {"name": "Puck screen", "type": "bool"},
+//!     {"name": "RPM", "type": "number"}
+//! ]
+//! ```
+//!
+//! They're listed after the built-in fields. Values are stored on the entry by field name, so
+//! renaming a field in the config hides what was recorded under the old name rather than losing
+//! it.
+
+use std::io;
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{Event, KeyCode, KeyEvent},
+    layout::Rect,
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{Paragraph, Widget},
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use crate::{
+    json::{invalid, Value},
+    number_error, App, Entry, InputMode,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CustomKind {
+    Number,
+    Text,
+    /// yes or no, toggled in place
+    Bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct CustomField {
+    pub name: String,
+    pub kind: CustomKind,
+}
+
+impl CustomField {
+    /// reads one item of the config's `custom_fields`
+    pub fn from_config(value: &Value) -> io::Result<Self> {
+        let name = value
+            .get("name")
+            .and_then(Value::as_str)
+            .filter(|n| !n.trim().is_empty())
+            .ok_or_else(|| invalid("custom fields need a `name`"))?;
+        let kind = match value.get("type").and_then(Value::as_str) {
+            Some("number") => CustomKind::Number,
+            Some("text") => CustomKind::Text,
+            Some("bool") => CustomKind::Bool,
+            _ => {
+                return Err(invalid(format!(
+                    "custom field `{name}` should have a `type` of \"number\", \"text\" or \"bool\""
+                )));
+            }
+        };
+        Ok(Self {
+            name: name.trim().to_string(),
+            kind,
+        })
+    }
+
+    /// the entry's value for this field, as shown in the edit view
+    pub(crate) fn value_text(&self, entry: &Entry) -> String {
+        match entry.custom.get(&self.name) {
+            None => String::from("-"),
+            Some(Value::Number(n)) => format!("{n}"),
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Bool(b)) => String::from(if *b { "Yes" } else { "No" }),
+            Some(_) => String::from("?"),
+        }
+    }
+}
+
+impl App {
+    fn custom_field(&self, i: usize) -> Option<&CustomField> {
+        self.config.custom_fields.get(i)
+    }
+
+    /// toggles a yes/no field, or starts typing into the others
+    pub(crate) fn open_custom(&mut self, i: usize) {
+        let Some(field) = self.custom_field(i).cloned() else {
+            return;
+        };
+        let Some(entry) = self.edited_entry_mut() else {
+            return;
+        };
+        let current = entry.custom.get(&field.name);
+        match field.kind {
+            CustomKind::Bool => {
+                let yes = !matches!(current, Some(Value::Bool(true)));
+                entry.custom.insert(field.name, Value::Bool(yes));
+                self.field_saved();
+            }
+            CustomKind::Number | CustomKind::Text => {
+                let text = match current {
+                    Some(Value::Number(n)) => format!("{n}"),
+                    Some(Value::String(s)) => s.clone(),
+                    _ => String::new(),
+                };
+                self.state.edit.input = Input::new(text);
+                self.state.edit.input_mode = InputMode::Editing;
+            }
+        }
+    }
+
+    /// what's wrong with what's typed into a number field, while something is
+    pub(crate) fn custom_error(&self, i: usize) -> Option<&'static str> {
+        self.custom_field(i)
+            .filter(|f| f.kind == CustomKind::Number)
+            .and_then(|_| number_error(self.state.edit.input.value().trim()))
+    }
+
+    pub(crate) fn handle_key_events_custom(&mut self, i: usize, key_event: KeyEvent) {
+        match key_event.code {
+            // not saved until it's a number, the field says what's wrong meanwhile
+            KeyCode::Enter if self.custom_error(i).is_some() => {}
+            KeyCode::Enter => self.save_input(),
+            KeyCode::Esc => self.state.edit.input_mode = InputMode::Normal,
+            _ => {
+                _ = self.state.edit.input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    /// stores what was typed; an empty value unsets the field
+    pub(crate) fn save_custom(&mut self, i: usize) {
+        let Some(field) = self.custom_field(i).cloned() else {
+            return;
+        };
+        let text = self.state.edit.input.value().trim().to_string();
+        let value = match field.kind {
+            _ if text.is_empty() => None,
+            CustomKind::Number => match text.parse::<f64>() {
+                Ok(n) if n.is_finite() => Some(Value::Number(n)),
+                _ => return self.warn(format!("Invalid number `{text}`")),
+            },
+            _ => Some(Value::String(text)),
+        };
+        let Some(entry) = self.edited_entry_mut() else {
+            return;
+        };
+        match value {
+            Some(value) => _ = entry.custom.insert(field.name, value),
+            None => _ = entry.custom.remove(&field.name),
+        }
+        self.field_saved();
+    }
+
+    /// the label and value of a custom field, for the edit view
+    pub(crate) fn custom_line(&self, i: usize, entry: &Entry) -> Line<'static> {
+        match self.custom_field(i) {
+            Some(field) => Line::from(format!("  {}: {}", field.name, field.value_text(entry))),
+            None => Line::from(""),
+        }
+    }
+
+    /// the line being typed into a custom field, in red and saying what's wrong while a number
+    /// field doesn't hold a number
+    pub(crate) fn custom_input_line(&self, i: usize) -> Line<'static> {
+        let mut line = self.input_line(self.custom_label(i));
+        if let Some(error) = self.custom_error(i) {
+            for span in line.spans.iter_mut().skip(1) {
+                span.style = span.style.fg(Color::Red);
+            }
+            line.push_span(format!(" {error}").red());
+        }
+        line
+    }
+
+    /// the label to show while typing into a custom field
+    pub(crate) fn custom_label(&self, i: usize) -> &str {
+        self.custom_field(i).map_or("", |f| f.name.as_str())
+    }
+
+    pub(crate) fn render_footer_custom(&self, area: Rect, buf: &mut Buffer) {
+        let controls = Line::from(vec![
+            " Controls:".into(),
+            " Save ".into(),
+            "<Enter>".fg(self.theme.accent).bold(),
+            " | Cancel ".into(),
+            "<Esc> ".fg(self.theme.accent).bold(),
+        ]);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
@@ -0,0 +1,148 @@
+//! The home screen shown at startup: how many shots were pulled today, the coffee being brewed and
+//! how it's doing, the last shot's parameters, and the shots of the last couple of weeks as a
+//! sparkline.
+
+use chrono::{Days, Local, NaiveDate};
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Margin, Rect},
+    style::Stylize,
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Sparkline, Widget},
+};
+
+use crate::{
+    fields::Field,
+    freshness::{self, DAY_FMT},
+    keymap::DASHBOARD_KEYS,
+    App, Entry, Phase,
+};
+
+/// how many days the sparkline goes back, today included
+const ACTIVITY_DAYS: u64 = 14;
+
+impl App {
+    fn shots_on(&self, day: NaiveDate) -> usize {
+        self.data
+            .entries
+            .iter()
+            .filter(|e| e.dt_taken.date_naive() == day)
+            .count()
+    }
+
+    /// "Kenya (12 days off roast, 120 g left, empty around 2026-10-22)" for the coffee of the last
+    /// shot
+    fn current_coffee_line(&self, last: &Entry) -> Line<'static> {
+        let mut spans: Vec<Span> = vec![
+            "  Coffee: ".into(),
+            self.coffee_name(last).to_string().bold(),
+        ];
+        let today = Local::now().date_naive();
+        let coffee = self.data.coffees.iter().find(|c| c.uuid == last.coffee_id);
+        let mut extras = Vec::new();
+        if let Some(days) = coffee.and_then(|c| c.days_off_roast(today)) {
+            extras.push(freshness::roast_age_span(days));
+        }
+        if let Some(stock) = coffee.and_then(|c| self.stock_span(c)) {
+            extras.push(stock);
+        }
+        if let Some(day) = coffee
+            .filter(|c| c.bag_size_g.is_some() && c.remaining_g > 0.0)
+            .and_then(|c| self.bag_empty_day(c))
+        {
+            extras.push(format!("empty around {}", day.format(DAY_FMT)).into());
+        }
+        for (i, extra) in extras.into_iter().enumerate() {
+            spans.push(if i == 0 { " (" } else { ", " }.into());
+            spans.push(extra);
+        }
+        if spans.len() > 2 {
+            spans.push(")".into());
+        }
+        Line::from(spans)
+    }
+
+    fn last_shot_line(&self, last: &Entry) -> Line<'static> {
+        let mut parts = vec![
+            last.method.label().to_string(),
+            format!("{} in", self.weight(last.dose)),
+            format!("{} out", self.weight(last.output)),
+        ];
+        if last.dose > 0.0 {
+            parts.push(format!("{:.1} / 1", last.output / last.dose));
+        }
+        parts.push(format!("{:.0}{}", last.duration, Field::Duration.unit()));
+        Line::from(format!(
+            "  Last shot: {}, {}",
+            self.date_text(last.dt_taken),
+            parts.join(", ")
+        ))
+    }
+
+    pub(crate) fn render_dashboard(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title(self.title())
+            .border_set(border::ROUNDED);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        let today = Local::now().date_naive();
+        let shots = self.shots_on(today);
+        let mut lines = vec![Line::from(vec![
+            "  Today: ".into(),
+            format!("{shots} {}", if shots == 1 { "shot" } else { "shots" }).bold(),
+        ])];
+        match self.data.entries.iter().max_by_key(|e| e.dt_taken) {
+            Some(last) => {
+                lines.push(self.current_coffee_line(last));
+                lines.push(self.last_shot_line(last));
+            }
+            None => lines.push(Line::from(
+                "  Nothing logged yet: press a to add the first shot.",
+            )),
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("  Shots over the last {ACTIVITY_DAYS} days")).bold());
+        let [text_area, activity_area] = Layout::vertical([
+            Constraint::Length(lines.len() as u16),
+            Constraint::Length(3),
+        ])
+        .areas(inner);
+        Paragraph::new(lines).render(text_area, buf);
+        // oldest first, so today is on the right
+        let activity: Vec<u64> = (0..ACTIVITY_DAYS)
+            .rev()
+            .map(|back| {
+                today
+                    .checked_sub_days(Days::new(back))
+                    .map_or(0, |day| self.shots_on(day) as u64)
+            })
+            .collect();
+        let [activity_area] = Layout::horizontal([Constraint::Length(ACTIVITY_DAYS as u16)])
+            .areas(activity_area.inner(Margin::new(2, 0)));
+        Sparkline::default()
+            .data(&activity)
+            // so a day with a single shot doesn't fill the whole height
+            .max(activity.iter().copied().max().unwrap_or(0).max(3))
+            .fg(self.theme.accent)
+            .render(activity_area, buf);
+    }
+
+    pub(crate) fn handle_key_events_dashboard(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('q') => self.quit(),
+            KeyCode::Char('a') => self.new_entry(),
+            KeyCode::Char('w') => self.open_wizard(),
+            KeyCode::Char('.') => self.open_quick_log(),
+            KeyCode::Enter => self.phase = Phase::ListView,
+            _ => {}
+        }
+    }
+
+    pub(crate) fn render_footer_dashboard(&self, area: Rect, buf: &mut Buffer) {
+        let controls = self.controls(DASHBOARD_KEYS, area.width);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
@@ -0,0 +1,106 @@
+//! Editing a date and time one component at a time: h/l pick the year, month, day, hour or
+//! minute and j/k step it down or up. Steps carry over like a clock would, so going up from
+//! 23:59 lands on the next day, and a month step on the 31st clamps to the end of a shorter month.
+
+use chrono::{DateTime, Days, Local, Months, NaiveDateTime, TimeDelta, TimeZone};
+use ratatui::{
+    style::{Color, Stylize},
+    text::{Line, Span},
+};
+
+use crate::DATE_FMT;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum Part {
+    #[default]
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+}
+
+const PARTS: [Part; 5] = [Part::Year, Part::Month, Part::Day, Part::Hour, Part::Minute];
+
+#[derive(Debug, Default)]
+pub struct DatePicker {
+    value: NaiveDateTime,
+    part: Part,
+}
+
+impl DatePicker {
+    pub fn new(dt: DateTime<Local>) -> Self {
+        Self {
+            value: dt.naive_local(),
+            part: Part::default(),
+        }
+    }
+
+    /// moves the highlight to the next component, or the previous one
+    pub fn select(&mut self, next: bool) {
+        let i = PARTS.iter().position(|p| *p == self.part).unwrap_or(0);
+        let i = if next {
+            (i + 1).min(PARTS.len() - 1)
+        } else {
+            i.saturating_sub(1)
+        };
+        self.part = PARTS[i];
+    }
+
+    /// steps the highlighted component up or down by one, leaving the value alone if that would
+    /// take it out of chrono's range
+    pub fn step(&mut self, up: bool) {
+        let v = self.value;
+        let stepped = match (self.part, up) {
+            (Part::Year, true) => v.checked_add_months(Months::new(12)),
+            (Part::Year, false) => v.checked_sub_months(Months::new(12)),
+            (Part::Month, true) => v.checked_add_months(Months::new(1)),
+            (Part::Month, false) => v.checked_sub_months(Months::new(1)),
+            (Part::Day, true) => v.checked_add_days(Days::new(1)),
+            (Part::Day, false) => v.checked_sub_days(Days::new(1)),
+            (Part::Hour, true) => v.checked_add_signed(TimeDelta::hours(1)),
+            (Part::Hour, false) => v.checked_sub_signed(TimeDelta::hours(1)),
+            (Part::Minute, true) => v.checked_add_signed(TimeDelta::minutes(1)),
+            (Part::Minute, false) => v.checked_sub_signed(TimeDelta::minutes(1)),
+        };
+        if let Some(stepped) = stepped {
+            self.value = stepped;
+        }
+    }
+
+    /// the picked time, or `None` if it doesn't read back in `DATE_FMT` or doesn't exist in the
+    /// local time zone, e.g. when it falls in the hour skipped by a daylight saving change
+    pub fn value(&self) -> Option<DateTime<Local>> {
+        let text = self.value.format(DATE_FMT).to_string();
+        let naive = NaiveDateTime::parse_from_str(&text, DATE_FMT).ok()?;
+        Local.from_local_datetime(&naive).single()
+    }
+
+    /// `label` followed by the date in `DATE_FMT` layout, with the highlighted component picked
+    /// out in `accent`
+    pub fn line(&self, label: &str, accent: Color) -> Line<'static> {
+        let v = self.value;
+        let parts = [
+            (Part::Year, v.format("%Y").to_string(), "/"),
+            (Part::Month, v.format("%m").to_string(), "/"),
+            (Part::Day, v.format("%d").to_string(), " "),
+            (Part::Hour, v.format("%H").to_string(), ":"),
+            (Part::Minute, v.format("%M").to_string(), ""),
+        ];
+        let mut spans = vec![Span::from(label.to_string())];
+        for (part, text, sep) in parts {
+            spans.push(if part == self.part {
+                text.black().bg(accent)
+            } else {
+                text.into()
+            });
+            if !sep.is_empty() {
+                spans.push(sep.into());
+            }
+        }
+        if self.value().is_none() {
+            spans.push(" (not a valid local time)".red());
+        }
+        Line::from(spans)
+    }
+}
@@ -0,0 +1,208 @@
+//! Dial-in sessions: the run of shots pulled while dialing in a coffee, usually a fresh bag. A
+//! session starts at one of the coffee's entries and takes in every later entry of that coffee
+//! until it's marked dialed. The session view shows how each shot changed from the one before in
+//! grind, time and ratio, the things adjusted between shots.
+//!
+//! `:dialin` opens the session the selected entry belongs to, or starts one at it.
+
+use chrono::{DateTime, Local};
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, List, ListState, Paragraph, StatefulWidget, Widget},
+};
+use uuid::Uuid;
+
+use crate::{keymap::DIAL_IN_KEYS, targets::deviation, App, Entry, Phase, SELECTED_SYMBOL};
+
+#[derive(Debug, Clone)]
+pub struct DialInSession {
+    pub uuid: Uuid,
+    pub coffee_id: Uuid,
+    /// when the first shot of the session was pulled
+    pub started: DateTime<Local>,
+    /// when the last shot of the session was pulled, once it's marked dialed
+    pub dialed: Option<DateTime<Local>>,
+}
+
+impl DialInSession {
+    fn contains(&self, entry: &Entry) -> bool {
+        entry.coffee_id == self.coffee_id
+            && entry.dt_taken >= self.started
+            && self.dialed.is_none_or(|dialed| entry.dt_taken <= dialed)
+    }
+}
+
+/// the session shown in `Phase::DialIn`
+#[derive(Debug, Default)]
+pub struct DialInView {
+    session: Uuid,
+    list_state: ListState,
+}
+
+/// grind, time and ratio, e.g. "15.0 grind, 28.0 s, 1:2.0"
+fn shot_text(entry: &Entry) -> String {
+    let ratio = if entry.dose > 0.0 {
+        format!("1:{:.1}", entry.output / entry.dose)
+    } else {
+        String::from("1:?")
+    };
+    format!(
+        "{:.1} grind, {:.1} s, {ratio}",
+        entry.grind_setting, entry.duration
+    )
+}
+
+/// how a shot changed from the one before, e.g. "+0.5 grind, +3.1 s, −0.2 ratio"
+fn change_text(prev: &Entry, entry: &Entry) -> String {
+    let mut parts = vec![
+        deviation(entry.grind_setting - prev.grind_setting, "grind"),
+        deviation(entry.duration - prev.duration, "s"),
+    ];
+    if entry.dose > 0.0 && prev.dose > 0.0 {
+        let ratio = entry.output / entry.dose - prev.output / prev.dose;
+        parts.push(deviation(ratio, "ratio"));
+    }
+    parts.join(", ")
+}
+
+impl App {
+    fn dial_in_session(&self) -> Option<&DialInSession> {
+        let uuid = self.state.dial_in.session;
+        self.data.dial_in_sessions.iter().find(|s| s.uuid == uuid)
+    }
+
+    /// the name of the session's coffee, for the title
+    pub(crate) fn dial_in_coffee(&self) -> &str {
+        self.dial_in_session()
+            .and_then(|s| self.data.coffees.iter().find(|c| c.uuid == s.coffee_id))
+            .map_or("Unknown coffee", |c| c.name.as_str())
+    }
+
+    /// the entries of the session, oldest first
+    fn dial_in_entries(&self, session: &DialInSession) -> Vec<&Entry> {
+        let mut entries: Vec<&Entry> = self
+            .data
+            .entries
+            .iter()
+            .filter(|e| session.contains(e))
+            .collect();
+        entries.sort_by_key(|e| e.dt_taken);
+        entries
+    }
+
+    /// handles `:dialin`: opens the session the selected entry is part of, or starts a new one at
+    /// that entry
+    pub(crate) fn open_dial_in(&mut self) {
+        let Some(entry) = self.selected_entry().map(|i| &self.data.entries[i]) else {
+            return;
+        };
+        let (coffee_id, dt_taken) = (entry.coffee_id, entry.dt_taken);
+        let uuid = match self
+            .data
+            .dial_in_sessions
+            .iter()
+            .find(|s| s.contains(entry))
+        {
+            Some(session) => session.uuid,
+            None => {
+                let session = DialInSession {
+                    uuid: Uuid::new_v4(),
+                    coffee_id,
+                    started: dt_taken,
+                    dialed: None,
+                };
+                let uuid = session.uuid;
+                self.data.dial_in_sessions.push(session);
+                self.mark_changed();
+                uuid
+            }
+        };
+        self.state.dial_in = DialInView {
+            session: uuid,
+            list_state: ListState::default().with_selected(Some(0)),
+        };
+        self.phase = Phase::DialIn;
+    }
+
+    /// marks the session dialed as of its latest shot, or opens it back up if it already was
+    fn toggle_dialed(&mut self) {
+        let Some(session) = self.dial_in_session() else {
+            return;
+        };
+        let dialed = match session.dialed {
+            Some(_) => None,
+            None => Some(
+                self.dial_in_entries(session)
+                    .last()
+                    .map_or_else(Local::now, |e| e.dt_taken),
+            ),
+        };
+        let uuid = session.uuid;
+        if let Some(session) = self
+            .data
+            .dial_in_sessions
+            .iter_mut()
+            .find(|s| s.uuid == uuid)
+        {
+            session.dialed = dialed;
+            self.mark_changed();
+        }
+    }
+
+    pub(crate) fn handle_key_events_dial_in(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.phase = Phase::ListView,
+            KeyCode::Char('j') => self.state.dial_in.list_state.select_next(),
+            KeyCode::Char('k') => self.state.dial_in.list_state.select_previous(),
+            KeyCode::Char('m') => self.toggle_dialed(),
+            _ => {}
+        }
+    }
+
+    pub(crate) fn render_dial_in(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(session) = self.dial_in_session() else {
+            return;
+        };
+        let status = match session.dialed {
+            Some(dialed) => format!(" Dialed in {} ", self.date_text(dialed)),
+            None => String::from(" Dialing in "),
+        };
+        let entries = self.dial_in_entries(session);
+        let items: Vec<Line> = entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let mut line = Line::from(format!(
+                    "  {} | {}",
+                    self.date_text(entry.dt_taken),
+                    shot_text(entry)
+                ));
+                if let Some(prev) = i.checked_sub(1).map(|i| entries[i]) {
+                    line.push_span(" | ");
+                    line.push_span(change_text(prev, entry).fg(self.theme.accent));
+                }
+                line
+            })
+            .collect();
+        let block = Block::bordered()
+            .title(self.title())
+            .title_bottom(status)
+            .border_set(border::ROUNDED);
+        let list = List::new(items)
+            .highlight_style(self.theme.selected)
+            .highlight_symbol(SELECTED_SYMBOL)
+            .block(block);
+        StatefulWidget::render(list, area, buf, &mut self.state.dial_in.list_state);
+    }
+
+    pub(crate) fn render_footer_dial_in(&self, area: Rect, buf: &mut Buffer) {
+        let controls = self.controls(DIAL_IN_KEYS, area.width);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
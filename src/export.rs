@@ -0,0 +1,287 @@
+//! Writing the log out in formats meant for reading elsewhere. The Markdown export groups brews
+//! by day with a table per coffee, headed by what's known about the beans, for pasting a recap
+//! into a notes app; the CSV export writes the entries listed, or a range of them, a row each,
+//! with the headers the CSV import reads back; a single shot can be copied as a short plain-text
+//! recipe for sharing.
+
+use std::{fmt::Write as _, fs, io};
+
+use chrono::NaiveDate;
+
+use crate::{
+    clipboard, command::Range, csv, fields::BrewMethod, paths, storage::Dataset, tags::format_tags,
+    App, Coffee, Entry, RoastLevel, DATE_FMT,
+};
+
+const DAY_FMT: &str = "%Y-%m-%d";
+
+/// an inclusive range of days, open on either side
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DateRange {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+impl DateRange {
+    /// reads `FROM..TO`, where either side may be left out, or a single day
+    pub fn parse(s: &str) -> Option<Self> {
+        let day = |s: &str| NaiveDate::parse_from_str(s, DAY_FMT).ok();
+        let side = |s: &str| {
+            if s.is_empty() {
+                Some(None)
+            } else {
+                day(s).map(Some)
+            }
+        };
+        match s.split_once("..") {
+            Some((from, to)) => Some(Self {
+                from: side(from)?,
+                to: side(to)?,
+            }),
+            None => day(s).map(|d| Self {
+                from: Some(d),
+                to: Some(d),
+            }),
+        }
+    }
+
+    pub fn contains(&self, day: NaiveDate) -> bool {
+        self.from.is_none_or(|from| from <= day) && self.to.is_none_or(|to| day <= to)
+    }
+
+    fn describe(&self) -> Option<String> {
+        let day = |d: NaiveDate| d.format(DAY_FMT).to_string();
+        match (self.from, self.to) {
+            (None, None) => None,
+            (Some(from), Some(to)) if from == to => Some(day(from)),
+            (Some(from), Some(to)) => Some(format!("{} to {}", day(from), day(to))),
+            (Some(from), None) => Some(format!("from {}", day(from))),
+            (None, Some(to)) => Some(format!("until {}", day(to))),
+        }
+    }
+}
+
+/// renders the entries taken within `range`, oldest day first
+pub fn markdown(data: &Dataset, range: DateRange) -> String {
+    let mut entries: Vec<&Entry> = data
+        .entries
+        .iter()
+        .filter(|e| range.contains(e.dt_taken.date_naive()))
+        .collect();
+    entries.sort_by_key(|e| e.dt_taken);
+
+    let mut out = String::from("# Brew log");
+    if let Some(range) = range.describe() {
+        _ = write!(out, ", {range}");
+    }
+    out.push('\n');
+    if entries.is_empty() {
+        out.push_str("\nNo brews.\n");
+    }
+    for day in entries.chunk_by(|a, b| a.dt_taken.date_naive() == b.dt_taken.date_naive()) {
+        _ = write!(out, "\n## {}\n", day[0].dt_taken.format("%A, %Y-%m-%d"));
+        // coffees in the order they were first brewed that day
+        let mut coffees = Vec::new();
+        for entry in day {
+            if !coffees.contains(&entry.coffee_id) {
+                coffees.push(entry.coffee_id);
+            }
+        }
+        for coffee_id in coffees {
+            let coffee = data.coffees.iter().find(|c| c.uuid == coffee_id);
+            let name = coffee.map_or("Unknown coffee", |c| c.name.as_str());
+            _ = write!(out, "\n### {}\n\n", cell(name));
+            if let Some(about) = coffee.and_then(|c| describe_coffee(data, c)) {
+                _ = write!(out, "*{about}*\n\n");
+            }
+            out.push_str(
+                "| Time | Grinder | Grind | Dose (g) | Output (g) | Ratio | Duration (s) | Pre-infusion (s) | Pressure (bar) | Favorite | Notes |\n",
+            );
+            out.push_str("|---|---|--:|--:|--:|--:|--:|--:|--:|:-:|---|\n");
+            for entry in day.iter().filter(|e| e.coffee_id == coffee_id) {
+                let grinder = data
+                    .grinders
+                    .iter()
+                    .find(|g| g.uuid == entry.grinder_id)
+                    .map_or("", |g| g.name.as_str());
+                let ratio = if entry.dose > 0.0 {
+                    format!("{:.1}", entry.output / entry.dose)
+                } else {
+                    String::new()
+                };
+                let optional = |n: Option<f64>| n.map_or(String::new(), |n| format!("{n:.1}"));
+                _ = writeln!(
+                    out,
+                    "| {} | {} | {:.1} | {:.1} | {:.1} | {ratio} | {:.1} | {} | {} | {} | {} |",
+                    entry.dt_taken.time().format("%H:%M"),
+                    cell(grinder),
+                    entry.grind_setting,
+                    entry.dose,
+                    entry.output,
+                    entry.duration,
+                    optional(entry.preinfusion_s),
+                    optional(entry.pressure_bar),
+                    if entry.favorite { "★" } else { "" },
+                    cell(&entry.notes),
+                );
+            }
+        }
+    }
+    out
+}
+
+/// `entries` as CSV, a header and then a row each
+pub fn csv_table(data: &Dataset, entries: &[&Entry]) -> String {
+    let header = [
+        "Date brewed",
+        "Coffee",
+        "Grinder",
+        "Grind setting",
+        "Dose",
+        "Output",
+        "Duration",
+        "Favorite",
+        "Rating",
+        "Tags",
+        "Notes",
+    ];
+    let mut out = csv::record(&header.map(String::from));
+    out.push('\n');
+    for entry in entries {
+        let coffee = data.coffees.iter().find(|c| c.uuid == entry.coffee_id);
+        let grinder = data.grinders.iter().find(|g| g.uuid == entry.grinder_id);
+        out.push_str(&csv::record(&[
+            entry.dt_taken.format(DATE_FMT).to_string(),
+            coffee.map_or(String::new(), |c| c.name.clone()),
+            grinder.map_or(String::new(), |g| g.name.clone()),
+            entry.grind_setting.to_string(),
+            entry.dose.to_string(),
+            entry.output.to_string(),
+            entry.duration.to_string(),
+            if entry.favorite { "yes" } else { "" }.to_string(),
+            entry.rating.map_or(String::new(), |r| r.to_string()),
+            format_tags(&entry.tags),
+            entry.notes.clone(),
+        ]));
+        out.push('\n');
+    }
+    out
+}
+
+/// the entry as a short recipe, e.g.
+///
+/// ```text
+/// Ethiopia Guji
+/// Niche Zero @ 14
+/// 18.0 g in, 36.5 g out (1:2.0) in 28 s
+/// 8 s pre-infusion, 9.0 bar
+/// Notes: sweet
+/// ```
+pub fn snippet(data: &Dataset, entry: &Entry) -> String {
+    let coffee = data
+        .coffees
+        .iter()
+        .find(|c| c.uuid == entry.coffee_id)
+        .map_or("Unknown coffee", |c| c.name.as_str());
+    let grinder = data
+        .grinders
+        .iter()
+        .find(|g| g.uuid == entry.grinder_id)
+        .map_or("Unknown grinder", |g| g.name.as_str());
+    let mut out = format!("{coffee}\n{grinder} @ {}\n", entry.grind_setting);
+    _ = write!(out, "{:.1} g in, {:.1} g out", entry.dose, entry.output);
+    if entry.dose > 0.0 {
+        _ = write!(out, " (1:{:.1})", entry.output / entry.dose);
+    }
+    _ = writeln!(out, " in {} s", entry.duration);
+    let espresso: Vec<String> = [
+        entry.preinfusion_s.map(|s| format!("{s} s pre-infusion")),
+        entry.pressure_bar.map(|bar| format!("{bar:.1} bar")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if entry.method == BrewMethod::Espresso && !espresso.is_empty() {
+        _ = writeln!(out, "{}", espresso.join(", "));
+    }
+    if !entry.notes.is_empty() {
+        _ = writeln!(out, "Notes: {}", entry.notes);
+    }
+    out
+}
+
+/// the coffee's roaster, origin, process, varietal and roast level, as far as they're known, e.g.
+/// "Square Mile · Ethiopia, Guji · Washed · Heirloom · Light roast"
+fn describe_coffee(data: &Dataset, coffee: &Coffee) -> Option<String> {
+    let roaster = coffee
+        .roaster_id
+        .and_then(|id| data.roasters.iter().find(|r| r.uuid == id))
+        .map_or("", |r| r.name.as_str());
+    let origin = [coffee.country.as_str(), coffee.region.as_str()]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let roast = match coffee.roast_level {
+        RoastLevel::Unknown => String::new(),
+        level => format!("{} roast", level.label()),
+    };
+    let parts: Vec<&str> = [roaster, &origin, &coffee.process, &coffee.varietal, &roast]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+/// keeps free text from breaking out of a table cell
+fn cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', "<br>")
+}
+
+impl App {
+    /// handles `:export markdown [FROM..TO] <path>`
+    pub(crate) fn export_markdown(&self, args: &[&str]) -> io::Result<()> {
+        let (range, path) = match args.split_first() {
+            Some((first, rest)) if !rest.is_empty() => match DateRange::parse(first) {
+                Some(range) => (range, rest),
+                None => (DateRange::default(), args),
+            },
+            _ => (DateRange::default(), args),
+        };
+        if path.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no file to export to",
+            ));
+        }
+        let path = paths::expand_home(path.join(" ").as_ref());
+        fs::write(path, markdown(&self.data, range))
+    }
+
+    /// handles `:[range]export csv <path>`, writing the listed entries in `range`, or all of them
+    pub(crate) fn export_csv(&mut self, range: Option<Range>, path: &str) -> Result<(), String> {
+        if path.is_empty() {
+            return Err(String::from("no file to export to"));
+        }
+        let entries = self.range_entries(range)?;
+        let entries: Vec<&Entry> = entries.iter().map(|&i| &self.data.entries[i]).collect();
+        let text = csv_table(&self.data, &entries);
+        fs::write(paths::expand_home(path.as_ref()), text).map_err(|e| e.to_string())?;
+        let exported = entries.len();
+        self.info(match exported {
+            1 => String::from("Exported 1 entry"),
+            exported => format!("Exported {exported} entries"),
+        });
+        Ok(())
+    }
+
+    /// copies the entry at `entry_idx` to the clipboard as a recipe snippet
+    pub(crate) fn copy_entry(&self, entry_idx: usize) -> io::Result<()> {
+        let entry = self
+            .data
+            .entries
+            .get(entry_idx)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such entry"))?;
+        clipboard::copy(&snippet(&self.data, entry))
+    }
+}
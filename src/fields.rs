@@ -0,0 +1,357 @@
+//! The fields shown for an entry in the edit view. Which ones there are depends on the brew
+//! method: pour-overs have a bloom and a number of pours, espresso has the basket, pre-infusion,
+//! pressure and the milk drink it went into.
+
+use crate::{catalog::Kind, milk, tasting::MAX_RATING, Entry, FieldType};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum BrewMethod {
+    #[default]
+    Espresso,
+    PourOver,
+    /// French press, AeroPress, cupping…
+    Immersion,
+    Other,
+}
+
+pub const METHODS: [BrewMethod; 4] = [
+    BrewMethod::Espresso,
+    BrewMethod::PourOver,
+    BrewMethod::Immersion,
+    BrewMethod::Other,
+];
+
+impl BrewMethod {
+    pub fn label(self) -> &'static str {
+        match self {
+            BrewMethod::Espresso => "Espresso",
+            BrewMethod::PourOver => "Pour-over",
+            BrewMethod::Immersion => "Immersion",
+            BrewMethod::Other => "Other",
+        }
+    }
+
+    /// how the method is written in the data file
+    pub fn key(self) -> &'static str {
+        match self {
+            BrewMethod::Espresso => "espresso",
+            BrewMethod::PourOver => "pour_over",
+            BrewMethod::Immersion => "immersion",
+            BrewMethod::Other => "other",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        METHODS.into_iter().find(|m| m.key() == key)
+    }
+
+    /// the method after this one, wrapping around
+    pub fn next(self) -> Self {
+        let i = METHODS.iter().position(|m| *m == self).unwrap_or(0);
+        METHODS[(i + 1) % METHODS.len()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Date,
+    Method,
+    Coffee,
+    /// how long after its roast date the coffee was brewed
+    RoastAge,
+    Grinder,
+    Brewer,
+    Water,
+    Basket,
+    GrindSetting,
+    Dose,
+    /// the dose's share of the price of the bag
+    Cost,
+    Output,
+    Ratio,
+    /// total dissolved solids, as measured with a refractometer
+    Tds,
+    /// worked out from the dose, output and TDS
+    ExtractionYield,
+    /// in Celsius, whatever unit it's shown in
+    Temperature,
+    Duration,
+    /// grams out a second, worked out from the output and duration
+    FlowRate,
+    BloomTime,
+    Pours,
+    Preinfusion,
+    Pressure,
+    /// the milk drink an espresso was made into, if any
+    Drink,
+    /// the kind of milk in the drink
+    Milk,
+    MilkAmount,
+    /// how far the entry is from its coffee's targets
+    VsTarget,
+    /// what to change next time to get closer to them
+    Suggestion,
+    /// the overall rating, in stars
+    Rating,
+    Acidity,
+    Sweetness,
+    Body,
+    Bitterness,
+    Aftertaste,
+    /// descriptors picked from the flavor wheel
+    Flavors,
+    Tags,
+    Notes,
+    /// the field at this index in the config's `custom_fields`
+    Custom(usize),
+    /// the photo at this index in the entry's, or a new one past the last
+    Photo(usize),
+}
+
+impl Field {
+    pub fn label(self) -> &'static str {
+        match self {
+            Field::Date => "Date brewed",
+            Field::Method => "Method",
+            Field::Coffee => "Coffee",
+            Field::RoastAge => "Roasted",
+            Field::Grinder => "Grinder",
+            Field::Brewer => "Brewer",
+            Field::Water => "Water",
+            Field::Basket => "Basket",
+            Field::GrindSetting => "Grind setting",
+            Field::Dose => "Dose",
+            Field::Cost => "Cost",
+            Field::Output => "Output",
+            Field::Ratio => "Ratio",
+            Field::Tds => "TDS",
+            Field::ExtractionYield => "Extraction yield",
+            Field::Temperature => "Temperature",
+            Field::Duration => "Duration",
+            Field::FlowRate => "Flow rate",
+            Field::BloomTime => "Bloom time",
+            Field::Pours => "Pours",
+            Field::Preinfusion => "Pre-infusion",
+            Field::Pressure => "Pressure",
+            Field::Drink => "Drink",
+            Field::Milk => "Milk",
+            Field::MilkAmount => "Milk amount",
+            Field::VsTarget => "Vs target",
+            Field::Suggestion => "Next time",
+            Field::Rating => "Rating",
+            Field::Acidity => "Acidity",
+            Field::Sweetness => "Sweetness",
+            Field::Body => "Body",
+            Field::Bitterness => "Bitterness",
+            Field::Aftertaste => "Aftertaste",
+            Field::Flavors => "Flavors",
+            Field::Tags => "Tags",
+            Field::Notes => "Notes",
+            // named in the config
+            Field::Custom(_) => "Custom",
+            Field::Photo(_) => "Photo",
+        }
+    }
+
+    pub fn field_type(self) -> FieldType {
+        match self {
+            Field::Date => FieldType::Date,
+            Field::Method | Field::Rating | Field::Drink | Field::Milk => FieldType::Choice,
+            Field::Coffee => FieldType::Catalog(Kind::Coffee),
+            Field::Grinder => FieldType::Catalog(Kind::Grinder),
+            Field::Brewer => FieldType::Catalog(Kind::Brewer),
+            Field::Water => FieldType::Catalog(Kind::Water),
+            Field::Basket => FieldType::Catalog(Kind::Basket),
+            Field::GrindSetting
+            | Field::Dose
+            | Field::Output
+            | Field::Tds
+            | Field::Temperature
+            | Field::Duration
+            | Field::BloomTime
+            | Field::Pours
+            | Field::Preinfusion
+            | Field::Pressure
+            | Field::MilkAmount => FieldType::ShortString,
+            Field::Acidity
+            | Field::Sweetness
+            | Field::Body
+            | Field::Bitterness
+            | Field::Aftertaste => FieldType::Score,
+            Field::Flavors => FieldType::Flavors,
+            Field::Tags => FieldType::Tags,
+            Field::Notes => FieldType::LongString,
+            Field::Custom(i) => FieldType::Custom(i),
+            Field::Photo(i) => FieldType::Photo(i),
+            Field::Ratio
+            | Field::ExtractionYield
+            | Field::FlowRate
+            | Field::Cost
+            | Field::VsTarget
+            | Field::Suggestion
+            | Field::RoastAge => FieldType::Undefined,
+        }
+    }
+
+    /// the value of a number field, `None` when an optional one is unset
+    pub fn number(self, entry: &Entry) -> Option<f64> {
+        match self {
+            Field::GrindSetting => Some(entry.grind_setting),
+            Field::Dose => Some(entry.dose),
+            Field::Output => Some(entry.output),
+            Field::Tds => entry.tds_percent,
+            Field::Temperature => entry.temperature_c,
+            Field::Duration => Some(entry.duration),
+            Field::BloomTime => Some(entry.bloom_time),
+            Field::Pours => Some(entry.pours),
+            Field::Preinfusion => entry.preinfusion_s,
+            Field::Pressure => entry.pressure_bar,
+            Field::MilkAmount => entry.milk.and_then(|m| m.amount_ml),
+            _ => None,
+        }
+    }
+
+    /// sets a number field, doing nothing for the others
+    pub fn set_number(self, entry: &mut Entry, val: f64) {
+        match self {
+            Field::GrindSetting => entry.grind_setting = val,
+            Field::Dose => entry.dose = val,
+            Field::Output => entry.output = val,
+            Field::Tds => entry.tds_percent = Some(val),
+            Field::Temperature => entry.temperature_c = Some(val),
+            Field::Duration => entry.duration = val,
+            Field::BloomTime => entry.bloom_time = val,
+            Field::Pours => entry.pours = val.round(),
+            Field::Preinfusion => entry.preinfusion_s = Some(val),
+            Field::Pressure => entry.pressure_bar = Some(val),
+            Field::MilkAmount => {
+                if let Some(milk) = &mut entry.milk {
+                    milk.amount_ml = Some(val);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// unsets an optional number field, doing nothing for the others
+    pub fn clear_number(self, entry: &mut Entry) {
+        match self {
+            Field::Tds => entry.tds_percent = None,
+            Field::Temperature => entry.temperature_c = None,
+            Field::Preinfusion => entry.preinfusion_s = None,
+            Field::Pressure => entry.pressure_bar = None,
+            Field::MilkAmount => {
+                if let Some(milk) = &mut entry.milk {
+                    milk.amount_ml = None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// the value of a tasting score field; `None` for other fields too
+    pub fn score(self, entry: &Entry) -> Option<u8> {
+        let taste = &entry.taste;
+        match self {
+            Field::Acidity => taste.acidity,
+            Field::Sweetness => taste.sweetness,
+            Field::Body => taste.body,
+            Field::Bitterness => taste.bitterness,
+            Field::Aftertaste => taste.aftertaste,
+            _ => None,
+        }
+    }
+
+    /// sets a tasting score field, doing nothing for the others
+    pub fn set_score(self, entry: &mut Entry, score: Option<u8>) {
+        let taste = &mut entry.taste;
+        match self {
+            Field::Acidity => taste.acidity = score,
+            Field::Sweetness => taste.sweetness = score,
+            Field::Body => taste.body = score,
+            Field::Bitterness => taste.bitterness = score,
+            Field::Aftertaste => taste.aftertaste = score,
+            _ => {}
+        }
+    }
+
+    /// moves a choice field on to its next option, doing nothing for the others
+    pub fn cycle(self, entry: &mut Entry) {
+        match self {
+            Field::Method => entry.method = entry.method.next(),
+            Field::Rating => {
+                entry.rating = match entry.rating {
+                    None => Some(1),
+                    Some(MAX_RATING) => None,
+                    Some(r) => Some(r + 1),
+                }
+            }
+            Field::Drink => entry.milk = milk::next_drink(entry.milk),
+            Field::Milk => {
+                if let Some(milk) = &mut entry.milk {
+                    milk.milk_type = milk.milk_type.next();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// what follows a number field's value, if anything
+    pub fn unit(self) -> &'static str {
+        match self {
+            Field::Dose | Field::Output => " g",
+            Field::Duration | Field::BloomTime | Field::Preinfusion => " sec",
+            Field::Pressure => " bar",
+            Field::Tds => " %",
+            Field::MilkAmount => " ml",
+            _ => "",
+        }
+    }
+}
+
+impl Entry {
+    /// the percentage of the dose that ended up in the cup, if the TDS was measured
+    pub fn extraction_yield(&self) -> Option<f64> {
+        let tds = self.tds_percent?;
+        (self.dose > 0.0).then(|| self.output * tds / self.dose)
+    }
+
+    /// grams of output a second, if the shot was timed
+    pub fn flow_rate(&self) -> Option<f64> {
+        (self.duration > 0.0).then(|| self.output / self.duration)
+    }
+
+    /// the fields to show for this entry, in order
+    pub fn fields(&self) -> Vec<Field> {
+        use Field::*;
+        let mut fields = vec![Date, Method, Coffee, RoastAge, Grinder, Brewer, Water];
+        if self.method == BrewMethod::Espresso {
+            fields.push(Basket);
+        }
+        fields.extend([
+            GrindSetting,
+            Dose,
+            Cost,
+            Output,
+            Ratio,
+            Tds,
+            ExtractionYield,
+            Temperature,
+        ]);
+        match self.method {
+            BrewMethod::Espresso => {
+                fields.extend([Preinfusion, Duration, FlowRate, Pressure, Drink]);
+                if self.milk.is_some() {
+                    fields.extend([Milk, MilkAmount]);
+                }
+            }
+            BrewMethod::PourOver => fields.extend([BloomTime, Pours, Duration, FlowRate]),
+            BrewMethod::Immersion | BrewMethod::Other => fields.extend([Duration, FlowRate]),
+        }
+        fields.extend([
+            VsTarget, Suggestion, Rating, Acidity, Sweetness, Body, Bitterness, Aftertaste,
+            Flavors, Tags, Notes,
+        ]);
+        fields
+    }
+}
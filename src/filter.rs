@@ -0,0 +1,173 @@
+//! Narrowing the entry list with `:filter`, e.g. `:filter coffee:FSL28 ratio>2.2 favorite`. Each
+//! term has to hold for an entry to be listed:
+//!
+//! - `coffee:`, `grinder:`, `brewer:` and `method:` followed by part of the name, ignoring case;
+//!   quote names with spaces in them, like `coffee:"La Esperanza"`
+//! - `tag:` followed by a tag the entry has
+//! - `dose`, `yield`, `ratio`, `time` or `rating` compared with `<`, `<=`, `=`, `>=` or `>` to a
+//!   number
+//! - `favorite` on its own
+//!
+//! `:filter clear`, or `:filter` on its own, lists every entry again.
+
+use crate::{command::split, tags::parse_tags, App, Entry};
+
+#[derive(Debug, Clone, Copy)]
+enum Name {
+    Coffee,
+    Grinder,
+    Brewer,
+    Method,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Number {
+    Dose,
+    Yield,
+    Ratio,
+    Time,
+    Rating,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+/// longer operators first, so `>=` isn't read as `>` then `=2`
+const OPS: [(&str, Op); 5] = [
+    ("<=", Op::LessOrEqual),
+    (">=", Op::GreaterOrEqual),
+    ("<", Op::Less),
+    (">", Op::Greater),
+    ("=", Op::Equal),
+];
+
+#[derive(Debug, Clone)]
+enum Term {
+    /// part of the name, lowercased
+    Name(Name, String),
+    Tag(String),
+    Compare(Number, Op, f64),
+    Favorite,
+}
+
+#[derive(Debug, Clone)]
+pub struct Filter {
+    terms: Vec<Term>,
+    /// as it was typed, to show in the footer
+    text: String,
+}
+
+impl Filter {
+    /// reads a query, saying what's wrong with it if it can't be read
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let terms = split(query)?
+            .iter()
+            .map(|word| parse_term(word))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            terms,
+            text: query.trim().to_string(),
+        })
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+fn parse_term(word: &str) -> Result<Term, String> {
+    if matches!(word, "favorite" | "fav") {
+        return Ok(Term::Favorite);
+    }
+    if let Some((field, value)) = word.split_once(':') {
+        let name = match field {
+            "coffee" => Name::Coffee,
+            "grinder" => Name::Grinder,
+            "brewer" => Name::Brewer,
+            "method" => Name::Method,
+            "tag" => {
+                return parse_tags(value)
+                    .into_iter()
+                    .next()
+                    .map(Term::Tag)
+                    .ok_or_else(|| String::from("`tag:` needs a tag after it"));
+            }
+            _ => return Err(format!("can't filter by `{field}`")),
+        };
+        return Ok(Term::Name(name, value.to_lowercase()));
+    }
+    let Some((at, op)) = OPS
+        .iter()
+        .find_map(|&(text, op)| word.find(text).map(|at| ((at, at + text.len()), op)))
+    else {
+        return Err(format!("don't know what `{word}` means"));
+    };
+    let number = match &word[..at.0] {
+        "dose" => Number::Dose,
+        "yield" => Number::Yield,
+        "ratio" => Number::Ratio,
+        "time" | "duration" => Number::Time,
+        "rating" => Number::Rating,
+        field => return Err(format!("can't compare `{field}`")),
+    };
+    let value = word[at.1..]
+        .parse()
+        .map_err(|_| format!("`{}` isn't a number", &word[at.1..]))?;
+    Ok(Term::Compare(number, op, value))
+}
+
+impl App {
+    /// whether the entry holds to every term of the filter, if there is one
+    pub(crate) fn filter_match(&self, entry: &Entry) -> bool {
+        let Some(filter) = &self.state.filter else {
+            return true;
+        };
+        filter.terms.iter().all(|term| match term {
+            Term::Name(name, part) => {
+                let text = match name {
+                    Name::Coffee => self.coffee_name(entry),
+                    Name::Grinder => self.grinder_name(entry),
+                    Name::Brewer => self.brewer_name(entry),
+                    Name::Method => entry.method.label(),
+                };
+                text.to_lowercase().contains(part)
+            }
+            Term::Tag(tag) => entry.has_tag(tag),
+            Term::Favorite => entry.favorite,
+            Term::Compare(number, op, value) => {
+                let n = match number {
+                    Number::Dose => Some(entry.dose),
+                    Number::Yield => Some(entry.output),
+                    Number::Ratio => (entry.dose > 0.0).then(|| entry.output / entry.dose),
+                    Number::Time => Some(entry.duration),
+                    Number::Rating => entry.rating.map(f64::from),
+                };
+                n.is_some_and(|n| match op {
+                    Op::Less => n < *value,
+                    Op::LessOrEqual => n <= *value,
+                    // to the precision the list shows
+                    Op::Equal => (n - value).abs() < 0.05,
+                    Op::GreaterOrEqual => n >= *value,
+                    Op::Greater => n > *value,
+                })
+            }
+        })
+    }
+
+    /// handles `:filter <query>`, clearing the filter for `clear` or no query
+    pub(crate) fn filter_command(&mut self, query: &str) -> Result<(), String> {
+        self.state.filter = match query.trim() {
+            "" | "clear" => None,
+            query => Some(Filter::parse(query)?),
+        };
+        self.state.view = None;
+        self.state.entry_list_state.select_first();
+        Ok(())
+    }
+}
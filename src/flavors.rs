@@ -0,0 +1,378 @@
+//! Flavor descriptors from a flavor wheel laid out like the SCA's, picked in a popup that walks
+//! down from the broad categories (fruity) to the specific ones (berry, then blueberry). A
+//! descriptor can be picked at any depth, for when "berry" is as close as the taster can get.
+//!
+//! Descriptors are stored by their path through the wheel, like "Fruity > Berry > Blueberry", and
+//! sit alongside the free-text notes.
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, List, ListState, Paragraph, StatefulWidget, Widget},
+};
+
+use crate::{App, InputMode, SELECTED_SYMBOL};
+
+/// between the levels of a stored descriptor; some names have a slash in them already
+const SEPARATOR: &str = " > ";
+
+#[derive(Debug)]
+struct Flavor {
+    name: &'static str,
+    more: &'static [Flavor],
+}
+
+const fn flavor(name: &'static str, more: &'static [Flavor]) -> Flavor {
+    Flavor { name, more }
+}
+
+const fn leaves<const N: usize>(names: [&'static str; N]) -> [Flavor; N] {
+    let mut flavors = [const { flavor("", &[]) }; N];
+    let mut i = 0;
+    while i < N {
+        flavors[i] = flavor(names[i], &[]);
+        i += 1;
+    }
+    flavors
+}
+
+const WHEEL: &[Flavor] = &[
+    flavor(
+        "Floral",
+        &[
+            flavor("Black tea", &[]),
+            flavor("Floral", &leaves(["Chamomile", "Rose", "Jasmine"])),
+        ],
+    ),
+    flavor(
+        "Fruity",
+        &[
+            flavor(
+                "Berry",
+                &leaves(["Blackberry", "Raspberry", "Blueberry", "Strawberry"]),
+            ),
+            flavor("Dried fruit", &leaves(["Raisin", "Prune"])),
+            flavor(
+                "Other fruit",
+                &leaves([
+                    "Coconut",
+                    "Cherry",
+                    "Pomegranate",
+                    "Pineapple",
+                    "Grape",
+                    "Apple",
+                    "Peach",
+                    "Pear",
+                ]),
+            ),
+            flavor(
+                "Citrus fruit",
+                &leaves(["Grapefruit", "Orange", "Lemon", "Lime"]),
+            ),
+        ],
+    ),
+    flavor(
+        "Sour/Fermented",
+        &[
+            flavor(
+                "Sour",
+                &leaves([
+                    "Sour aromatics",
+                    "Acetic acid",
+                    "Butyric acid",
+                    "Isovaleric acid",
+                    "Citric acid",
+                    "Malic acid",
+                ]),
+            ),
+            flavor(
+                "Alcohol/Fermented",
+                &leaves(["Winey", "Whiskey", "Fermented", "Overripe"]),
+            ),
+        ],
+    ),
+    flavor(
+        "Green/Vegetative",
+        &[
+            flavor("Olive oil", &[]),
+            flavor("Raw", &[]),
+            flavor(
+                "Green/Vegetative",
+                &leaves([
+                    "Under-ripe",
+                    "Peapod",
+                    "Fresh",
+                    "Dark green",
+                    "Vegetative",
+                    "Hay-like",
+                    "Herb-like",
+                ]),
+            ),
+            flavor("Beany", &[]),
+        ],
+    ),
+    flavor(
+        "Other",
+        &[
+            flavor(
+                "Papery/Musty",
+                &leaves([
+                    "Stale",
+                    "Cardboard",
+                    "Papery",
+                    "Woody",
+                    "Moldy/Damp",
+                    "Musty/Dusty",
+                    "Musty/Earthy",
+                    "Animalic",
+                    "Meaty brothy",
+                    "Phenolic",
+                ]),
+            ),
+            flavor(
+                "Chemical",
+                &leaves([
+                    "Bitter",
+                    "Salty",
+                    "Medicinal",
+                    "Petroleum",
+                    "Skunky",
+                    "Rubber",
+                ]),
+            ),
+        ],
+    ),
+    flavor(
+        "Roasted",
+        &[
+            flavor("Pipe tobacco", &[]),
+            flavor("Tobacco", &[]),
+            flavor("Burnt", &leaves(["Acrid", "Ashy", "Smoky", "Brown, roast"])),
+            flavor("Cereal", &leaves(["Grain", "Malt"])),
+        ],
+    ),
+    flavor(
+        "Spices",
+        &[
+            flavor("Pungent", &[]),
+            flavor("Pepper", &[]),
+            flavor(
+                "Brown spice",
+                &leaves(["Anise", "Nutmeg", "Cinnamon", "Clove"]),
+            ),
+        ],
+    ),
+    flavor(
+        "Nutty/Cocoa",
+        &[
+            flavor("Nutty", &leaves(["Peanuts", "Hazelnut", "Almond"])),
+            flavor("Cocoa", &leaves(["Chocolate", "Dark chocolate"])),
+        ],
+    ),
+    flavor(
+        "Sweet",
+        &[
+            flavor(
+                "Brown sugar",
+                &leaves(["Molasses", "Maple syrup", "Caramelized", "Honey"]),
+            ),
+            flavor("Vanilla", &[]),
+            flavor("Vanillin", &[]),
+            flavor("Overall sweet", &[]),
+            flavor("Sweet aromatics", &[]),
+        ],
+    ),
+];
+
+/// the popup's place in the wheel and what's been picked so far
+#[derive(Debug, Default)]
+pub struct FlavorPicker {
+    /// indices of the flavors walked down through to get to the one being shown
+    path: Vec<usize>,
+    list_state: ListState,
+    picked: Vec<String>,
+}
+
+impl FlavorPicker {
+    /// the flavors at the current depth
+    fn level(&self) -> &'static [Flavor] {
+        self.path.iter().fold(WHEEL, |level, &i| level[i].more)
+    }
+
+    /// e.g. "Fruity > Berry", for the current depth
+    fn prefix(&self) -> String {
+        let mut level = WHEEL;
+        let mut names = Vec::new();
+        for &i in &self.path {
+            names.push(level[i].name);
+            level = level[i].more;
+        }
+        names.join(SEPARATOR)
+    }
+
+    /// the stored form of the flavor at `i` of the current depth
+    fn descriptor(&self, i: usize) -> String {
+        let name = self.level()[i].name;
+        match self.prefix().as_str() {
+            "" => name.to_string(),
+            prefix => format!("{prefix}{SEPARATOR}{name}"),
+        }
+    }
+
+    /// the highlighted row, if it's on one of the flavors
+    fn selected(&self) -> Option<usize> {
+        self.list_state
+            .selected()
+            .filter(|&i| i < self.level().len())
+    }
+
+    fn toggle(&mut self) {
+        let Some(i) = self.selected() else {
+            return;
+        };
+        let descriptor = self.descriptor(i);
+        match self.picked.iter().position(|d| *d == descriptor) {
+            Some(at) => _ = self.picked.remove(at),
+            None => self.picked.push(descriptor),
+        }
+    }
+
+    fn descend(&mut self) {
+        if let Some(i) = self.selected()
+            && !self.level()[i].more.is_empty()
+        {
+            self.path.push(i);
+            self.list_state.select(Some(0));
+        }
+    }
+
+    fn ascend(&mut self) {
+        if let Some(i) = self.path.pop() {
+            self.list_state.select(Some(i));
+        }
+    }
+}
+
+/// the most specific part of a descriptor, e.g. "Blueberry" for "Fruity > Berry > Blueberry"
+pub fn flavor_name(descriptor: &str) -> &str {
+    descriptor.rsplit(SEPARATOR).next().unwrap_or(descriptor)
+}
+
+/// e.g. "Blueberry, Dark chocolate"
+pub fn format_flavors(flavors: &[String]) -> String {
+    flavors
+        .iter()
+        .map(|d| flavor_name(d))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl App {
+    /// opens the picker at the top of the wheel with the entry's flavors already picked
+    pub(crate) fn open_flavor_picker(&mut self, flavors: &[String]) {
+        self.state.edit.flavors = FlavorPicker {
+            path: Vec::new(),
+            list_state: ListState::default().with_selected(Some(0)),
+            picked: flavors.to_vec(),
+        };
+        self.state.edit.input_mode = InputMode::Editing;
+    }
+
+    pub(crate) fn handle_key_events_flavors(&mut self, key_event: KeyEvent) {
+        let picker = &mut self.state.edit.flavors;
+        match key_event.code {
+            KeyCode::Enter => self.save_input(),
+            KeyCode::Esc => self.state.edit.input_mode = InputMode::Normal,
+            KeyCode::Char('j') | KeyCode::Down => picker.list_state.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => picker.list_state.select_previous(),
+            KeyCode::Char('l') | KeyCode::Right => picker.descend(),
+            KeyCode::Char('h') | KeyCode::Left | KeyCode::Backspace => picker.ascend(),
+            KeyCode::Char(' ') => picker.toggle(),
+            _ => {}
+        }
+    }
+
+    /// the flavors picked in the popup, to store on the entry
+    pub(crate) fn picked_flavors(&self) -> Vec<String> {
+        self.state.edit.flavors.picked.clone()
+    }
+
+    /// draws the picker as a popup over `area`
+    pub(crate) fn render_flavor_picker(&mut self, area: Rect, buf: &mut Buffer) {
+        let picker = &mut self.state.edit.flavors;
+        let level = picker.level();
+        let items: Vec<Line> = level
+            .iter()
+            .enumerate()
+            .map(|(i, flavor)| {
+                let picked = picker.picked.contains(&picker.descriptor(i));
+                let mut line = Line::from(format!(
+                    "  [{}] {}",
+                    if picked { "x" } else { " " },
+                    flavor.name
+                ));
+                if !flavor.more.is_empty() {
+                    line.push_span(" ›".fg(self.theme.accent));
+                }
+                line
+            })
+            .collect();
+        // picked flavors and borders around the list
+        let height = items.len() as u16 + 4;
+        let [popup] = Layout::vertical([Constraint::Max(height)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup] = Layout::horizontal([Constraint::Max(60)])
+            .flex(Flex::Center)
+            .areas(popup);
+        let title = match picker.prefix().as_str() {
+            "" => String::from(" Flavors "),
+            prefix => format!(" Flavors: {prefix} "),
+        };
+        let block = Block::bordered().title(title).border_set(border::ROUNDED);
+        let inner = block.inner(popup);
+        Clear.render(popup, buf);
+        block.render(popup, buf);
+        let [picked_area, _, list_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+        let picked = match format_flavors(&picker.picked) {
+            text if text.is_empty() => String::from("nothing yet"),
+            text => text,
+        };
+        Paragraph::new(Line::from(vec![
+            "  Picked: ".fg(self.theme.accent).bold(),
+            picked.into(),
+        ]))
+        .render(picked_area, buf);
+        let list = List::new(items)
+            .highlight_style(self.theme.selected)
+            .highlight_symbol(SELECTED_SYMBOL);
+        StatefulWidget::render(list, list_area, buf, &mut picker.list_state);
+    }
+
+    pub(crate) fn render_footer_flavors(&self, area: Rect, buf: &mut Buffer) {
+        let controls = Line::from(vec![
+            " Controls:".into(),
+            " Pick ".into(),
+            "<Space>".fg(self.theme.accent).bold(),
+            " | More specific ".into(),
+            "<l>".fg(self.theme.accent).bold(),
+            " | Broader ".into(),
+            "<h>".fg(self.theme.accent).bold(),
+            " | Save ".into(),
+            "<Enter>".fg(self.theme.accent).bold(),
+            " | Cancel ".into(),
+            "<Esc> ".fg(self.theme.accent).bold(),
+        ]);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
@@ -0,0 +1,93 @@
+//! How long after roasting a coffee was brewed. Beans need a few days to rest after roasting and
+//! go stale after about six weeks, so ages outside that window are colored. Beans don't age in the
+//! freezer, so the days a coffee spent frozen are left out.
+
+use chrono::{Local, NaiveDate};
+use ratatui::{style::Stylize, text::Span};
+
+use crate::{App, Coffee, Entry};
+
+/// how roast and open dates are written, both on screen and in the data file
+pub const DAY_FMT: &str = "%Y-%m-%d";
+
+/// beans brewed fewer days than this after roasting are still resting
+const TOO_FRESH_DAYS: i64 = 5;
+/// and ones brewed more days than this after roasting are past their best
+const STALE_DAYS: i64 = 45;
+
+pub fn parse_day(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s, DAY_FMT).ok()
+}
+
+/// "1 day" or "12 days"
+fn days_text(days: i64) -> String {
+    match days {
+        1 => String::from("1 day"),
+        _ => format!("{days} days"),
+    }
+}
+
+impl Coffee {
+    /// how many of the days before `day` the coffee spent in the freezer
+    pub(crate) fn days_frozen(&self, day: NaiveDate) -> i64 {
+        let Some(frozen) = self.frozen_date else {
+            return 0;
+        };
+        let out = self.thawed_date.map_or(day, |thawed| thawed.min(day));
+        (out - frozen).num_days().max(0)
+    }
+
+    /// days between the coffee's roast date and `day`, not counting the days it was frozen, if
+    /// it has a roast date
+    pub(crate) fn days_off_roast(&self, day: NaiveDate) -> Option<i64> {
+        let roasted = self.roast_date?;
+        Some((day - roasted).num_days() - self.days_frozen(day))
+    }
+
+    /// "frozen 30 days, thawed 4 days ago", or "frozen 30 days so far" while still in the freezer;
+    /// empty if it was never frozen
+    pub(crate) fn freezer_text(&self) -> String {
+        let today = Local::now().date_naive();
+        if self.frozen_date.is_none() {
+            return String::new();
+        }
+        let frozen = days_text(self.days_frozen(today));
+        match self.thawed_date.map(|thawed| (today - thawed).num_days()) {
+            Some(0) => format!("frozen {frozen}, thawed today"),
+            Some(days) => format!("frozen {frozen}, thawed {} ago", days_text(days)),
+            None => format!("frozen {frozen} so far"),
+        }
+    }
+}
+
+/// "12 days off roast", in yellow when too fresh and red when stale
+pub fn roast_age_span(days: i64) -> Span<'static> {
+    colored_age(days, format!("{} off roast", days_text(days)))
+}
+
+/// just "12 days", colored the same, for where it's clear what the days are
+pub fn roast_days_span(days: i64) -> Span<'static> {
+    colored_age(days, days_text(days))
+}
+
+fn colored_age(days: i64, text: String) -> Span<'static> {
+    if days < TOO_FRESH_DAYS {
+        text.yellow()
+    } else if days > STALE_DAYS {
+        text.red()
+    } else {
+        text.into()
+    }
+}
+
+impl App {
+    /// days between the roast date of the entry's coffee and the day it was brewed, not counting
+    /// the days it was frozen, if the coffee has a roast date
+    pub(crate) fn days_off_roast(&self, entry: &Entry) -> Option<i64> {
+        self.data
+            .coffees
+            .iter()
+            .find(|c| c.uuid == entry.coffee_id)?
+            .days_off_roast(entry.dt_taken.date_naive())
+    }
+}
@@ -0,0 +1,196 @@
+//! The help overlay. `?`, wherever a key isn't being typed into something, lists the keys of the
+//! screen it was opened on, the ones that work on every screen and all of the commands, since the
+//! footer may not have room for all of a screen's keys. They come from [`crate::keymap`], and
+//! after the commands come the config's aliases, if there are any. j and k scroll it, and Esc
+//! closes it again.
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Margin, Rect},
+    style::{Color, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::{
+    keymap::{Binding, COMMAND_KEYS, GLOBAL_KEYS, TIMER_KEYS},
+    App, Phase,
+};
+
+#[derive(Debug, Default)]
+pub struct Help {
+    /// how many lines are scrolled past
+    scroll: usize,
+}
+
+const COMMANDS: &[(&str, &str)] = &[
+    (":w", "Save, saying where to"),
+    (":q", "Quit, asking first if something hasn't been saved"),
+    (":wq", "Save and quit"),
+    (":q!", "Quit without saving"),
+    (
+        ":new [field=value ...]",
+        "New entry, filling in e.g. dose=18",
+    ),
+    (":wizard", "New entry, step by step"),
+    (":sync [pull|push]", "Sync with the server"),
+    (":profile [name]", "Switch to another profile"),
+    (":import csv <path>", "Import a CSV file"),
+    (
+        ":import beanconqueror <path>",
+        "Import a Beanconqueror export",
+    ),
+    (
+        ":export markdown [FROM..TO] <path>",
+        "Write the log as Markdown",
+    ),
+    (
+        ":[range]export csv <path>",
+        "Write the listed entries, or a range of them, as CSV",
+    ),
+    (":<n>", "Go to the nth entry listed"),
+    (
+        ":set [option[=value]]",
+        "Units, temperature, dateformat or relative-dates",
+    ),
+    (
+        ":[range]delete",
+        "Delete the selected entry, or a range like 1,5 . $ or %",
+    ),
+    (":report week [file]", "The last week's summary"),
+    (
+        ":filter <terms>|clear",
+        "List only the entries matching the terms",
+    ),
+    (":tag [tag]", "List only the entries with a tag"),
+    (":sort [field] [asc|desc]", "Sort the list"),
+    (":view [save|delete] <name>", "Saved views"),
+    (
+        ":period <preset>|<from> <to>|pick|clear",
+        "Narrow to a period",
+    ),
+    (":chart ratio|grind|flow|off", "The chart under the list"),
+    (":suggest", "What to change for the next shot"),
+    (":brew <pours>|<schedule>", "The pour-over assistant"),
+    (":scale [off]", "Read the smart scale into the new entry"),
+    (":upload", "Upload the entry to Visualizer"),
+    (":coffees, :grinders, ...", "The catalogs"),
+    (":stats", "Stats"),
+    (":dialin", "The dial-in session"),
+    (
+        ":theme [name]",
+        "Switch the colors, dark, light, solarized or high-contrast",
+    ),
+    (
+        ":restore [n]",
+        "Roll back to backup n, 1 being the most recent",
+    ),
+    ("/<text>", "Search the list"),
+];
+
+/// the keys and what the help says they do
+fn described(bindings: &[Binding]) -> Vec<(&'static str, &'static str)> {
+    bindings.iter().map(|b| (b.keys, b.long)).collect()
+}
+
+/// a heading and its keys, the keys lined up in a column
+fn section(lines: &mut Vec<Line<'static>>, accent: Color, heading: &str, keys: &[(&str, &str)]) {
+    let width = keys.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+    lines.push(Line::from(format!(" {heading}")).bold());
+    for (key, what) in keys {
+        lines.push(Line::from(vec![
+            format!("   {key:<width$}").fg(accent).bold(),
+            format!("  {what}").into(),
+        ]));
+    }
+    lines.push(Line::from(""));
+}
+
+impl App {
+    pub(crate) fn open_help(&mut self) {
+        self.state.help = Some(Help::default());
+    }
+
+    /// j and k scroll, g goes back to the top, and Esc, q or ? close the help
+    pub(crate) fn handle_key_events_help(&mut self, key_event: KeyEvent) {
+        let Some(help) = &mut self.state.help else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => help.scroll += 1,
+            KeyCode::Char('k') | KeyCode::Up => help.scroll = help.scroll.saturating_sub(1),
+            KeyCode::PageDown => help.scroll += 10,
+            KeyCode::PageUp => help.scroll = help.scroll.saturating_sub(10),
+            KeyCode::Char('g') | KeyCode::Home => help.scroll = 0,
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => self.state.help = None,
+            _ => {}
+        }
+    }
+
+    /// draws the help over all of `area`
+    pub(crate) fn render_help(&mut self, area: Rect, buf: &mut Buffer) {
+        if self.state.help.is_none() {
+            return;
+        }
+        let (name, keys) = self.phase_keys();
+        let mut lines = vec![Line::from("")];
+        section(&mut lines, self.theme.accent, name, &described(keys));
+        if matches!(self.phase, Phase::EditEntry(_) | Phase::NewEntry) {
+            let timer = described(TIMER_KEYS);
+            section(&mut lines, self.theme.accent, "Shot timer", &timer);
+        }
+        section(
+            &mut lines,
+            self.theme.accent,
+            "Everywhere",
+            &described(GLOBAL_KEYS),
+        );
+        section(&mut lines, self.theme.accent, "Commands", COMMANDS);
+        if !self.config.aliases.is_empty() {
+            let described: Vec<(String, String)> = self
+                .config
+                .aliases
+                .iter()
+                .map(|(alias, command)| (format!(":{alias}"), format!(":{command}")))
+                .collect();
+            let aliases: Vec<(&str, &str)> = described
+                .iter()
+                .map(|(alias, command)| (alias.as_str(), command.as_str()))
+                .collect();
+            section(&mut lines, self.theme.accent, "Aliases", &aliases);
+        }
+        let typing = described(COMMAND_KEYS);
+        section(&mut lines, self.theme.accent, "Typing a command", &typing);
+        let popup = area.inner(Margin::new(2, 1));
+        let height = popup.height.saturating_sub(2) as usize;
+        let Some(help) = &mut self.state.help else {
+            return;
+        };
+        // not past the end, so scrolling back up answers straight away
+        help.scroll = help.scroll.min(lines.len().saturating_sub(height));
+        let block = Block::bordered()
+            .title(" Help ")
+            .border_set(border::ROUNDED);
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .scroll((help.scroll as u16, 0))
+            .block(block)
+            .render(popup, buf);
+    }
+
+    pub(crate) fn render_footer_help(&self, area: Rect, buf: &mut Buffer) {
+        let controls = Line::from(vec![
+            " Controls:".into(),
+            " Scroll ".into(),
+            "<j/k>".fg(self.theme.accent).bold(),
+            " | Top ".into(),
+            "<g>".fg(self.theme.accent).bold(),
+            " | Close ".into(),
+            "<Esc> ".fg(self.theme.accent).bold(),
+        ]);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
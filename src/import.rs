@@ -0,0 +1,464 @@
+//! Importing entries from other tools. CSV files get an interactive screen for mapping the file's
+//! columns to entry fields before anything is imported; Beanconqueror exports have a known layout
+//! and are imported straight away (see [`beanconqueror`]).
+
+mod beanconqueror;
+
+use std::{fs, io, path::Path};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, List, ListState, Paragraph, StatefulWidget, Widget},
+};
+use uuid::Uuid;
+
+use crate::{
+    csv, keymap::IMPORT_KEYS, paths, storage::Dataset, App, Coffee, Entry, Grinder, Phase,
+    SELECTED_SYMBOL,
+};
+
+/// entry fields a CSV column can be mapped to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Date,
+    Coffee,
+    Grinder,
+    GrindSetting,
+    Dose,
+    Output,
+    Duration,
+    Favorite,
+    Notes,
+}
+
+const FIELDS: [Field; 9] = [
+    Field::Date,
+    Field::Coffee,
+    Field::Grinder,
+    Field::GrindSetting,
+    Field::Dose,
+    Field::Output,
+    Field::Duration,
+    Field::Favorite,
+    Field::Notes,
+];
+
+/// used for rows that don't name a grinder
+const UNKNOWN_GRINDER: &str = "Unknown grinder";
+
+const DATETIME_FORMATS: [&str; 8] = [
+    "%Y/%m/%d %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%d.%m.%Y %H:%M",
+    "%m/%d/%Y %H:%M:%S",
+    "%m/%d/%Y %H:%M",
+];
+const DATE_FORMATS: [&str; 4] = ["%Y/%m/%d", "%Y-%m-%d", "%d.%m.%Y", "%m/%d/%Y"];
+
+impl Field {
+    fn label(self) -> &'static str {
+        match self {
+            Field::Date => "Date brewed",
+            Field::Coffee => "Coffee",
+            Field::Grinder => "Grinder",
+            Field::GrindSetting => "Grind setting",
+            Field::Dose => "Dose",
+            Field::Output => "Output",
+            Field::Duration => "Duration",
+            Field::Favorite => "Favorite",
+            Field::Notes => "Notes",
+        }
+    }
+
+    /// words in a column header that suggest it holds this field
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Field::Date => &["date", "timestamp", "when", "day"],
+            Field::Coffee => &["coffee", "bean", "beans"],
+            Field::Grinder => &["grinder", "mill"],
+            Field::GrindSetting => &["grind", "setting", "clicks"],
+            Field::Dose => &["dose", "in", "input"],
+            Field::Output => &["output", "yield", "out", "weight"],
+            Field::Duration => &["duration", "time", "seconds", "secs", "sec", "s"],
+            Field::Favorite => &["favorite", "favourite", "fav", "star"],
+            Field::Notes => &["notes", "note", "comments", "comment", "tasting"],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ImportScreen {
+    file_name: String,
+    /// only CSV files need their columns mapped
+    csv: Option<CsvImport>,
+    /// filled in once the import has run
+    report: Option<ImportReport>,
+}
+
+#[derive(Debug)]
+struct CsvImport {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+    /// the column mapped to each of `FIELDS`, if any
+    mapping: [Option<usize>; FIELDS.len()],
+    list_state: ListState,
+}
+
+#[derive(Debug, Default)]
+struct ImportReport {
+    imported: usize,
+    new_coffees: usize,
+    new_grinders: usize,
+    /// which record was skipped and why, e.g. "row 3: no coffee"
+    skipped: Vec<String>,
+}
+
+impl CsvImport {
+    fn open(path: &Path) -> io::Result<Self> {
+        let mut records = csv::parse(&fs::read_to_string(path)?).into_iter();
+        let header = records
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "the CSV file is empty"))?;
+        let mut import = Self {
+            header,
+            rows: records.collect(),
+            mapping: [None; FIELDS.len()],
+            list_state: ListState::default().with_selected(Some(0)),
+        };
+        import.guess_mapping();
+        Ok(import)
+    }
+
+    /// maps each field to the first unused column whose header contains one of its keywords
+    fn guess_mapping(&mut self) {
+        let headers: Vec<Vec<String>> = self
+            .header
+            .iter()
+            .map(|h| {
+                h.to_lowercase()
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|w| !w.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .collect();
+        for (i, field) in FIELDS.iter().enumerate() {
+            self.mapping[i] = (0..headers.len()).find(|col| {
+                !self.mapping.contains(&Some(*col))
+                    && headers[*col]
+                        .iter()
+                        .any(|word| field.keywords().contains(&word.as_str()))
+            });
+        }
+    }
+
+    /// moves the selected field's mapping to the next (or previous) column, passing through "not
+    /// imported" between the last and first columns
+    fn cycle_column(&mut self, forward: bool) {
+        let Some(i) = self.list_state.selected() else {
+            return;
+        };
+        let columns = self.header.len();
+        self.mapping[i] = match (self.mapping[i], forward) {
+            (None, true) => (columns > 0).then_some(0),
+            (None, false) => columns.checked_sub(1),
+            (Some(col), true) => (col + 1 < columns).then_some(col + 1),
+            (Some(col), false) => col.checked_sub(1),
+        };
+    }
+
+    fn cell<'a>(&self, row: &'a [String], field: Field) -> &'a str {
+        let i = FIELDS.iter().position(|f| *f == field).unwrap();
+        self.mapping[i]
+            .and_then(|col| row.get(col))
+            .map_or("", |val| val.trim())
+    }
+
+    fn describe_mapping(&self, i: usize) -> String {
+        match self.mapping[i] {
+            None => String::from("(not imported)"),
+            Some(col) => {
+                let sample = self
+                    .rows
+                    .first()
+                    .and_then(|row| row.get(col))
+                    .map_or("", |val| val.trim());
+                format!("column \"{}\", e.g. \"{sample}\"", self.header[col])
+            }
+        }
+    }
+
+    fn run(&self, data: &mut Dataset) -> ImportReport {
+        let mut report = ImportReport::default();
+        for (i, row) in self.rows.iter().enumerate() {
+            match self.read_row(row) {
+                Ok(parsed) => {
+                    let (coffee_id, new_coffee) = find_or_add_coffee(data, parsed.coffee);
+                    let (grinder_id, new_grinder) = find_or_add_grinder(data, parsed.grinder);
+                    report.new_coffees += usize::from(new_coffee);
+                    report.new_grinders += usize::from(new_grinder);
+                    data.entries.push(Entry {
+                        dt_added: Local::now(),
+                        coffee_id,
+                        grinder_id,
+                        ..parsed.entry
+                    });
+                    report.imported += 1;
+                }
+                Err(reason) => report.skipped.push(format!("row {}: {reason}", i + 1)),
+            }
+        }
+        report
+    }
+
+    fn read_row<'a>(&self, row: &'a [String]) -> Result<ParsedRow<'a>, String> {
+        let date = self.cell(row, Field::Date);
+        let dt_taken = parse_date(date).ok_or_else(|| format!("can't read date \"{date}\""))?;
+        let coffee = self.cell(row, Field::Coffee);
+        if coffee.is_empty() {
+            return Err(String::from("no coffee"));
+        }
+        let grinder = match self.cell(row, Field::Grinder) {
+            "" => UNKNOWN_GRINDER,
+            name => name,
+        };
+        let number = |field: Field| {
+            let val = self.cell(row, field);
+            parse_number(val).ok_or_else(|| format!("{}: \"{val}\" is not a number", field.label()))
+        };
+        Ok(ParsedRow {
+            coffee,
+            grinder,
+            entry: Entry {
+                dt_taken,
+                grind_setting: number(Field::GrindSetting)?,
+                dose: number(Field::Dose)?,
+                output: number(Field::Output)?,
+                duration: number(Field::Duration)?,
+                favorite: parse_bool(self.cell(row, Field::Favorite)),
+                notes: self.cell(row, Field::Notes).to_string(),
+                ..Default::default()
+            },
+        })
+    }
+}
+
+struct ParsedRow<'a> {
+    coffee: &'a str,
+    grinder: &'a str,
+    entry: Entry,
+}
+
+/// returns the id of the coffee called `name` (ignoring case), adding it if there is none, and
+/// whether it was added
+pub(crate) fn find_or_add_coffee(data: &mut Dataset, name: &str) -> (Uuid, bool) {
+    match data
+        .coffees
+        .iter()
+        .find(|c| c.name.eq_ignore_ascii_case(name))
+    {
+        Some(coffee) => (coffee.uuid, false),
+        None => {
+            let coffee = Coffee::new(name.to_string());
+            let uuid = coffee.uuid;
+            data.coffees.push(coffee);
+            (uuid, true)
+        }
+    }
+}
+
+/// like [`find_or_add_coffee`], for grinders
+pub(crate) fn find_or_add_grinder(data: &mut Dataset, name: &str) -> (Uuid, bool) {
+    match data
+        .grinders
+        .iter()
+        .find(|g| g.name.eq_ignore_ascii_case(name))
+    {
+        Some(grinder) => (grinder.uuid, false),
+        None => {
+            let grinder = Grinder::new(name.to_string());
+            let uuid = grinder.uuid;
+            data.grinders.push(grinder);
+            (uuid, true)
+        }
+    }
+}
+
+fn parse_date(s: &str) -> Option<DateTime<Local>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Local));
+    }
+    let naive = DATETIME_FORMATS
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+        .or_else(|| {
+            DATE_FORMATS
+                .iter()
+                .find_map(|fmt| NaiveDate::parse_from_str(s, fmt).ok())
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+    Local.from_local_datetime(&naive).earliest()
+}
+
+/// empty cells count as 0; decimal commas and trailing units ("18,5 g", "27s") are accepted,
+/// numbers too big to hold aren't
+fn parse_number(s: &str) -> Option<f64> {
+    if s.is_empty() {
+        return Some(0.0);
+    }
+    s.trim_end_matches(|c: char| c.is_alphabetic() || c.is_whitespace())
+        .replace(',', ".")
+        .parse()
+        .ok()
+        .filter(|n: &f64| n.is_finite())
+}
+
+fn parse_bool(s: &str) -> bool {
+    matches!(
+        s.to_lowercase().as_str(),
+        "true" | "yes" | "y" | "1" | "x" | "*" | "★"
+    )
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map_or_else(String::new, |name| name.to_string_lossy().into_owned())
+}
+
+impl App {
+    /// opens the column mapping screen for the CSV file at `path`
+    pub(crate) fn start_csv_import(&mut self, path: &str) -> io::Result<()> {
+        let path = paths::expand_home(path.as_ref());
+        self.state.import = Some(ImportScreen {
+            file_name: file_name(&path),
+            csv: Some(CsvImport::open(&path)?),
+            report: None,
+        });
+        self.phase = Phase::Import;
+        Ok(())
+    }
+
+    /// imports a Beanconqueror export and shows what was imported
+    pub(crate) fn import_beanconqueror(&mut self, path: &str) -> io::Result<()> {
+        let path = paths::expand_home(path.as_ref());
+        let report = beanconqueror::import(&path, &mut self.data)?;
+        if report.imported > 0 {
+            self.mark_changed();
+        }
+        self.state.import = Some(ImportScreen {
+            file_name: file_name(&path),
+            csv: None,
+            report: Some(report),
+        });
+        self.phase = Phase::Import;
+        Ok(())
+    }
+
+    pub(crate) fn handle_key_events_import(&mut self, key_event: KeyEvent) {
+        let Some(import) = &mut self.state.import else {
+            return;
+        };
+        let csv = match (&import.report, &mut import.csv) {
+            (None, Some(csv)) => csv,
+            _ => {
+                if matches!(
+                    key_event.code,
+                    KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter
+                ) {
+                    self.state.import = None;
+                    self.phase = Phase::ListView;
+                }
+                return;
+            }
+        };
+        match key_event.code {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.state.import = None;
+                self.phase = Phase::ListView;
+            }
+            KeyCode::Char('j') | KeyCode::Down => csv.list_state.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => csv.list_state.select_previous(),
+            KeyCode::Char('l') | KeyCode::Right => csv.cycle_column(true),
+            KeyCode::Char('h') | KeyCode::Left => csv.cycle_column(false),
+            KeyCode::Enter => {
+                let report = csv.run(&mut self.data);
+                let changed = report.imported > 0;
+                import.report = Some(report);
+                if changed {
+                    self.mark_changed();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub(crate) fn render_import_view(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(import) = &mut self.state.import else {
+            return;
+        };
+        let block = Block::bordered()
+            .title(format!(" Coffee Tracking - Import {} ", import.file_name))
+            .border_set(border::ROUNDED);
+        match (&import.report, &mut import.csv) {
+            (None, Some(csv)) => {
+                let items: Vec<String> = FIELDS
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        format!("  {:<14} <- {}", field.label(), csv.describe_mapping(i))
+                    })
+                    .collect();
+                let list = List::new(items)
+                    .highlight_style(self.theme.selected)
+                    .highlight_symbol(SELECTED_SYMBOL)
+                    .block(block.title_bottom(format!(" {} rows ", csv.rows.len())));
+                StatefulWidget::render(list, area, buf, &mut csv.list_state);
+            }
+            (Some(report), _) => {
+                let mut lines = vec![
+                    Line::from(format!(
+                        "  Imported {} entries, added {} coffees and {} grinders.",
+                        report.imported, report.new_coffees, report.new_grinders
+                    )),
+                    Line::from(format!("  Skipped {} records.", report.skipped.len())),
+                ];
+                lines.extend(
+                    report
+                        .skipped
+                        .iter()
+                        .map(|s| Line::from(format!("    {s}"))),
+                );
+                Paragraph::new(lines).block(block).render(area, buf);
+            }
+            (None, None) => block.render(area, buf),
+        }
+    }
+
+    pub(crate) fn render_footer_import(&self, area: Rect, buf: &mut Buffer) {
+        let mapping = self
+            .state
+            .import
+            .as_ref()
+            .is_some_and(|i| i.report.is_none() && i.csv.is_some());
+        let controls = if mapping {
+            self.controls(IMPORT_KEYS, area.width)
+        } else {
+            Line::from(vec![
+                " Controls:".into(),
+                " Back ".into(),
+                "<q> ".fg(self.theme.accent).bold(),
+            ])
+        };
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
@@ -0,0 +1,173 @@
+//! Beanconqueror export files. The app exports a single JSON document, optionally zipped, holding
+//! everything it knows about; only the `BREWS`, `BEANS` and `MILL` collections are read here.
+//! Beans and mills are referenced from brews by their `config.uuid`.
+
+use std::{fs, io, path::Path, process::Command};
+
+use chrono::{Local, TimeZone};
+
+use crate::{
+    json::{self, invalid, Value},
+    storage::Dataset,
+    Entry,
+};
+
+use super::{find_or_add_coffee, find_or_add_grinder, ImportReport, UNKNOWN_GRINDER};
+
+/// name of the document inside a zipped export
+const ZIPPED_FILE: &str = "Beanconqueror.json";
+
+pub(super) fn import(path: &Path, data: &mut Dataset) -> io::Result<ImportReport> {
+    let doc = json::parse(&read(path)?)?;
+    let brews = collection(&doc, "BREWS")?;
+    if brews.is_empty() {
+        return Err(invalid("no brews in the Beanconqueror export"));
+    }
+    let beans = collection(&doc, "BEANS")?;
+    let mills = collection(&doc, "MILL")?;
+
+    let mut report = ImportReport::default();
+    for (i, brew) in brews.iter().enumerate() {
+        match read_brew(brew, beans, mills) {
+            Ok((coffee, grinder, entry)) => {
+                let (coffee_id, new_coffee) = find_or_add_coffee(data, coffee);
+                let (grinder_id, new_grinder) = find_or_add_grinder(data, grinder);
+                report.new_coffees += usize::from(new_coffee);
+                report.new_grinders += usize::from(new_grinder);
+                data.entries.push(Entry {
+                    coffee_id,
+                    grinder_id,
+                    ..entry
+                });
+                report.imported += 1;
+            }
+            Err(reason) => report.skipped.push(format!("brew {}: {reason}", i + 1)),
+        }
+    }
+    Ok(report)
+}
+
+/// reads the export, unpacking it with `unzip` if it's a zip file
+fn read(path: &Path) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+    if !bytes.starts_with(b"PK") {
+        return String::from_utf8(bytes).map_err(|e| invalid(format!("export file: {e}")));
+    }
+    let output = Command::new("unzip")
+        .arg("-p")
+        .arg(path)
+        .arg(ZIPPED_FILE)
+        .output()
+        .map_err(|e| io::Error::new(e.kind(), format!("could not run unzip: {e}")))?;
+    if !output.status.success() {
+        return Err(invalid(format!("no {ZIPPED_FILE} in {}", path.display())));
+    }
+    String::from_utf8(output.stdout).map_err(|e| invalid(format!("export file: {e}")))
+}
+
+/// a top-level array of the export; missing collections are treated as empty
+fn collection<'a>(doc: &'a Value, key: &str) -> io::Result<&'a [Value]> {
+    match doc.get(key) {
+        None | Some(Value::Null) => Ok(&[]),
+        Some(value) => value
+            .as_array()
+            .map(Vec::as_slice)
+            .ok_or_else(|| invalid(format!("`{key}` should be an array"))),
+    }
+}
+
+fn uuid_of(value: &Value) -> Option<&str> {
+    value.get("config")?.get("uuid")?.as_str()
+}
+
+/// the `name` of the item in `items` that `brew[key]` refers to
+fn name_of<'a>(brew: &Value, key: &str, items: &'a [Value]) -> Option<&'a str> {
+    let uuid = brew.get(key)?.as_str()?;
+    items
+        .iter()
+        .find(|item| uuid_of(item) == Some(uuid))?
+        .get("name")?
+        .as_str()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+}
+
+/// numbers are usually stored as numbers, but older versions wrote some of them as strings;
+/// anything that isn't a finite number is taken as 0
+fn number(brew: &Value, key: &str) -> f64 {
+    match brew.get(key) {
+        Some(Value::Number(n)) => Some(*n),
+        Some(Value::String(s)) => s.trim().replace(',', ".").parse().ok(),
+        _ => None,
+    }
+    .filter(|n: &f64| n.is_finite())
+    .unwrap_or(0.0)
+}
+
+/// returns the coffee and grinder names along with the entry, whose ids still need filling in
+fn read_brew<'a>(
+    brew: &Value,
+    beans: &'a [Value],
+    mills: &'a [Value],
+) -> Result<(&'a str, &'a str, Entry), String> {
+    let timestamp = brew
+        .get("config")
+        .and_then(|config| config.get("unix_timestamp"))
+        .and_then(Value::as_f64)
+        .ok_or("no timestamp")?;
+    let dt_taken = Local
+        .timestamp_opt(timestamp as i64, 0)
+        .single()
+        .ok_or_else(|| format!("invalid timestamp {timestamp}"))?;
+    let coffee = name_of(brew, "bean", beans).ok_or("no bean")?;
+    let grinder = name_of(brew, "mill", mills).unwrap_or(UNKNOWN_GRINDER);
+
+    let mut notes = brew
+        .get("note")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    // grind sizes are free text in Beanconqueror; keep the ones that aren't numbers in the notes
+    let grind_size = brew
+        .get("grind_size")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .trim();
+    let setting = grind_size.replace(',', ".").parse().ok();
+    let grind_setting = match setting.filter(|s: &f64| s.is_finite()) {
+        Some(setting) => setting,
+        None if grind_size.is_empty() => 0.0,
+        None => {
+            if !notes.is_empty() {
+                notes.push_str("; ");
+            }
+            notes.push_str(&format!("Grind: {grind_size}"));
+            0.0
+        }
+    };
+    // the beverage weight is what ends up in the cup, `brew_quantity` is the water used
+    let mut output = number(brew, "brew_beverage_quantity");
+    if output <= 0.0 {
+        output = number(brew, "brew_quantity");
+    }
+
+    Ok((
+        coffee,
+        grinder,
+        Entry {
+            dt_added: dt_taken,
+            dt_taken,
+            grind_setting,
+            duration: number(brew, "brew_time"),
+            dose: number(brew, "grind_weight"),
+            output,
+            favorite: brew
+                .get("favourite")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            notes,
+            ..Default::default()
+        },
+    ))
+}
@@ -0,0 +1,71 @@
+//! How much is left in each bag of coffee. Every entry added takes its dose out of its coffee's
+//! bag, and bags that drop below the `low_stock_g` setting are flagged in the entry list, with a
+//! notification as they do.
+
+use ratatui::{
+    style::Stylize,
+    text::{Line, Span},
+};
+use uuid::Uuid;
+
+use crate::{App, Coffee};
+
+/// used when the config doesn't set `low_stock_g`, about two doses
+const DEFAULT_LOW_STOCK_G: f64 = 36.0;
+
+impl App {
+    /// takes `dose` grams out of the bag of the coffee with id `coffee_id`, if its bag is tracked
+    pub(crate) fn use_beans(&mut self, coffee_id: Uuid, dose: f64) {
+        let threshold = self.low_stock_g();
+        let Some(coffee) = self.data.coffees.iter_mut().find(|c| c.uuid == coffee_id) else {
+            return;
+        };
+        if coffee.bag_size_g.is_none() {
+            return;
+        }
+        let was_low = coffee.remaining_g < threshold;
+        coffee.remaining_g = (coffee.remaining_g - dose).max(0.0);
+        // warn as the bag goes below the threshold, not on every entry after
+        if !was_low && coffee.remaining_g < threshold {
+            let text = format!(
+                "Running low on {}: {:.0} g left",
+                coffee.name, coffee.remaining_g
+            );
+            self.warn(text);
+        }
+    }
+
+    fn low_stock_g(&self) -> f64 {
+        self.config.low_stock_g.unwrap_or(DEFAULT_LOW_STOCK_G)
+    }
+
+    fn low_on(&self, coffee: &Coffee) -> bool {
+        coffee.bag_size_g.is_some() && coffee.remaining_g < self.low_stock_g()
+    }
+
+    /// "120 g left" for a coffee whose bag is tracked, in red when it's running low
+    pub(crate) fn stock_span(&self, coffee: &Coffee) -> Option<Span<'static>> {
+        coffee.bag_size_g?;
+        let text = format!("{:.0} g left", coffee.remaining_g);
+        Some(if self.low_on(coffee) {
+            text.red()
+        } else {
+            text.into()
+        })
+    }
+
+    /// a warning naming the coffees that are running low, if any are
+    pub(crate) fn low_stock_warning(&self) -> Option<Line<'static>> {
+        let low: Vec<String> = self
+            .data
+            .coffees
+            .iter()
+            .filter(|c| self.low_on(c))
+            .map(|c| format!("{} ({:.0} g)", c.name, c.remaining_g))
+            .collect();
+        if low.is_empty() {
+            return None;
+        }
+        Some(Line::from(format!(" Running low: {} ", low.join(", "))).yellow())
+    }
+}
@@ -0,0 +1,320 @@
+//! The keys of each screen, in one place for both the footer and the help overlay, so a new
+//! binding only has to be added here to show up in both. The footer lists a screen's keys in the
+//! order they're given, the most used first, as many as the terminal is wide enough for; when
+//! some don't fit it ends with `More <?>`, since the help lists them all.
+
+use ratatui::{style::Stylize, text::Line};
+
+use crate::{App, Phase};
+
+/// a key, or keys doing related things, and what it does
+pub struct Binding {
+    /// e.g. "j / k"
+    pub keys: &'static str,
+    /// what the footer calls it, a word or two
+    pub short: &'static str,
+    /// what the help says it does
+    pub long: &'static str,
+    /// the footer only shows the key when this says it does something, if it's set
+    pub when: Option<fn(&App) -> bool>,
+}
+
+const fn key(keys: &'static str, short: &'static str, long: &'static str) -> Binding {
+    Binding {
+        keys,
+        short,
+        long,
+        when: None,
+    }
+}
+
+const fn key_when(
+    keys: &'static str,
+    short: &'static str,
+    long: &'static str,
+    when: fn(&App) -> bool,
+) -> Binding {
+    Binding {
+        keys,
+        short,
+        long,
+        when: Some(when),
+    }
+}
+
+pub const DASHBOARD_KEYS: &[Binding] = &[
+    key("a", "New", "New entry"),
+    key("w", "Step by step", "New entry, step by step"),
+    key(".", "Again", "Log the last shot again"),
+    key("Enter", "Entries", "The entry list"),
+    key("q", "Quit", "Quit"),
+];
+
+pub const LIST_KEYS: &[Binding] = &[
+    key(
+        "j / k",
+        "Next/previous",
+        "Next / previous entry, 5j five entries down",
+    ),
+    key("Enter", "Open", "Open the entry"),
+    key("a", "New", "New entry"),
+    key("w", "Step by step", "New entry, step by step"),
+    key("q", "Quit", "Quit"),
+    key("g / G", "First/last", "First / last entry, 12G the twelfth"),
+    key("c / r / b", "Catalogs", "Coffees / grinders / brewers"),
+    key("R", "Repeat", "Brew the selected entry again"),
+    key(".", "Again", "Log the last shot again"),
+    key("f", "Favorite", "Mark as a favorite"),
+    key(
+        "m",
+        "Compare",
+        "Mark to compare, m on a second entry compares the two",
+    ),
+    key("B", "Reference", "Make it the coffee's reference shot"),
+    key(
+        "s / S",
+        "Sort",
+        "Sort by the next field / reverse the order",
+    ),
+    key("v", "Views", "Next saved view"),
+    key("P", "Period", "Pick a period"),
+    key("C", "Chart", "Next chart"),
+    key("/", "Search", "Search"),
+    key_when("n / N", "Matches", "Next / previous match", |app| {
+        app.state.search.is_some()
+    }),
+    key_when("Esc", "Clear search", "Clear the search", |app| {
+        app.state.search.is_some()
+    }),
+    key("d", "Delete", "Delete the entry"),
+    key("u / Ctrl-r", "Undo/redo", "Undo / redo"),
+    key("Ctrl-d / Ctrl-u", "Half page", "Half a page down / up"),
+    key("PgDn / PgUp", "Page", "A page down / up"),
+    key_when(
+        "Ctrl-h / Ctrl-l",
+        "Divider",
+        "Move the divider beside the details, on wide screens",
+        |app| app.split.beside(),
+    ),
+];
+
+pub const ENTRY_KEYS: &[Binding] = &[
+    key("j / k", "Next/previous", "Next / previous field"),
+    key("e", "Edit", "Edit the field"),
+    key("q", "Back", "Back to the list"),
+    key("0-5", "Rate", "Rate the shot"),
+    key("Space", "Timer", "Start / stop the shot timer"),
+    key_when(
+        "=",
+        "Use ratio",
+        "Use the offered output or dose for the target ratio",
+        |app| app.state.ratio_offer.is_some(),
+    ),
+    key_when("o", "Open photo", "Open the photo", App::photo_selected),
+    key(
+        "n",
+        "Notes",
+        "Show the notes, with their Markdown formatted",
+    ),
+    key("y", "Copy", "Copy as a recipe"),
+    key("u / Ctrl-r", "Undo/redo", "Undo / redo"),
+    key("U", "Upload", "Upload to Visualizer"),
+    key("Ctrl-d / Ctrl-u", "Half page", "Half a page down / up"),
+    key("PgDn / PgUp", "Page", "A page down / up"),
+];
+
+pub const NEW_ENTRY_KEYS: &[Binding] = &[
+    key("j / k", "Next/previous", "Next / previous field"),
+    key("e", "Edit", "Edit the field"),
+    key("Enter", "Add", "Add the new entry"),
+    key("q", "Cancel", "Leave without adding the entry"),
+    key("0-5", "Rate", "Rate the shot"),
+    key("Space", "Timer", "Start / stop the shot timer"),
+    key_when(
+        "=",
+        "Use ratio",
+        "Use the offered output or dose for the target ratio",
+        |app| app.state.ratio_offer.is_some(),
+    ),
+    key_when("o", "Open photo", "Open the photo", App::photo_selected),
+    key(
+        "n",
+        "Notes",
+        "Show the notes, with their Markdown formatted",
+    ),
+    key("y", "Copy", "Copy as a recipe"),
+    key("Ctrl-d / Ctrl-u", "Half page", "Half a page down / up"),
+    key("PgDn / PgUp", "Page", "A page down / up"),
+];
+
+/// the timer's own keys, while it runs
+pub const TIMER_KEYS: &[Binding] = &[
+    key("Space", "Stop", "Stop the shot timer"),
+    key("Esc", "Cancel", "Stop without keeping the time"),
+    key_when(
+        "p",
+        "Pre-infusion done",
+        "End the pre-infusion",
+        App::preinfusing,
+    ),
+];
+
+fn coffees(app: &App) -> bool {
+    matches!(app.phase, Phase::EditCoffee)
+}
+
+pub const COMPARE_KEYS: &[Binding] = &[
+    key("j / k", "Next/previous", "Next / previous field"),
+    key(
+        "c",
+        "Changed only",
+        "Show only the fields that differ, or all of them",
+    ),
+    key("q", "Back", "Back to the list"),
+    key("Ctrl-d / Ctrl-u", "Half page", "Half a page down / up"),
+    key("PgDn / PgUp", "Page", "A page down / up"),
+];
+
+pub const CATALOG_KEYS: &[Binding] = &[
+    key("j / k", "Next/previous", "Next / previous"),
+    key("Enter", "Details", "Details"),
+    key("a", "Add", "Add"),
+    key("r", "Rename", "Rename"),
+    key("d", "Delete", "Delete"),
+    key("q", "Back", "Back to the list"),
+    key_when("x", "Archive", "Archive the coffee", coffees),
+    key_when("A", "Archived", "Show / hide the archived coffees", coffees),
+    key_when("o", "Roasters", "Roasters, from the coffees", coffees),
+];
+
+pub const STATS_KEYS: &[Binding] = &[
+    key("j / k", "Next/previous", "Next / previous coffee"),
+    key("Enter", "Shots", "The coffee's history"),
+    key("h", "Shot times", "The coffee's shot times"),
+    key("b", "Shot counts", "Shot counts, g to group by roaster"),
+    key("c", "Ratings", "Correlations"),
+    key("m", "Spending", "Spending"),
+    key("P", "Period", "Pick a period"),
+    key("q", "Back", "Back"),
+];
+
+pub const DIAL_IN_KEYS: &[Binding] = &[
+    key("j / k", "Next/previous", "Next / previous shot"),
+    key("m", "Dialed in", "Mark as dialed in, or reopen"),
+    key("q", "Back", "Back to the list"),
+];
+
+pub const IMPORT_KEYS: &[Binding] = &[
+    key("j / k", "Next/previous", "Next / previous column"),
+    key("h / l", "Change column", "Change the field it's read into"),
+    key("Enter", "Import", "Import"),
+    key("q", "Cancel", "Cancel"),
+];
+
+pub const SYNC_CONFLICT_KEYS: &[Binding] = &[
+    key("m", "Keep mine", "Keep this machine's version"),
+    key("t", "Take theirs", "Take the server's version"),
+    key("q", "Cancel", "Cancel the sync"),
+];
+
+pub const WIZARD_KEYS: &[Binding] = &[
+    key("Enter", "Next", "Next step"),
+    key("Esc", "Back", "Previous step"),
+    key(
+        "Space",
+        "Timer",
+        "Start / stop the shot timer, at the brew step",
+    ),
+];
+
+/// while a command is typed after `:`
+pub const COMMAND_KEYS: &[Binding] = &[
+    key("Tab / Shift-Tab", "Complete", "Next / previous completion"),
+    key(
+        "Up / Down",
+        "History",
+        "Earlier / later commands starting with what's typed",
+    ),
+    key(
+        "Ctrl-r",
+        "Search",
+        "Search back through the commands run before",
+    ),
+    key("Enter", "Run", "Run the command"),
+    key("Esc", "Cancel", "Leave without running it"),
+];
+
+pub const GLOBAL_KEYS: &[Binding] = &[
+    key("Tab / Shift-Tab", "Tabs", "Next / previous tab"),
+    key("1-6", "Tab", "Go to a tab, except in the entry list"),
+    key(":", "Command", "Type a command"),
+    key("?", "Help", "This help"),
+];
+
+/// how a key is shown in the footer, "<j/k>" for "j / k"
+fn footer_keys(keys: &str) -> String {
+    format!("<{}>", keys.replace(" / ", "/"))
+}
+
+impl App {
+    /// the keys of the current screen, and what the help calls it
+    pub(crate) fn phase_keys(&self) -> (&'static str, &'static [Binding]) {
+        match self.phase {
+            Phase::Dashboard => ("Home", DASHBOARD_KEYS),
+            Phase::ListView => ("Entries", LIST_KEYS),
+            Phase::EditEntry(_) => ("Entry", ENTRY_KEYS),
+            Phase::NewEntry => ("New entry", NEW_ENTRY_KEYS),
+            Phase::EditCoffee
+            | Phase::EditGrinder
+            | Phase::EditBrewer
+            | Phase::EditRoaster
+            | Phase::EditWater
+            | Phase::EditBasket => ("Catalog", CATALOG_KEYS),
+            Phase::Stats => ("Stats", STATS_KEYS),
+            Phase::DialIn => ("Dial-in", DIAL_IN_KEYS),
+            Phase::Import => ("Import", IMPORT_KEYS),
+            Phase::SyncConflict => ("Sync conflict", SYNC_CONFLICT_KEYS),
+            Phase::Wizard(_) => ("New entry, step by step", WIZARD_KEYS),
+            Phase::Compare(..) => ("Comparison", COMPARE_KEYS),
+        }
+    }
+
+    /// the footer's line of controls for `bindings`, as many as fit in `width` columns, then
+    /// `More <?>` if some didn't or `Help <?>` if they all did
+    pub(crate) fn controls(&self, bindings: &[Binding], width: u16) -> Line<'static> {
+        let width = usize::from(width);
+        let shown: Vec<&Binding> = bindings
+            .iter()
+            .filter(|b| b.when.is_none_or(|when| when(self)))
+            .collect();
+        let mut line = Line::from(" Controls:");
+        for (i, binding) in shown.iter().enumerate() {
+            let label = format!(" {} ", binding.short);
+            let keys = footer_keys(binding.keys);
+            // the last key needs no room left for `More`
+            let more = if i + 1 == shown.len() {
+                0
+            } else {
+                " | More <?> ".len()
+            };
+            if line.width() + label.len() + keys.len() + more > width {
+                line.push_span(" More ");
+                line.push_span("<?> ".fg(self.theme.accent).bold());
+                return line;
+            }
+            line.push_span(label);
+            line.push_span(keys.fg(self.theme.accent).bold());
+            line.push_span(" |");
+        }
+        let help = " Help <?> ";
+        if line.width() + help.len() <= width {
+            line.push_span(" Help ");
+            line.push_span("<?> ".fg(self.theme.accent).bold());
+        } else {
+            // drop the last separator
+            line.spans.pop();
+            line.push_span(" ");
+        }
+        line
+    }
+}
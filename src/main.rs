@@ -1,27 +1,180 @@
-use std::{io, time::Duration};
+mod aliases;
+mod burrs;
+mod caffeine;
+mod catalog;
+mod charts;
+mod cli;
+mod clipboard;
+mod columns;
+mod command;
+mod command_history;
+mod compare;
+mod complete;
+mod config;
+mod consistency;
+mod consumption;
+mod correlation;
+mod cost;
+mod crypto;
+mod csv;
+mod custom;
+mod dashboard;
+mod date_picker;
+mod dial_in;
+mod export;
+mod fields;
+mod filter;
+mod flavors;
+mod freshness;
+mod help;
+mod import;
+mod inventory;
+mod json;
+mod keymap;
+mod maintenance;
+mod milk;
+mod notes;
+mod paths;
+mod period;
+mod photos;
+mod pour;
+mod presets;
+mod quick_log;
+mod quit;
+mod ranges;
+mod reference;
+mod report;
+mod scale;
+mod scroll;
+mod search;
+mod selector;
+mod session;
+mod settings;
+mod shot_counts;
+mod size;
+mod sort;
+mod split;
+mod stats;
+mod storage;
+mod suggest;
+mod sync;
+mod tabs;
+mod tags;
+mod targets;
+mod tasting;
+mod temperature;
+mod textarea;
+mod theme;
+mod time_histogram;
+mod timer;
+mod toast;
+mod undo;
+mod views;
+mod visualizer;
+mod weight;
+mod wizard;
 
-use chrono::{DateTime, Local};
+use std::{
+    collections::{HashMap, VecDeque},
+    env, io,
+    ops::RangeInclusive,
+    process,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local, NaiveDate};
 // use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
 use ratatui::{
     buffer::Buffer,
-    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
-    layout::{Constraint, Direction, Flex, Layout, Rect},
-    style::{palette::tailwind::SLATE, Color, Modifier, Style, Stylize},
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Direction, Flex, Layout, Margin, Rect},
+    style::{Style, Stylize},
     symbols::border,
     text::Line,
-    widgets::{Block, List, ListState, Paragraph, StatefulWidget, Widget},
+    widgets::{Block, Clear, List, ListState, Paragraph, StatefulWidget, TableState, Widget},
     DefaultTerminal,
 };
 use tui_input::{backend::crossterm::EventHandler, Input};
 use uuid::Uuid;
 
-const DATE_FMT: &'static str = "%Y/%m/%d %H:%M";
-const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
-const SELECTED_SYMBOL: &'static str = "->";
+use crate::{
+    catalog::{CatalogScreen, Kind},
+    charts::{ChartKind, CHART_HEIGHT},
+    cli::Args,
+    command::Command,
+    command_history::CommandHistory,
+    compare::CompareView,
+    complete::Completion,
+    config::Config,
+    date_picker::DatePicker,
+    dial_in::DialInView,
+    fields::{BrewMethod, Field},
+    filter::Filter,
+    flavors::FlavorPicker,
+    help::Help,
+    import::ImportScreen,
+    json::Value,
+    keymap::{ENTRY_KEYS, LIST_KEYS, NEW_ENTRY_KEYS},
+    maintenance::Maintenance,
+    milk::Milk,
+    notes::NotesPreview,
+    period::{Period, RangePicker},
+    pour::BrewAssistant,
+    quick_log::QuickLog,
+    reference::REFERENCE_WIDTH,
+    report::Report,
+    scale::Scale,
+    selector::Selector,
+    size::REFERENCE_MIN_WIDTH,
+    sort::Sort,
+    split::Split,
+    stats::StatsView,
+    storage::{Dataset, Storage},
+    sync::{SyncConflict, SyncStatus},
+    targets::{RatioOffer, Target},
+    tasting::Taste,
+    textarea::TextArea,
+    theme::Theme,
+    timer::{ShotTimer, TIMER_TICK},
+    toast::{Severity, Toast},
+    undo::History,
+    wizard::Step,
+};
+
+const DATE_FMT: &str = "%Y/%m/%d %H:%M";
+const SELECTED_SYMBOL: &str = "->";
+/// below this many columns the selected entry's details go under the list instead of beside it
+const SPLIT_MIN_WIDTH: u16 = 100;
+/// how long the data has to sit unchanged before it is written out automatically
+const AUTOSAVE_DELAY: Duration = Duration::from_secs(1);
+/// autosave's "Saved" only reassures, so it goes sooner than other notifications
+const SAVED_TIMEOUT: Duration = Duration::from_secs(2);
 
 fn main() -> io::Result<()> {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(msg) => {
+            eprint!("{msg}");
+            process::exit(2);
+        }
+    };
+    if args.help {
+        print!("{}", cli::USAGE);
+        return Ok(());
+    }
+    let config = Config::load()?;
+    let options = storage::Options {
+        data_dir: args
+            .data_dir
+            .or(config.data_dir.clone())
+            .unwrap_or_else(paths::data_dir),
+        sqlite: args.sqlite,
+        encrypt: args.encrypt || config.encrypt,
+    };
+    let storage = storage::open(&options, args.profile.as_deref(), cli::read_passphrase)?;
+    let app = App::load(storage, options, args.profile, config)?;
     let terminal = ratatui::init();
-    let app_result = App::default().run(terminal);
+    let app_result = app.run(terminal);
     ratatui::restore();
     app_result
 }
@@ -30,23 +183,101 @@ fn main() -> io::Result<()> {
 pub struct App {
     state: AppState,
     phase: Phase,
-    entries: Vec<Entry>,
-    coffees: Vec<Coffee>,
-    grinders: Vec<Grinder>,
+    data: Dataset,
+    storage: Box<dyn Storage>,
+    storage_options: storage::Options,
+    /// `None` for the default profile
+    profile: Option<String>,
+    config: Config,
+    /// the colors, the config's until `:theme` picks others
+    theme: Theme,
+    /// where the list and the selected entry's details are divided
+    split: Split,
+    command_history: CommandHistory,
+    sync_status: Option<SyncStatus>,
+    /// set by `:profile`, the switch happens in the main loop since it may need the terminal
+    pending_profile: Option<Option<String>>,
+    history: History,
+    /// time of the most recent change that hasn't been written to storage yet
+    last_change: Option<Instant>,
+    /// whether the on-disk data has been copied into the backups yet this session
+    backed_up: bool,
     exit: bool,
+    /// set by `:q!`, quits without writing out what hasn't been yet
+    discard: bool,
 }
 
 #[derive(Debug)]
 pub struct AppState {
-    entry_list_state: ListState,
+    entry_list_state: TableState,
     command: CommandState,
     edit: EditState,
+    /// whether the list view is asking to confirm deleting the selected entry
+    confirm_delete: bool,
+    /// the rows of the list `:delete` is asking to confirm deleting, when it's more than one
+    confirm_range: Option<RangeInclusive<usize>>,
+    /// whether quitting is waiting on what to do with what hasn't been saved
+    confirm_quit: bool,
+    /// the entry being filled in while in `Phase::NewEntry`
+    new_entry: Option<Entry>,
+    import: Option<ImportScreen>,
+    catalog: CatalogScreen,
+    sync_conflict: Option<SyncConflict>,
+    /// when set, only entries with this tag are listed
+    tag_filter: Option<String>,
+    /// the list is in the order the entries were added when unset
+    sort: Option<Sort>,
+    /// what was last searched for with `/`, lowercased
+    search: Option<String>,
+    /// set with `:filter`, narrows the list to the entries it matches
+    filter: Option<Filter>,
+    /// the name of the saved view the list is showing, until its filter or sort is changed
+    view: Option<String>,
+    /// when set, the list and the stats only take in entries from these days
+    period: Option<Period>,
+    /// the calendar for picking the period, while it's open
+    range_picker: Option<RangePicker>,
+    /// shown under the entry list when set
+    chart: Option<ChartKind>,
+    /// what to change for the next shot, shown in the footer until the next key press
+    suggestion: Option<String>,
+    /// the popup of `:report`, while it's open
+    report: Option<Report>,
+    /// the shot timer, while it runs
+    shot_timer: Option<ShotTimer>,
+    /// the pour-over assistant of `:brew`, while it's open
+    brew: Option<BrewAssistant>,
+    /// the popup for logging a repeat shot, while it's open
+    quick_log: Option<QuickLog>,
+    /// the smart scale filling in the new entry, while it's connected
+    scale: Option<Scale>,
+    /// what's wrong with the answer to the wizard's step, until it's changed
+    wizard_error: Option<String>,
+    /// the output or dose that would hit the coffee's target ratio, until the next key press
+    ratio_offer: Option<RatioOffer>,
+    /// the help overlay of `?`, while it's open
+    help: Option<Help>,
+    /// the entry's notes over the whole screen, while they're shown
+    notes_preview: Option<NotesPreview>,
+    /// the notifications in the corner, oldest first, until they're cleared
+    toasts: VecDeque<Toast>,
+    /// how many rows the list on screen has room for, for moving through it a page at a time
+    page_rows: u16,
+    /// the number typed in the entry list before the motion it counts
+    count: Option<usize>,
+    /// the entries marked with `m`, to be compared once there are two
+    compare_marks: Vec<usize>,
+    compare: CompareView,
+    dial_in: DialInView,
+    stats: StatsView,
 }
 
 #[derive(Debug, Default)]
 struct CommandState {
     buffer: String,
     input_mode: InputMode,
+    /// the completions Tab is going through, until something else is typed
+    completion: Option<Completion>,
 }
 
 #[derive(Debug, Default)]
@@ -54,6 +285,12 @@ pub struct EditState {
     list_state: ListState,
     input_mode: InputMode,
     input: Input,
+    date: DatePicker,
+    selector: Selector,
+    notes: TextArea,
+    /// the tasting score being adjusted
+    score: Option<u8>,
+    flavors: FlavorPicker,
 }
 
 #[derive(Debug, Default)]
@@ -64,16 +301,155 @@ enum InputMode {
 }
 
 impl App {
-    /// runs the application's main loop until the user quits
+    /// loads the coffee log from `storage`, starting empty if nothing has been saved yet
+    pub fn load(
+        storage: Box<dyn Storage>,
+        storage_options: storage::Options,
+        profile: Option<String>,
+        config: Config,
+    ) -> io::Result<Self> {
+        let data = storage.load()?;
+        Ok(Self {
+            state: Default::default(),
+            phase: Default::default(),
+            history: History::new(&data),
+            data,
+            storage,
+            storage_options,
+            profile,
+            theme: config.theme,
+            split: Split::load(),
+            command_history: CommandHistory::load(),
+            config,
+            sync_status: None,
+            pending_profile: None,
+            last_change: None,
+            backed_up: false,
+            exit: false,
+            discard: false,
+        })
+    }
+
+    /// runs the application's main loop until the user quits, then writes the log back to disk
     pub fn run(mut self, mut terminal: DefaultTerminal) -> io::Result<()> {
         while !self.exit {
             terminal.draw(|frame| frame.render_widget(&mut self, frame.area()))?;
             self.handle_events()?;
+            self.ring_preinfusion();
+            self.ring_due_pours();
+            self.read_scale();
+            self.autosave();
+            self.expire_toasts();
+            if let Some(profile) = self.pending_profile.take() {
+                // an encrypted profile needs its passphrase typed in outside of the TUI
+                let mut left_tui = false;
+                let switched = self.switch_profile(profile, |new| {
+                    left_tui = true;
+                    ratatui::restore();
+                    cli::read_passphrase(new)
+                });
+                self.report_result(switched, "Switched profile");
+                if left_tui {
+                    terminal = ratatui::init();
+                }
+            }
         }
+        if self.state.sync_conflict.is_some() {
+            // leave the repository as it was before the pull rather than halfway through a merge
+            self.resolve_sync_conflict(None);
+        }
+        if self.discard {
+            return Ok(());
+        }
+        self.save()?;
+        self.commit_data()
+    }
+
+    /// saves the current profile, then loads `profile` in its place
+    fn switch_profile(
+        &mut self,
+        profile: Option<String>,
+        passphrase: impl FnOnce(bool) -> io::Result<String>,
+    ) -> io::Result<()> {
+        self.save()?;
+        let storage = storage::open(&self.storage_options, profile.as_deref(), passphrase)?;
+        self.data = storage.load()?;
+        self.history = History::new(&self.data);
+        self.storage = storage;
+        self.profile = profile;
+        self.backed_up = false;
+        self.phase = Phase::ListView;
+        self.state = AppState::default();
+        Ok(())
+    }
+
+    fn save(&mut self) -> io::Result<()> {
+        // autosave writes often, so only rotate backups on the first write of a session. That
+        // keeps the backups as snapshots of what was on disk at the start of recent sessions.
+        if !self.backed_up {
+            storage::rotate_backups(self.storage.path())?;
+            self.backed_up = true;
+        }
+        self.storage.save(&self.data)?;
+        self.last_change = None;
+        Ok(())
+    }
+
+    /// rolls the data file back to backup `n` (1 is the most recent) and reloads it
+    fn restore(&mut self, n: usize) -> io::Result<()> {
+        storage::restore_backup(self.storage.path(), n)?;
+        self.backed_up = true;
+        self.data = self.storage.load()?;
+        self.history = History::new(&self.data);
+        self.last_change = None;
+        self.phase = Phase::ListView;
+        self.state = AppState::default();
         Ok(())
     }
 
+    /// records that the data was modified, (re)starting the autosave countdown, and makes the
+    /// change something that can be undone
+    fn mark_changed(&mut self) {
+        self.history.record(&self.data);
+        self.last_change = Some(Instant::now());
+    }
+
+    /// writes the data out once it has been left alone for `AUTOSAVE_DELAY`
+    fn autosave(&mut self) {
+        if let Some(changed) = self.last_change
+            && changed.elapsed() >= AUTOSAVE_DELAY
+        {
+            match self.save() {
+                Ok(()) => self.notify(Severity::Info, String::from("Saved"), SAVED_TIMEOUT),
+                Err(e) => {
+                    self.error(format!("Couldn't save: {e}"));
+                    // try again after another delay rather than on every loop iteration
+                    self.last_change = Some(Instant::now());
+                }
+            }
+        }
+    }
+
     fn handle_events(&mut self) -> io::Result<()> {
+        // with an autosave pending, a clock running, a scale connected or a notification to clear,
+        // block until the next thing is due
+        let ticking = self.state.shot_timer.is_some()
+            || self.state.brew.is_some()
+            || self.state.scale.is_some();
+        let due = [
+            self.last_change
+                .map(|changed| AUTOSAVE_DELAY.saturating_sub(changed.elapsed())),
+            ticking.then_some(TIMER_TICK),
+            self.toast_left(),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        if let Some(due) = due
+            && !event::poll(due)?
+        {
+            return Ok(());
+        }
         match event::read()? {
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                 self.handle_key_event(key_event)
@@ -85,13 +461,23 @@ impl App {
 
     fn handle_key_event(&mut self, key_event: KeyEvent) {
         if matches!(self.state.command.input_mode, InputMode::Editing) {
+            if !matches!(key_event.code, KeyCode::Tab | KeyCode::BackTab) {
+                self.state.command.completion = None;
+            }
+            if self.command_history.searching() {
+                self.handle_key_events_history_search(key_event);
+                return;
+            }
             match key_event.code {
-                KeyCode::Char(val) => self.state.command.buffer.push(val),
-                KeyCode::Enter => {
-                    self.handle_command(self.state.command.buffer.clone());
-                    self.state.command.buffer.clear();
-                    self.state.command.input_mode = InputMode::Normal;
+                KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.start_history_search()
                 }
+                KeyCode::Up => self.recall_command(true),
+                KeyCode::Down => self.recall_command(false),
+                KeyCode::Char(val) => self.state.command.buffer.push(val),
+                KeyCode::Tab => self.complete_command(false),
+                KeyCode::BackTab => self.complete_command(true),
+                KeyCode::Enter => self.run_typed_command(self.state.command.buffer.clone()),
                 KeyCode::Backspace => {
                     _ = self.state.command.buffer.pop();
                     self.state.command.input_mode = if self.state.command.buffer.is_empty() {
@@ -107,76 +493,269 @@ impl App {
                 _ => {}
             }
         } else {
+            // a suggestion is only shown until the next key
+            self.state.suggestion = None;
+            // and so is the other side of the target ratio, which `=` takes
+            let ratio_offer = self.state.ratio_offer.take();
             // handle new command input
-            if matches!(key_event.code, KeyCode::Char(':')) {
+            // a ':' typed into a field is part of the text, not the start of a command
+            if matches!(key_event.code, KeyCode::Char(':'))
+                && self.editing_field().is_none()
+                && !self.catalog_typing()
+                && !matches!(self.phase, Phase::Wizard(_))
+            {
                 self.state.command.buffer.push(':');
                 self.state.command.input_mode = InputMode::Editing;
-            } else {
+            } else if self.state.confirm_quit {
+                self.handle_key_events_confirm_quit(key_event);
+            } else if self.state.help.is_some() {
+                self.handle_key_events_help(key_event);
+            } else if key_event.code == KeyCode::Char('?')
+                && self.editing_field().is_none()
+                && !self.catalog_typing()
+                && !matches!(self.phase, Phase::Wizard(_))
+            {
+                self.open_help();
+            } else if key_event.code == KeyCode::Char('=')
+                && let Some(offer) = ratio_offer
+            {
+                self.accept_ratio_offer(offer);
+            } else if self.state.report.is_some() {
+                self.handle_key_events_report(key_event);
+            } else if self.state.quick_log.is_some() {
+                self.handle_key_events_quick_log(key_event);
+            } else if !self.handle_key_events_tabs(key_event) {
                 // commands aren't being entered, pass key events on to phase-specific handling
                 match self.phase {
+                    Phase::Dashboard => self.handle_key_events_dashboard(key_event),
                     Phase::ListView => self.handle_key_events_listview(key_event),
-                    Phase::EditEntry(idx) => self.handle_key_events_editentry(idx, key_event),
-                    _ => {}
+                    Phase::EditEntry(_) | Phase::NewEntry => {
+                        self.handle_key_events_editentry(key_event)
+                    }
+                    Phase::Import => self.handle_key_events_import(key_event),
+                    Phase::SyncConflict => self.handle_key_events_sync_conflict(key_event),
+                    Phase::EditCoffee => self.handle_key_events_catalog(Kind::Coffee, key_event),
+                    Phase::EditGrinder => self.handle_key_events_catalog(Kind::Grinder, key_event),
+                    Phase::EditBrewer => self.handle_key_events_catalog(Kind::Brewer, key_event),
+                    Phase::EditRoaster => self.handle_key_events_catalog(Kind::Roaster, key_event),
+                    Phase::EditWater => self.handle_key_events_catalog(Kind::Water, key_event),
+                    Phase::EditBasket => self.handle_key_events_catalog(Kind::Basket, key_event),
+                    Phase::Stats => self.handle_key_events_stats(key_event),
+                    Phase::DialIn => self.handle_key_events_dial_in(key_event),
+                    Phase::Wizard(step) => self.handle_key_events_wizard(step, key_event),
+                    Phase::Compare(..) => self.handle_key_events_compare(key_event),
                 }
             }
         }
     }
 
-    fn handle_key_events_editentry(&mut self, entry_idx: usize, key_event: KeyEvent) {
+    fn handle_key_events_editentry(&mut self, key_event: KeyEvent) {
+        if self.state.shot_timer.is_some() {
+            self.handle_key_events_timer(key_event);
+            return;
+        }
+        if self.state.brew.is_some() {
+            self.handle_key_events_brew(key_event);
+            return;
+        }
+        if self.state.notes_preview.is_some() {
+            self.handle_key_events_notes_preview(key_event);
+            return;
+        }
+        if matches!(self.state.edit.input_mode, InputMode::Normal) && self.page_fields(key_event) {
+            return;
+        }
         match self.state.edit.input_mode {
             InputMode::Normal => match key_event.code {
-                KeyCode::Char('q') => self.phase = Phase::ListView,
+                KeyCode::Char('q') => {
+                    self.state.new_entry = None;
+                    self.phase = Phase::ListView;
+                }
                 KeyCode::Char('j') => self.state.edit.list_state.select_next(),
                 KeyCode::Char('k') => self.state.edit.list_state.select_previous(),
+                KeyCode::Enter if matches!(self.phase, Phase::NewEntry) => self.add_new_entry(),
+                KeyCode::Char('y') => {
+                    if let Phase::EditEntry(entry_idx) = self.phase {
+                        let copied = self.copy_entry(entry_idx);
+                        self.report_result(copied, "Copied the recipe");
+                    }
+                }
+                KeyCode::Char('U') if matches!(self.phase, Phase::EditEntry(_)) => self.upload(),
+                // the form of a new entry isn't part of the data yet, so there's nothing to undo
+                KeyCode::Char('u') if matches!(self.phase, Phase::EditEntry(_)) => self.undo(),
+                KeyCode::Char('r')
+                    if matches!(self.phase, Phase::EditEntry(_))
+                        && key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    self.redo()
+                }
+                KeyCode::Char('o') => {
+                    let opened = self.view_selected_photo();
+                    self.report_result(opened, "");
+                }
+                KeyCode::Char(' ') => self.toggle_timer(),
+                KeyCode::Char('n') => self.open_notes_preview(),
+                KeyCode::Char('0') => self.rate(None),
+                KeyCode::Char(c @ '1'..='5') => self.rate(c.to_digit(10).map(|d| d as u8)),
                 KeyCode::Char('e') => {
-                    let field_idx = self.state.edit.list_state.selected().unwrap();
-                    match Entry::field_type(field_idx) {
-                        FieldType::Date => todo!(),
-                        FieldType::CoffeeType => todo!(),
-                        FieldType::GrinderType => todo!(),
+                    let Some(field) = self.selected_field() else {
+                        return;
+                    };
+                    match field.field_type() {
+                        FieldType::Date => {
+                            if let Some(entry) = self.edited_entry() {
+                                self.state.edit.date = DatePicker::new(entry.dt_taken);
+                                self.state.edit.input_mode = InputMode::Editing;
+                            }
+                        }
+                        FieldType::Choice => {
+                            if let Some(entry) = self.edited_entry_mut() {
+                                field.cycle(entry);
+                                self.field_saved();
+                            }
+                        }
+                        FieldType::Catalog(kind) => {
+                            self.open_selector(kind);
+                            self.state.edit.input_mode = InputMode::Editing;
+                        }
                         FieldType::ShortString => {
                             self.state.edit.input_mode = InputMode::Editing;
-                            self.state.edit.input =
-                                Input::new(self.field_val_as_string(entry_idx, field_idx));
+                            self.state.edit.input = Input::new(self.field_val_as_string(field));
+                        }
+                        FieldType::LongString => {
+                            if let Some(entry) = self.edited_entry() {
+                                self.state.edit.notes = TextArea::new(&entry.notes);
+                                self.state.edit.input_mode = InputMode::Editing;
+                            }
+                        }
+                        FieldType::Score => {
+                            if let Some(entry) = self.edited_entry() {
+                                self.open_score(field.score(entry));
+                            }
+                        }
+                        FieldType::Tags => {
+                            if let Some(entry) = self.edited_entry() {
+                                self.open_tags(&entry.tags.clone());
+                            }
+                        }
+                        FieldType::Custom(i) => self.open_custom(i),
+                        FieldType::Photo(i) => self.open_photo(i),
+                        FieldType::Flavors => {
+                            if let Some(entry) = self.edited_entry() {
+                                self.open_flavor_picker(&entry.flavors.clone());
+                            }
                         }
-                        FieldType::LongString => todo!(),
                         FieldType::Undefined => {}
                     }
                 }
                 _ => {}
             },
             InputMode::Editing => {
-                if matches!(
-                    Entry::field_type(self.state.edit.list_state.selected().unwrap()),
-                    FieldType::ShortString
-                ) {
-                    match key_event.code {
-                        KeyCode::Enter => {
-                            self.save_input(entry_idx);
+                match self
+                    .selected_field()
+                    .map_or(FieldType::Undefined, Field::field_type)
+                {
+                    FieldType::Date => match key_event.code {
+                        KeyCode::Enter => self.save_input(),
+                        KeyCode::Esc => self.state.edit.input_mode = InputMode::Normal,
+                        KeyCode::Char('h') | KeyCode::Left => self.state.edit.date.select(false),
+                        KeyCode::Char('l') | KeyCode::Right => self.state.edit.date.select(true),
+                        KeyCode::Char('j') | KeyCode::Down => self.state.edit.date.step(false),
+                        KeyCode::Char('k') | KeyCode::Up => self.state.edit.date.step(true),
+                        _ => {}
+                    },
+                    FieldType::Catalog(kind) => self.handle_key_events_selector(kind, key_event),
+                    FieldType::Score => self.handle_key_events_score(key_event),
+                    FieldType::Tags => self.handle_key_events_tags(key_event),
+                    FieldType::Custom(i) => self.handle_key_events_custom(i, key_event),
+                    FieldType::Photo(_) => self.handle_key_events_photo(key_event),
+                    FieldType::Flavors => self.handle_key_events_flavors(key_event),
+                    FieldType::LongString => match key_event.code {
+                        KeyCode::Esc => self.save_input(),
+                        KeyCode::Char('s')
+                            if key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+                        {
+                            self.save_input()
+                        }
+                        _ => self.state.edit.notes.input(key_event),
+                    },
+                    FieldType::ShortString => match key_event.code {
+                        // not saved until it's a number, the field says what's wrong meanwhile
+                        KeyCode::Enter if number_error(self.state.edit.input.value()).is_some() => {
                         }
+                        KeyCode::Enter => self.save_input(),
+                        KeyCode::Esc => self.state.edit.input_mode = InputMode::Normal,
                         _ => {
-                            let oldval = self.state.edit.input.value().to_string().clone();
                             _ = self.state.edit.input.handle_event(&Event::Key(key_event));
-                            if !valid_float(self.state.edit.input.value())
-                                && !self.state.edit.input.value().is_empty()
-                            {
-                                self.state.edit.input = Input::new(oldval);
-                            }
                         }
-                    }
+                    },
+                    _ => {}
                 }
             }
         }
     }
 
     fn handle_key_events_listview(&mut self, key_event: KeyEvent) {
+        if self.state.confirm_delete {
+            match key_event.code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    self.state.confirm_delete = false;
+                    self.delete_selected_entry();
+                }
+                KeyCode::Char('n') | KeyCode::Esc => self.state.confirm_delete = false,
+                _ => {}
+            }
+            return;
+        }
+        if let Some(rows) = self.state.confirm_range.clone() {
+            match key_event.code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    self.state.confirm_range = None;
+                    self.delete_rows(rows);
+                }
+                KeyCode::Char('n') | KeyCode::Esc => self.state.confirm_range = None,
+                _ => {}
+            }
+            return;
+        }
+        if self.state.range_picker.is_some() {
+            self.handle_key_events_range_picker(key_event);
+            return;
+        }
+        if self.move_in_entry_list(key_event) || self.resize_split(key_event) {
+            return;
+        }
         match key_event.code {
-            KeyCode::Char('q') => self.exit(),
-            KeyCode::Char('j') => self.state.entry_list_state.select_next(),
-            KeyCode::Char('k') => self.state.entry_list_state.select_previous(),
-            KeyCode::Char('g') => self.state.entry_list_state.select_first(),
+            KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.redo()
+            }
+            KeyCode::Char('q') => self.quit(),
+            KeyCode::Char('c') => self.open_catalog(Kind::Coffee),
+            KeyCode::Char('r') => self.open_catalog(Kind::Grinder),
+            KeyCode::Char('b') => self.open_catalog(Kind::Brewer),
+            KeyCode::Char('a') => self.new_entry(),
+            KeyCode::Char('w') => self.open_wizard(),
+            KeyCode::Char('/') => {
+                self.state.command.buffer.push('/');
+                self.state.command.input_mode = InputMode::Editing;
+            }
+            KeyCode::Char('n') => self.next_match(true),
+            KeyCode::Char('N') => self.next_match(false),
+            KeyCode::Esc => self.clear_search(),
+            KeyCode::Char('R') => self.repeat_selected_entry(),
+            KeyCode::Char('.') => self.open_quick_log(),
+            KeyCode::Char('f') => self.toggle_favorite(),
+            KeyCode::Char('m') => self.mark_for_compare(),
+            KeyCode::Char('B') => self.toggle_reference(),
+            KeyCode::Char('s') => self.next_sort(),
+            KeyCode::Char('S') => self.reverse_sort(),
+            KeyCode::Char('v') => self.next_view(),
+            KeyCode::Char('P') => self.open_range_picker(),
+            KeyCode::Char('C') => self.next_chart(),
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('d') => self.state.confirm_delete = self.selected_entry().is_some(),
             KeyCode::Enter => {
-                if let Some(i) = self.state.entry_list_state.selected() {
+                if let Some(i) = self.selected_entry() {
                     self.phase = Phase::EditEntry(i);
                 }
             }
@@ -184,67 +763,335 @@ impl App {
         }
     }
 
+    /// runs what was typed after `:` or `/`, leaving the command line
+    fn run_typed_command(&mut self, cmd: String) {
+        self.record_command(&cmd);
+        self.handle_command(cmd);
+        self.state.command.buffer.clear();
+        self.state.command.input_mode = InputMode::Normal;
+    }
+
     fn handle_command(&mut self, cmd: String) {
-        match cmd.as_str() {
-            ":q" => self.exit = true,
+        if let Some(query) = cmd.strip_prefix('/') {
+            self.search(query);
+            return;
+        }
+        let cmd = match aliases::expand(&cmd, &self.config.aliases) {
+            Ok(cmd) => cmd,
+            Err(e) => return self.error(e),
+        };
+        let command = match Command::parse(&cmd) {
+            Ok(Some(command)) => command,
+            Ok(None) => return,
+            Err(e) => return self.error(e),
+        };
+        match command.name {
+            "q" => self.quit(),
+            "q!" => self.discard_and_quit(),
+            "wq" => self.save_and_quit(),
+            "w" => self.write(),
+            "sync" => {
+                let (pull, push) = match command.arg(0) {
+                    Some("pull") => (true, false),
+                    Some("push") => (false, true),
+                    None => (true, true),
+                    Some(_) => return self.error("Sync pull or push, or both with :sync"),
+                };
+                self.sync(pull, push);
+            }
+            "profile" => {
+                let profile = command.arg(0).map(String::from);
+                if profile.as_deref().is_none_or(storage::valid_profile_name) {
+                    self.pending_profile = Some(profile);
+                } else {
+                    self.error(format!(
+                        "Invalid profile name {}",
+                        profile.unwrap_or_default()
+                    ));
+                }
+            }
+            "import" => {
+                let path = command.joined_from(1);
+                let imported = match command.arg(0) {
+                    Some("csv") => self.start_csv_import(&path),
+                    Some("beanconqueror") => self.import_beanconqueror(&path),
+                    _ => return self.error("Import csv or beanconqueror, e.g. :import csv <path>"),
+                };
+                self.report_result(imported, "");
+            }
+            "export" => match command.arg(0) {
+                Some("csv") => {
+                    let exported = self.export_csv(command.range, &command.joined_from(1));
+                    self.report_result(exported, "");
+                }
+                Some("markdown" | "md") if command.range.is_none() => {
+                    let exported = self.export_markdown(&command.args()[1..]);
+                    self.report_result(exported, "Exported");
+                }
+                Some("markdown" | "md") => self.error("Only :export csv takes a range"),
+                _ => self.error("Export csv or markdown, e.g. :export markdown <path>"),
+            },
+            "set" => {
+                let set = self.set_command(command.rest());
+                self.report_result(set, "");
+            }
+            "delete" => {
+                let deleted = self.delete_range(command.range);
+                self.report_result(deleted, "");
+            }
+            "" => {
+                if let Some(range) = command.range {
+                    let moved = self.goto_row(range);
+                    self.report_result(moved, "");
+                }
+            }
+            "report" => {
+                let args = command.args();
+                let reported = self.report_command(&args);
+                // a report written to a file has no popup to show that it's done
+                let done = if args.len() > 1 {
+                    "Wrote the report"
+                } else {
+                    ""
+                };
+                self.report_result(reported, done);
+            }
+            "new" => {
+                let opened = self.new_command(&command.args());
+                self.report_result(opened, "");
+            }
+            "wizard" => self.open_wizard(),
+            "tag" => self.filter_by_tag(command.arg(0)),
+            "sort" => self.sort_command(command.arg(0), command.arg(1)),
+            "view" => self.view_command(command.arg(0), command.arg(1)),
+            "period" => self.period_command(command.arg(0), command.arg(1)),
+            "chart" => self.chart_command(command.arg(0)),
+            "suggest" => self.suggest(),
+            "scale" => {
+                let scale = self.scale_command(command.arg(0));
+                self.report_result(scale, "");
+            }
+            "brew" => {
+                let brew = self.brew_command(command.rest());
+                self.report_result(brew, "");
+            }
+            "filter" => {
+                let filtered = self.filter_command(command.rest());
+                self.report_result(filtered, "");
+            }
+            "coffees" => self.open_catalog(Kind::Coffee),
+            "grinders" => self.open_catalog(Kind::Grinder),
+            "brewers" => self.open_catalog(Kind::Brewer),
+            "roasters" => self.open_catalog(Kind::Roaster),
+            "waters" => self.open_catalog(Kind::Water),
+            "baskets" => self.open_catalog(Kind::Basket),
+            "stats" => self.open_stats(),
+            "dialin" => self.open_dial_in(),
+            "theme" => self.theme_command(command.arg(0)),
+            "upload" => self.upload(),
+            "restore" => match command.arg(0).unwrap_or("1").parse() {
+                Ok(n) => {
+                    let restored = self.restore(n);
+                    self.report_result(restored, &format!("Restored backup {n}"));
+                }
+                Err(_) => self.error("Restore takes the number of a backup, 1 being the latest"),
+            },
+            // the names parse() knows are all above
             _ => {}
         }
     }
 
+    /// saves for `:w`, saying how many entries were written and where
+    fn write(&mut self) {
+        match self.save().and_then(|_| self.commit_data()) {
+            Ok(()) => {
+                let entries = self.data.entries.len();
+                self.info(format!(
+                    "Wrote {entries} {} to {}",
+                    if entries == 1 { "entry" } else { "entries" },
+                    paths::tilde(self.storage.path())
+                ));
+            }
+            Err(e) => self.error(format!("Couldn't save: {e}")),
+        }
+    }
+
+    /// uploads the shot to Visualizer, showing where it went
+    fn upload(&mut self) {
+        match self.upload_to_visualizer() {
+            Ok(url) => self.info(format!("Uploaded to {url}")),
+            Err(e) => self.error(format!("Couldn't upload: {e}")),
+        }
+    }
+
     fn render_main(&mut self, area: Rect, buf: &mut Buffer) {
         match self.phase {
+            Phase::Dashboard => self.render_dashboard(area, buf),
             Phase::ListView => self.render_list_view(area, buf),
-            Phase::EditEntry(i) => self.render_edit_entry_view(i, area, buf),
-            Phase::EditCoffee => todo!(),
-            Phase::EditGrinder => todo!(),
+            Phase::EditEntry(_) | Phase::NewEntry => self.render_edit_entry_view(area, buf),
+            Phase::Import => self.render_import_view(area, buf),
+            Phase::SyncConflict => self.render_sync_conflict(area, buf),
+            Phase::EditCoffee => self.render_catalog_view(Kind::Coffee, area, buf),
+            Phase::EditGrinder => self.render_catalog_view(Kind::Grinder, area, buf),
+            Phase::EditBrewer => self.render_catalog_view(Kind::Brewer, area, buf),
+            Phase::EditRoaster => self.render_catalog_view(Kind::Roaster, area, buf),
+            Phase::EditWater => self.render_catalog_view(Kind::Water, area, buf),
+            Phase::EditBasket => self.render_catalog_view(Kind::Basket, area, buf),
+            Phase::Stats => self.render_stats(area, buf),
+            Phase::DialIn => self.render_dial_in(area, buf),
+            Phase::Wizard(step) => self.render_wizard(step, area, buf),
+            Phase::Compare(a, b) => self.render_compare(a, b, area, buf),
+        }
+        if matches!(self.phase, Phase::EditEntry(_) | Phase::NewEntry) {
+            self.render_notes_preview(area, buf);
         }
+        // over whichever screen it was asked for on
+        self.render_report(area, buf);
+        self.render_quick_log(area, buf);
+        self.render_help(area, buf);
+        self.render_confirm_quit(area, buf);
     }
 
-    fn render_edit_entry_view(&mut self, entry_idx: usize, area: Rect, buf: &mut Buffer) {
+    fn render_edit_entry_view(&mut self, area: Rect, buf: &mut Buffer) {
+        // the comparison with the coffee's reference shot goes on the right, when it has one
+        let area = match self.edited_entry().filter(|entry| {
+            area.width >= REFERENCE_MIN_WIDTH && self.reference_for(entry).is_some()
+        }) {
+            Some(entry) => {
+                let [fields, reference] =
+                    Layout::horizontal([Constraint::Fill(1), Constraint::Length(REFERENCE_WIDTH)])
+                        .areas(area);
+                self.render_reference_comparison(entry, reference, buf);
+                fields
+            }
+            None => area,
+        };
         let block = Block::bordered()
             .title(self.title())
             .border_set(border::ROUNDED);
-        let text = self.format_entry_details(&self.entries[entry_idx]);
+        let Some(entry) = self.edited_entry() else {
+            return;
+        };
+        let text = self.format_entry_details(entry);
+        self.state.page_rows = scroll::visible_rows(area, 0);
         match self.state.edit.input_mode {
             InputMode::Normal => {
+                let total = text.len();
                 let list = List::new(text)
-                    .highlight_style(SELECTED_STYLE)
+                    .highlight_style(self.theme.selected)
                     .highlight_symbol(SELECTED_SYMBOL)
                     .block(block);
                 StatefulWidget::render(list, area, buf, &mut self.state.edit.list_state);
+                let selected = self.state.edit.list_state.selected();
+                self.render_scrollbar(area, buf, total, self.state.page_rows, selected);
+                self.render_timer(area, buf);
+                self.render_brew(area, buf);
             }
             InputMode::Editing => {
-                match Entry::field_type(self.state.edit.list_state.selected().unwrap()) {
-                    FieldType::Date => todo!(),
-                    FieldType::CoffeeType => todo!(),
-                    FieldType::GrinderType => todo!(),
+                match self
+                    .selected_field()
+                    .map_or(FieldType::Undefined, Field::field_type)
+                {
+                    field_type @ (FieldType::Date
+                    | FieldType::Score
+                    | FieldType::Tags
+                    | FieldType::Custom(_)
+                    | FieldType::Photo(_)) => {
+                        let mut lines = text;
+                        let block = match field_type {
+                            FieldType::Custom(i) if self.custom_error(i).is_some() => {
+                                block.border_style(Style::new().red())
+                            }
+                            _ => block,
+                        };
+                        if let Some(i) = self.state.edit.list_state.selected()
+                            && let Some(line) = lines.get_mut(i)
+                        {
+                            *line = match field_type {
+                                FieldType::Date => self
+                                    .state
+                                    .edit
+                                    .date
+                                    .line("  Date brewed: ", self.theme.accent),
+                                FieldType::Tags => self.input_line(Field::Tags.label()),
+                                FieldType::Custom(i) => self.custom_input_line(i),
+                                FieldType::Photo(i) => self.photo_input_line(i),
+                                _ => {
+                                    let label = self.selected_field().map_or("", Field::label);
+                                    Line::from(vec![
+                                        format!("  {label}: ").into(),
+                                        tasting::slider(self.state.edit.score)
+                                            .fg(self.theme.accent),
+                                    ])
+                                }
+                            };
+                        }
+                        let list = List::new(lines)
+                            .highlight_style(self.theme.selected)
+                            .highlight_symbol(SELECTED_SYMBOL)
+                            .block(block);
+                        StatefulWidget::render(list, area, buf, &mut self.state.edit.list_state);
+                    }
+                    FieldType::Catalog(kind) => {
+                        let list = List::new(text)
+                            .highlight_style(self.theme.selected)
+                            .highlight_symbol(SELECTED_SYMBOL)
+                            .block(block);
+                        StatefulWidget::render(list, area, buf, &mut self.state.edit.list_state);
+                        self.render_selector(kind, area, buf);
+                    }
+                    FieldType::Flavors => {
+                        let list = List::new(text)
+                            .highlight_style(self.theme.selected)
+                            .highlight_symbol(SELECTED_SYMBOL)
+                            .block(block);
+                        StatefulWidget::render(list, area, buf, &mut self.state.edit.list_state);
+                        self.render_flavor_picker(area, buf);
+                    }
                     FieldType::ShortString => {
+                        let error = number_error(self.state.edit.input.value());
+                        let block = match error {
+                            Some(_) => block.border_style(Style::new().red()),
+                            None => block,
+                        };
                         let inner_area = block.inner(area);
                         block.render(area, buf);
-                        for row in 0..9 {
+                        // only the rows the list showed before editing started, so the fields
+                        // stay put and none are drawn past the bottom of a short terminal
+                        let offset = self.state.edit.list_state.offset();
+                        let rows = text
+                            .into_iter()
+                            .enumerate()
+                            .skip(offset)
+                            .take(usize::from(inner_area.height));
+                        for (row, line) in rows {
                             let subarea = Rect::new(
                                 inner_area.x + (SELECTED_SYMBOL.len() as u16),
-                                inner_area.y + (row as u16),
-                                inner_area.width,
+                                inner_area.y + ((row - offset) as u16),
+                                inner_area
+                                    .width
+                                    .saturating_sub(SELECTED_SYMBOL.len() as u16),
                                 1,
                             );
                             if row == self.state.edit.list_state.selected().unwrap() {
+                                let line = line.to_string();
                                 // split the string at the :
-                                let parts: Vec<&str> = text[row as usize].split(":").collect();
+                                let parts: Vec<&str> = line.split(":").collect();
                                 let mut label = parts[0].to_string();
                                 label.push_str(": ");
                                 let rhs = parts[1].to_string();
                                 let rhs: Vec<&str> = rhs.trim().split(" ").collect();
                                 let units_exist = rhs.len() == 2;
-                                // need to split this subarea into three parts: label, input box,
-                                // units
+                                // need to split this subarea into four parts: label, input box,
+                                // units and what's wrong with the input
                                 let line_area = if units_exist {
                                     Layout::default()
                                         .direction(Direction::Horizontal)
                                         .constraints(vec![
                                             Constraint::Length(label.len() as u16),
                                             Constraint::Length(7),
-                                            Constraint::Length(rhs[1].len() as u16),
+                                            Constraint::Length(rhs[1].len() as u16 + 1),
+                                            Constraint::Fill(1),
                                         ])
                                         .flex(Flex::Legacy)
                                         .split(subarea)
@@ -255,25 +1102,53 @@ impl App {
                                             Constraint::Length(label.len() as u16),
                                             Constraint::Length(7),
                                             Constraint::Length(1),
+                                            Constraint::Fill(1),
                                         ])
                                         .flex(Flex::Legacy)
                                         .split(subarea)
                                 };
                                 Paragraph::new(label).render(line_area[0], buf);
+                                let input_style = match error {
+                                    Some(_) => self.theme.selected.red(),
+                                    None => self.theme.selected,
+                                };
                                 Paragraph::new(self.state.edit.input.value())
-                                    .style(SELECTED_STYLE)
+                                    .style(input_style)
                                     .render(line_area[1], buf);
                                 if units_exist {
                                     let unit_str = format!(" {}", rhs[1]);
                                     Paragraph::new(unit_str).render(line_area[2], buf);
                                 }
+                                if let Some(error) = error {
+                                    Paragraph::new(format!(" {error}").red())
+                                        .render(line_area[3], buf);
+                                }
                             } else {
-                                Paragraph::new(text[row as usize].as_str()).render(subarea, buf);
+                                Paragraph::new(line).render(subarea, buf);
                             }
                         }
                     }
-                    FieldType::LongString => todo!(),
-                    FieldType::Undefined => {
+                    FieldType::LongString => {
+                        let list = List::new(text)
+                            .highlight_style(self.theme.selected)
+                            .highlight_symbol(SELECTED_SYMBOL)
+                            .block(block);
+                        StatefulWidget::render(list, area, buf, &mut self.state.edit.list_state);
+                        let [popup] = Layout::vertical([Constraint::Max(14)])
+                            .flex(Flex::Center)
+                            .areas(area);
+                        let [popup] = Layout::horizontal([Constraint::Max(72)])
+                            .flex(Flex::Center)
+                            .areas(popup);
+                        let block = Block::bordered()
+                            .title(" Notes ")
+                            .border_set(border::ROUNDED);
+                        let inner = block.inner(popup).inner(Margin::new(1, 0));
+                        Clear.render(popup, buf);
+                        block.render(popup, buf);
+                        self.state.edit.notes.render(inner, buf);
+                    }
+                    FieldType::Choice | FieldType::Undefined => {
                         unreachable!("Should never be able to edit an undefined field type")
                     }
                 }
@@ -281,215 +1156,906 @@ impl App {
         }
     }
 
+    /// the list with the selected entry's details on the right, or under it on a narrow screen
     fn render_list_view(&mut self, area: Rect, buf: &mut Buffer) {
-        let entries_text: Vec<String> = self
-            .entries
-            .iter()
-            .map(|e| self.format_entry_item(e))
-            .collect();
-        let block = Block::bordered()
+        let (list_area, details_area) = self.split.areas(area);
+        let (list_area, chart_area) = if self.state.chart.is_some() {
+            let [list, chart] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(CHART_HEIGHT)])
+                    .areas(list_area);
+            (list, Some(chart))
+        } else {
+            (list_area, None)
+        };
+        let listed = self.listed_entries();
+        let table = self.entry_table(&listed);
+        self.state.page_rows = scroll::visible_rows(list_area, 1);
+        let mut block = Block::bordered()
             .title(self.title())
             .border_set(border::ROUNDED);
-        let list = List::new(entries_text)
-            .highlight_style(SELECTED_STYLE)
-            .highlight_symbol(SELECTED_SYMBOL)
-            .block(block);
-        StatefulWidget::render(list, area, buf, &mut self.state.entry_list_state);
+        if let Some(i) = self.state.entry_list_state.selected()
+            && !listed.is_empty()
+        {
+            let position = format!(" {} of {} ", (i + 1).min(listed.len()), listed.len());
+            block = block.title_bottom(Line::from(position).right_aligned());
+        }
+        if !self.state.compare_marks.is_empty() {
+            let marked = " Marked to compare, m on another entry ".fg(self.theme.accent);
+            block = block.title_bottom(marked);
+        }
+        if let Some(warning) = self.low_stock_warning() {
+            block = block.title_bottom(warning);
+        }
+        if let Some(warning) = self.burr_warning() {
+            block = block.title_bottom(warning);
+        }
+        if let Some(warning) = self.maintenance_warning() {
+            block = block.title_bottom(warning);
+        }
+        if let Some(warning) = self.caffeine_warning() {
+            block = block.title_bottom(warning);
+        }
+        StatefulWidget::render(
+            table.block(block),
+            list_area,
+            buf,
+            &mut self.state.entry_list_state,
+        );
+        let selected = self.state.entry_list_state.selected();
+        // below the header row
+        let rows_area = Rect {
+            y: list_area.y + 1,
+            height: list_area.height.saturating_sub(1),
+            ..list_area
+        };
+        self.render_scrollbar(rows_area, buf, listed.len(), self.state.page_rows, selected);
+        // after the list, which keeps the selection within it
+        if let Some(details_area) = details_area {
+            self.render_entry_preview(details_area, buf);
+        }
+        if let Some(chart_area) = chart_area {
+            self.render_chart(chart_area, buf);
+        }
+        if self.state.confirm_delete {
+            self.render_confirm_delete(area, buf);
+        }
+        self.render_confirm_range(area, buf);
+        self.render_range_picker(area, buf);
+    }
+
+    fn render_confirm_delete(&self, area: Rect, buf: &mut Buffer) {
+        let Some(entry) = self.selected_entry().and_then(|i| self.data.entries.get(i)) else {
+            return;
+        };
+        let lines = vec![
+            Line::from(format!(
+                "  {} | {}",
+                self.date_text(entry.dt_taken),
+                self.coffee_name(entry)
+            )),
+            Line::from(""),
+            Line::from(vec![
+                "  Delete this entry? ".into(),
+                "y".fg(self.theme.accent).bold(),
+                "/".into(),
+                "n".fg(self.theme.accent).bold(),
+            ]),
+        ];
+        let [popup] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup] = Layout::horizontal([Constraint::Max(60)])
+            .flex(Flex::Center)
+            .areas(popup);
+        let block = Block::bordered()
+            .title(" Delete entry ")
+            .border_set(border::ROUNDED);
+        Clear.render(popup, buf);
+        Paragraph::new(lines).block(block).render(popup, buf);
     }
 
     fn render_footer(&self, area: Rect, buf: &mut Buffer) {
         match self.phase {
+            _ if self.state.confirm_quit => self.render_footer_confirm_quit(area, buf),
+            _ if self.state.help.is_some() => self.render_footer_help(area, buf),
+            _ if self.state.report.is_some() => self.render_footer_report(area, buf),
+            _ if self.state.quick_log.is_some() => self.render_footer_quick_log(area, buf),
+            Phase::Dashboard => self.render_footer_dashboard(area, buf),
             Phase::ListView => self.render_footer_listview(area, buf),
-            Phase::EditEntry(_) => self.render_footer_editview(area, buf),
-            _ => {}
+            Phase::EditEntry(_) | Phase::NewEntry => match self.editing_field() {
+                _ if self.state.shot_timer.is_some() => self.render_footer_timer(area, buf),
+                _ if self.state.brew.is_some() => self.render_footer_brew(area, buf),
+                _ if self.state.notes_preview.is_some() => {
+                    self.render_footer_notes_preview(area, buf)
+                }
+                Some(FieldType::Date) => self.render_footer_date_picker(area, buf),
+                Some(FieldType::Catalog(_)) => self.render_footer_selector(area, buf),
+                Some(FieldType::LongString) => self.render_footer_notes(area, buf),
+                Some(FieldType::ShortString) => self.render_footer_number(area, buf),
+                Some(FieldType::Score) => self.render_footer_score(area, buf),
+                Some(FieldType::Tags) => self.render_footer_tags(area, buf),
+                Some(FieldType::Custom(_)) => self.render_footer_custom(area, buf),
+                Some(FieldType::Photo(_)) => self.render_footer_photo(area, buf),
+                Some(FieldType::Flavors) => self.render_footer_flavors(area, buf),
+                _ if matches!(self.phase, Phase::NewEntry) => {
+                    self.render_footer_new_entry(area, buf)
+                }
+                _ => self.render_footer_editview(area, buf),
+            },
+            Phase::Import => self.render_footer_import(area, buf),
+            Phase::SyncConflict => self.render_footer_sync_conflict(area, buf),
+            Phase::EditCoffee
+            | Phase::EditGrinder
+            | Phase::EditBrewer
+            | Phase::EditRoaster
+            | Phase::EditWater
+            | Phase::EditBasket => self.render_footer_catalog(area, buf),
+            Phase::Stats => self.render_footer_stats(area, buf),
+            Phase::DialIn => self.render_footer_dial_in(area, buf),
+            Phase::Wizard(step) => self.render_footer_wizard(step, area, buf),
+            Phase::Compare(..) => self.render_footer_compare(area, buf),
         }
+        self.render_sync_status(area, buf);
     }
 
     fn render_footer_listview(&self, area: Rect, buf: &mut Buffer) {
+        let controls = if self.state.range_picker.is_some() {
+            self.range_picker_controls()
+        } else if self.state.confirm_delete || self.state.confirm_range.is_some() {
+            Line::from(vec![
+                " Controls:".into(),
+                " Delete ".into(),
+                "<y>".fg(self.theme.accent).bold(),
+                " | Cancel ".into(),
+                "<n> ".fg(self.theme.accent).bold(),
+            ])
+        } else {
+            self.controls(LIST_KEYS, area.width)
+        };
+        let cmd = match (&self.state.filter, self.state.count) {
+            (_, Some(count)) => Line::from(format!(" {count}")),
+            _ if self.state.command.buffer.is_empty() && self.state.suggestion.is_some() => {
+                self.suggestion_line()
+            }
+            (Some(filter), _) if self.state.command.buffer.is_empty() => Line::from(vec![
+                " Filter: ".fg(self.theme.accent).bold(),
+                filter.text().to_string().into(),
+                " (:filter clear to list everything) ".dark_gray(),
+            ]),
+            _ => Line::from(self.state.command.buffer.clone()),
+        };
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+
+    fn render_footer_editview(&self, area: Rect, buf: &mut Buffer) {
+        let controls = self.controls(ENTRY_KEYS, area.width);
+        let cmd = match self.ratio_offer_line() {
+            _ if !self.state.command.buffer.is_empty() => {
+                Line::from(self.state.command.buffer.clone())
+            }
+            Some(line) => line,
+            None if self.state.suggestion.is_some() => self.suggestion_line(),
+            None => Line::from(self.state.command.buffer.clone()),
+        };
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+
+    fn render_footer_date_picker(&self, area: Rect, buf: &mut Buffer) {
         let controls = Line::from(vec![
             " Controls:".into(),
-            " Next ".into(),
-            "<j>".blue().bold(),
-            " | Previous ".into(),
-            "<k>".blue().bold(),
-            " | Quit ".into(),
-            "<q> ".blue().bold(),
+            " Pick part ".into(),
+            "<h/l>".fg(self.theme.accent).bold(),
+            " | Down/Up ".into(),
+            "<j/k>".fg(self.theme.accent).bold(),
+            " | Save ".into(),
+            "<Enter>".fg(self.theme.accent).bold(),
+            " | Cancel ".into(),
+            "<Esc> ".fg(self.theme.accent).bold(),
         ]);
         let cmd = Line::from(self.state.command.buffer.clone());
         Paragraph::new(vec![controls, cmd]).render(area, buf);
     }
 
-    fn render_footer_editview(&self, area: Rect, buf: &mut Buffer) {
+    fn render_footer_number(&self, area: Rect, buf: &mut Buffer) {
         let controls = Line::from(vec![
             " Controls:".into(),
-            " Next ".into(),
-            "<j>".blue().bold(),
-            " | Previous ".into(),
-            "<k>".blue().bold(),
-            " | Back ".into(),
-            "<q>".blue().bold(),
-            " | Edit ".into(),
-            "<e> ".blue().bold(),
+            " Save ".into(),
+            "<Enter>".fg(self.theme.accent).bold(),
+            " | Cancel ".into(),
+            "<Esc> ".fg(self.theme.accent).bold(),
         ]);
         let cmd = Line::from(self.state.command.buffer.clone());
         Paragraph::new(vec![controls, cmd]).render(area, buf);
     }
 
-    fn exit(&mut self) {
-        self.exit = true;
+    fn render_footer_notes(&self, area: Rect, buf: &mut Buffer) {
+        let controls = Line::from(vec![
+            " Controls:".into(),
+            " Move ".into(),
+            "<arrows>".fg(self.theme.accent).bold(),
+            " | New line ".into(),
+            "<Enter>".fg(self.theme.accent).bold(),
+            " | Save ".into(),
+            "<Esc/Ctrl-s> ".fg(self.theme.accent).bold(),
+        ]);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+
+    fn render_footer_new_entry(&self, area: Rect, buf: &mut Buffer) {
+        let controls = self.controls(NEW_ENTRY_KEYS, area.width);
+        let cmd = match self.ratio_offer_line().or_else(|| self.scale_line()) {
+            Some(line) if self.state.command.buffer.is_empty() => line,
+            _ => Line::from(self.state.command.buffer.clone()),
+        };
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
     }
 
     fn title(&self) -> String {
-        match self.phase {
-            Phase::ListView => String::from(" Coffee Tracking - Entries "),
+        let title = match self.phase {
+            Phase::ListView | Phase::SyncConflict => {
+                let mut title = String::from(" Coffee Tracking - Entries ");
+                if let Some(view) = &self.state.view {
+                    title.push_str(&format!("({view}) "));
+                }
+                if let Some(tag) = &self.state.tag_filter {
+                    title.push_str(&format!("#{tag} "));
+                }
+                if let Some(sort) = self.state.sort {
+                    title.push_str(&format!("{} ", sort.describe()));
+                }
+                if let Some(period) = &self.state.period {
+                    title.push_str(&format!("{} ", period.label()));
+                }
+                if let Some(query) = &self.state.search {
+                    title.push_str(&format!("/{query} "));
+                }
+                title
+            }
+            Phase::Dashboard => String::from(" Coffee Tracking - Home "),
+            Phase::NewEntry => String::from(" Coffee Tracking - New entry "),
+            Phase::Stats => match &self.state.period {
+                Some(period) => format!(" Coffee Tracking - Stats for {} ", period.label()),
+                None => String::from(" Coffee Tracking - Stats "),
+            },
+            Phase::DialIn => format!(" Coffee Tracking - Dialing in {} ", self.dial_in_coffee()),
+            Phase::Compare(..) => String::from(" Coffee Tracking - Comparing two entries "),
             _ => String::from(" Coffee Tracking "),
+        };
+        match &self.profile {
+            Some(profile) => format!("{title}[{profile}] "),
+            None => title,
         }
     }
 
-    fn format_entry_item(&self, entry: &Entry) -> String {
-        let star = if entry.favorite { "*" } else { " " }.bold().blue();
-        // let star = if entry.favorite { "★" } else { "☆" }.bold().blue();
-        format!(
-            " {} {} | {}",
-            star,
-            entry.dt_taken.format(DATE_FMT),
-            &self
-                .coffees
+    /// the name of the entry's coffee; entries whose coffee is missing from the data are still
+    /// shown rather than taking the whole screen down
+    fn coffee_name(&self, entry: &Entry) -> &str {
+        self.data
+            .coffees
+            .iter()
+            .find(|&c| c.uuid == entry.coffee_id)
+            .map_or("Unknown coffee", |c| &c.name)
+    }
+
+    /// like [`App::coffee_name`], for the entry's grinder
+    fn grinder_name(&self, entry: &Entry) -> &str {
+        self.data
+            .grinders
+            .iter()
+            .find(|&g| g.uuid == entry.grinder_id)
+            .map_or("Unknown grinder", |g| &g.name)
+    }
+
+    /// like [`App::coffee_name`], for the entry's brewer, which it may not have
+    fn brewer_name(&self, entry: &Entry) -> &str {
+        match entry.brewer_id {
+            None => "None",
+            Some(id) => self
+                .data
+                .brewers
                 .iter()
-                .find(|&c| c.uuid == entry.coffee_id)
-                .unwrap()
-                .name
-        )
-    }
-
-    fn format_entry_details(&self, entry: &Entry) -> Vec<String> {
-        vec![
-            format!("  Date brewed: {}", entry.dt_taken.format(DATE_FMT)),
-            format!(
-                "  Coffee: {}",
-                &self
-                    .coffees
-                    .iter()
-                    .find(|&c| c.uuid == entry.coffee_id)
-                    .unwrap()
-                    .name
-            ),
-            format!(
-                "  Grinder: {}",
-                &self
-                    .grinders
-                    .iter()
-                    .find(|&g| g.uuid == entry.grinder_id)
-                    .unwrap()
-                    .name
-            ),
-            format!("  Grind setting: {:.1}", entry.grind_setting),
-            format!("  Dose: {:.1} g", entry.dose),
-            format!("  Output: {:.1} g ", entry.output),
-            format!("  Ratio: {:.1} / 1", entry.output / entry.dose),
-            format!("  Duration: {:.1} sec", entry.duration),
-            format!("  Notes: {}", entry.notes),
-        ]
+                .find(|&b| b.uuid == id)
+                .map_or("Unknown brewer", |b| &b.name),
+        }
+    }
+
+    /// like [`App::brewer_name`], for the entry's water
+    fn water_name(&self, entry: &Entry) -> &str {
+        match entry.water_id {
+            None => "None",
+            Some(id) => self
+                .data
+                .waters
+                .iter()
+                .find(|&w| w.uuid == id)
+                .map_or("Unknown water", |w| &w.name),
+        }
+    }
+
+    /// like [`App::brewer_name`], for the entry's basket
+    fn basket_name(&self, entry: &Entry) -> &str {
+        match entry.basket_id {
+            None => "None",
+            Some(id) => self
+                .data
+                .baskets
+                .iter()
+                .find(|&b| b.uuid == id)
+                .map_or("Unknown basket", |b| &b.name),
+        }
+    }
+
+    /// the fields to show for the entry, then the custom ones from the config, then its photos and
+    /// a row for attaching another
+    fn entry_fields(&self, entry: &Entry) -> Vec<Field> {
+        let mut fields = entry.fields();
+        fields.extend((0..self.config.custom_fields.len()).map(Field::Custom));
+        fields.extend((0..=entry.photos.len()).map(Field::Photo));
+        fields
+    }
+
+    /// the selected entry's details, as the edit view shows them
+    fn render_entry_preview(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title(" Details ")
+            .border_set(border::ROUNDED);
+        let lines = match self.selected_entry().and_then(|i| self.data.entries.get(i)) {
+            Some(entry) => self.format_entry_details(entry),
+            None => vec![Line::from("  No entry selected").dark_gray()],
+        };
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+
+    fn format_entry_details(&self, entry: &Entry) -> Vec<Line<'static>> {
+        self.entry_fields(entry)
+            .into_iter()
+            .map(|field| self.field_line(entry, field))
+            .collect()
     }
 
-    fn field_val_as_string(&self, entry_idx: usize, field_idx: usize) -> String {
-        let entry = &self.entries[entry_idx];
-        format!(
-            "{}",
-            match field_idx {
-                3 => entry.grind_setting,
-                4 => entry.dose,
-                5 => entry.output,
-                7 => entry.duration,
-                _ => 0.0,
+    /// one of the entry's fields, its label and value, as the edit view shows it
+    fn field_line(&self, entry: &Entry, field: Field) -> Line<'static> {
+        let label = format!("  {}: ", field.label());
+        let value = match field {
+            Field::Custom(i) => return self.custom_line(i, entry),
+            Field::Photo(i) => return self.photo_line(i, entry),
+            Field::RoastAge => {
+                let age = match self.days_off_roast(entry) {
+                    Some(days) => freshness::roast_age_span(days),
+                    None => "Unknown".into(),
+                };
+                return Line::from(vec![label.into(), age]);
             }
-        )
+            Field::Date => self.date_text(entry.dt_taken),
+            Field::Method => entry.method.label().to_string(),
+            Field::Coffee => self.coffee_name(entry).to_string(),
+            Field::Grinder => self.grinder_name(entry).to_string(),
+            Field::Brewer => self.brewer_name(entry).to_string(),
+            Field::Water => self.water_name(entry).to_string(),
+            Field::Basket => self.basket_name(entry).to_string(),
+            Field::Ratio => format!("{:.1} / 1", entry.output / entry.dose),
+            Field::VsTarget => self
+                .target_deviation(entry)
+                .unwrap_or_else(|| String::from("-")),
+            Field::Suggestion => self.suggestion(entry).unwrap_or_else(|| String::from("-")),
+            Field::Cost => self
+                .shot_cost(entry)
+                .map_or(String::from("Unknown"), |cost| self.money(cost)),
+            Field::ExtractionYield => entry
+                .extraction_yield()
+                .map_or(String::from("-"), |ey| format!("{ey:.1} %")),
+            Field::FlowRate => entry
+                .flow_rate()
+                .map_or(String::from("-"), |flow| format!("{flow:.1} g/s")),
+            Field::Pours => format!("{}", entry.pours),
+            Field::Drink => entry
+                .milk
+                .map_or(milk::STRAIGHT, |m| m.drink.label())
+                .to_string(),
+            Field::Milk => entry.milk.map_or("-", |m| m.milk_type.label()).to_string(),
+            Field::Rating => tasting::stars(entry.rating, "-"),
+            Field::Temperature => self.config.temperature_unit.format(entry.temperature_c),
+            Field::Dose => self.weight(entry.dose),
+            Field::Output => self.weight(entry.output),
+            Field::Tags if entry.tags.is_empty() => String::from("-"),
+            Field::Tags => tags::format_tags(&entry.tags),
+            Field::Flavors if entry.flavors.is_empty() => String::from("-"),
+            Field::Flavors => flavors::format_flavors(&entry.flavors),
+            // one row per field, so later lines of the notes are run on
+            Field::Notes => entry.notes.replace('\n', " / "),
+            Field::Acidity
+            | Field::Sweetness
+            | Field::Body
+            | Field::Bitterness
+            | Field::Aftertaste => tasting::slider(field.score(entry)),
+            _ => match field.number(entry) {
+                Some(n) => format!("{n:.1}{}", field.unit()),
+                None => String::from("-"),
+            },
+        };
+        Line::from(label + &value)
+    }
+
+    /// the field highlighted in the edit view
+    fn selected_field(&self) -> Option<Field> {
+        let i = self.state.edit.list_state.selected()?;
+        let entry = self.edited_entry()?;
+        self.entry_fields(entry).get(i).copied()
+    }
+
+    /// the type of the field being edited in the edit view, if one is
+    fn editing_field(&self) -> Option<FieldType> {
+        match self.state.edit.input_mode {
+            InputMode::Normal => None,
+            InputMode::Editing => self.selected_field().map(Field::field_type),
+        }
+    }
+
+    /// the entry shown in the edit view: an existing one, or the one being created
+    fn edited_entry(&self) -> Option<&Entry> {
+        match self.phase {
+            Phase::EditEntry(idx) => self.data.entries.get(idx),
+            Phase::NewEntry | Phase::Wizard(_) => self.state.new_entry.as_ref(),
+            _ => None,
+        }
+    }
+
+    fn edited_entry_mut(&mut self) -> Option<&mut Entry> {
+        match self.phase {
+            Phase::EditEntry(idx) => self.data.entries.get_mut(idx),
+            Phase::NewEntry | Phase::Wizard(_) => self.state.new_entry.as_mut(),
+            _ => None,
+        }
+    }
+
+    /// opens the form for a new entry, filled in from the most recent brew
+    fn new_entry(&mut self) {
+        let entry = self.blank_entry();
+        self.open_new_entry(entry);
+    }
+
+    /// a new entry taken now, with the coffee, gear, method and dose of the last one
+    fn blank_entry(&self) -> Entry {
+        let last = self.data.entries.iter().max_by_key(|e| e.dt_taken);
+        let now = Local::now();
+        let archived = |uuid: Uuid| {
+            self.data
+                .coffees
+                .iter()
+                .any(|c| c.uuid == uuid && c.archived)
+        };
+        Entry {
+            dt_added: now,
+            dt_taken: now,
+            coffee_id: last
+                .map(|e| e.coffee_id)
+                .filter(|&uuid| !archived(uuid))
+                .or_else(|| {
+                    self.data
+                        .coffees
+                        .iter()
+                        .find(|c| !c.archived)
+                        .map(|c| c.uuid)
+                })
+                .unwrap_or_default(),
+            grinder_id: last
+                .map(|e| e.grinder_id)
+                .or_else(|| self.data.grinders.first().map(|g| g.uuid))
+                .unwrap_or_default(),
+            brewer_id: last.and_then(|e| e.brewer_id),
+            water_id: last.and_then(|e| e.water_id),
+            basket_id: last.and_then(|e| e.basket_id),
+            method: last.map(|e| e.method).unwrap_or_default(),
+            dose: last.map_or(0.0, |e| e.dose),
+            ..Default::default()
+        }
+    }
+
+    /// opens `entry` for editing, to be added with Enter
+    fn open_new_entry(&mut self, entry: Entry) {
+        self.state.new_entry = Some(entry);
+        self.state.edit = EditState {
+            list_state: ListState::default().with_selected(Some(0)),
+            ..Default::default()
+        };
+        self.phase = Phase::NewEntry;
+    }
+
+    /// adds a copy of the selected entry taken now and opens it for editing, for pulling the same
+    /// shot again
+    fn repeat_selected_entry(&mut self) {
+        let Some(entry) = self.selected_entry().and_then(|i| self.data.entries.get(i)) else {
+            return;
+        };
+        let entry = entry.again();
+        self.use_beans(entry.coffee_id, entry.dose);
+        self.data.entries.push(entry);
+        self.mark_changed();
+        let idx = self.data.entries.len() - 1;
+        self.select_entry(idx);
+        // start on the dose, the first of the fields that usually changes between shots
+        let dose = self.data.entries[idx]
+            .fields()
+            .iter()
+            .position(|f| *f == Field::Dose);
+        self.state.edit = EditState {
+            list_state: ListState::default().with_selected(dose),
+            ..Default::default()
+        };
+        self.phase = Phase::EditEntry(idx);
+    }
+
+    /// stars the selected entry, or unstars it
+    fn toggle_favorite(&mut self) {
+        if let Some(i) = self.selected_entry() {
+            self.data.entries[i].favorite = !self.data.entries[i].favorite;
+            self.mark_changed();
+        }
+    }
+
+    /// removes the entry selected in the list, keeping the selection on the entry that took its
+    /// place
+    fn delete_selected_entry(&mut self) {
+        let Some(i) = self.selected_entry() else {
+            return;
+        };
+        self.data.entries.remove(i);
+        self.mark_changed();
+        // the entries after it moved up, so the marks may be on others now
+        self.state.compare_marks.clear();
+        let listed = self.listed_entries().len();
+        if self
+            .state
+            .entry_list_state
+            .selected()
+            .is_some_and(|row| row >= listed)
+        {
+            self.state.entry_list_state.select(listed.checked_sub(1));
+        }
+    }
+
+    /// whether the entry is shown in the list under the current filter
+    fn listed(&self, entry: &Entry) -> bool {
+        self.state
+            .tag_filter
+            .as_ref()
+            .is_none_or(|tag| entry.has_tag(tag))
+            && self.filter_match(entry)
+            && self.in_period(entry)
+    }
+
+    /// indices into the entries of the ones shown in the list, in the order they're shown
+    fn listed_entries(&self) -> Vec<usize> {
+        let mut rows: Vec<usize> = (0..self.data.entries.len())
+            .filter(|&i| self.listed(&self.data.entries[i]))
+            .collect();
+        self.sort_entries(&mut rows);
+        rows
+    }
+
+    /// the index into the entries of the one selected in the list
+    fn selected_entry(&self) -> Option<usize> {
+        let row = self.state.entry_list_state.selected()?;
+        self.listed_entries().get(row).copied()
+    }
+
+    /// selects the entry at `idx` in the list, if it's listed
+    fn select_entry(&mut self, idx: usize) {
+        if let Some(row) = self.listed_entries().iter().position(|&i| i == idx) {
+            self.state.entry_list_state.select(Some(row));
+        }
+    }
+
+    /// appends the entry from the new entry form and selects it in the list
+    fn add_new_entry(&mut self) {
+        let Some(mut entry) = self.state.new_entry.take() else {
+            return;
+        };
+        entry.dt_added = Local::now();
+        self.use_beans(entry.coffee_id, entry.dose);
+        self.data.entries.push(entry);
+        self.mark_changed();
+        self.select_entry(self.data.entries.len() - 1);
+        self.phase = Phase::ListView;
+        self.suggest();
+    }
+
+    /// the text being typed into a one-line field, with the cursor shown
+    pub(crate) fn input_line(&self, label: &str) -> Line<'static> {
+        let input = &self.state.edit.input;
+        let before: String = input.value().chars().take(input.cursor()).collect();
+        let mut after = input.value().chars().skip(input.cursor());
+        let under_cursor = after.next().map_or(String::from(" "), String::from);
+        Line::from(vec![
+            format!("  {label}: ").into(),
+            before.fg(self.theme.accent),
+            under_cursor.reversed(),
+            after.collect::<String>().fg(self.theme.accent),
+        ])
     }
 
-    fn save_input(&mut self, entry_idx: usize) {
-        match Entry::field_type(self.state.edit.list_state.selected().unwrap()) {
-            FieldType::Date => todo!(),
-            FieldType::CoffeeType => todo!(),
-            FieldType::GrinderType => todo!(),
+    fn field_val_as_string(&self, field: Field) -> String {
+        let Some(entry) = self.edited_entry() else {
+            return String::new();
+        };
+        if field == Field::Temperature {
+            let unit = self.config.temperature_unit;
+            // rounded, so that a converted temperature doesn't come out as 199.40000000000003
+            return entry.temperature_c.map_or(String::new(), |c| {
+                format!("{}", (unit.in_unit(c) * 10.0).round() / 10.0)
+            });
+        }
+        if matches!(field, Field::Dose | Field::Output) {
+            return field
+                .number(entry)
+                .map_or(String::new(), |grams| self.typed_weight(grams));
+        }
+        field
+            .number(entry)
+            .map_or(String::new(), |n| format!("{n}"))
+    }
+
+    /// leaves field editing after a value has been written to the entry
+    fn field_saved(&mut self) {
+        // a new entry only becomes part of the data once it is added
+        if matches!(self.phase, Phase::EditEntry(_)) {
+            self.mark_changed();
+        }
+        self.state.edit.input_mode = InputMode::Normal;
+    }
+
+    fn save_input(&mut self) {
+        let Some(field) = self.selected_field() else {
+            return;
+        };
+        match field.field_type() {
+            FieldType::Date => {
+                if let Some(dt) = self.state.edit.date.value()
+                    && let Some(entry) = self.edited_entry_mut()
+                {
+                    entry.dt_taken = dt;
+                    self.field_saved();
+                }
+            }
+            // these write the choice to the entry themselves
+            FieldType::Choice | FieldType::Catalog(_) => {}
+            FieldType::ShortString if field == Field::Temperature => {
+                let unit = self.config.temperature_unit;
+                let temperature = match self.state.edit.input.value() {
+                    "" => None,
+                    val => match val.parse::<f64>() {
+                        Ok(val) => Some(unit.to_celsius(val)),
+                        Err(_) => return self.warn(format!("Invalid number `{val}`")),
+                    },
+                };
+                if let Some(entry) = self.edited_entry_mut() {
+                    entry.temperature_c = temperature;
+                    self.field_saved();
+                }
+            }
             FieldType::ShortString => {
-                if let Ok(val) = self.state.edit.input.value().parse::<f64>() {
-                    match self.state.edit.list_state.selected().unwrap() {
-                        3 => self.entries[entry_idx].grind_setting = val,
-                        4 => self.entries[entry_idx].dose = val,
-                        5 => self.entries[entry_idx].output = val,
-                        7 => self.entries[entry_idx].duration = val,
-                        _ => {}
+                // an empty value unsets optional fields and leaves the others as they were
+                let val = match self.state.edit.input.value() {
+                    "" => None,
+                    val => match val.parse::<f64>() {
+                        Ok(val) => Some(val),
+                        Err(_) => return self.warn(format!("Invalid number `{val}`")),
+                    },
+                };
+                // typed in the unit the settings say, kept in grams
+                let val = val.map(|v| self.typed_number(field, v));
+                if let Some(entry) = self.edited_entry_mut() {
+                    match val {
+                        Some(val) => field.set_number(entry, val),
+                        None => field.clear_number(entry),
                     }
-                    self.state.edit.input_mode = InputMode::Normal;
+                    self.field_saved();
+                    self.offer_ratio(field);
                 }
                 // let val = self.state.edit.input.value_and_reset();
                 // let val: f64 = val.parse().unwrap();
             }
-            FieldType::LongString => todo!(),
-            FieldType::Undefined => todo!(),
+            FieldType::LongString => {
+                let notes = self.state.edit.notes.text().trim_end().to_string();
+                if let Some(entry) = self.edited_entry_mut() {
+                    entry.notes = notes;
+                    self.field_saved();
+                }
+            }
+            FieldType::Score => {
+                let score = self.state.edit.score;
+                if let Some(entry) = self.edited_entry_mut() {
+                    field.set_score(entry, score);
+                    self.field_saved();
+                }
+            }
+            FieldType::Custom(i) => self.save_custom(i),
+            FieldType::Photo(i) => self.save_photo(i),
+            FieldType::Flavors => {
+                let flavors = self.picked_flavors();
+                if let Some(entry) = self.edited_entry_mut() {
+                    entry.flavors = flavors;
+                    self.field_saved();
+                }
+            }
+            FieldType::Tags => {
+                let tags = tags::parse_tags(self.state.edit.input.value());
+                if let Some(entry) = self.edited_entry_mut() {
+                    entry.tags = tags;
+                    self.field_saved();
+                }
+            }
+            FieldType::Undefined => {}
         }
     }
 }
 
 impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let [main_area, footer_area] =
-            Layout::vertical([Constraint::Fill(1), Constraint::Length(2)]).areas(area);
+        let [tabs_area, main_area, footer_area] = Layout::vertical([
+            Constraint::Length(u16::from(self.tab().is_some())),
+            Constraint::Fill(1),
+            Constraint::Length(2),
+        ])
+        .areas(area);
 
+        buf.set_style(area, self.theme.base);
+        if size::too_small(area) {
+            self.render_too_small(area, buf);
+            return;
+        }
+        self.render_tab_bar(tabs_area, buf);
         self.render_main(main_area, buf);
+        self.render_toasts(main_area, buf);
+        self.render_completions(main_area, buf);
+        self.render_history_search(main_area, buf);
         self.render_footer(footer_area, buf);
     }
 }
 
 #[derive(Debug, Default)]
 enum Phase {
+    /// the home screen, shown at startup
     #[default]
+    Dashboard,
     ListView,
     EditEntry(usize),
+    NewEntry,
+    Import,
+    SyncConflict,
     EditCoffee,
     EditGrinder,
+    EditBrewer,
+    EditRoaster,
+    EditWater,
+    EditBasket,
+    Stats,
+    /// the dial-in session picked with `:dialin`
+    DialIn,
+    /// the new entry being filled in a step at a time
+    Wizard(Step),
+    /// two entries side by side, the ones marked with `m`
+    Compare(usize, usize),
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 struct Entry {
     dt_added: DateTime<Local>,
     dt_taken: DateTime<Local>,
     coffee_id: Uuid,
     grinder_id: Uuid,
+    /// older entries, and ones logged without picking a brewer, have none
+    brewer_id: Option<Uuid>,
+    water_id: Option<Uuid>,
+    /// espresso only
+    basket_id: Option<Uuid>,
+    method: BrewMethod,
     grind_setting: f64,
     duration: f64,
     dose: f64,
     output: f64,
+    /// in percent, when measured
+    tds_percent: Option<f64>,
+    /// brew water temperature, in Celsius
+    temperature_c: Option<f64>,
+    /// pour-over only
+    bloom_time: f64,
+    pours: f64,
+    /// espresso only
+    preinfusion_s: Option<f64>,
+    /// espresso only, in bar
+    pressure_bar: Option<f64>,
+    /// for espressos made into milk drinks
+    milk: Option<Milk>,
     favorite: bool,
+    /// whether this is the shot the others of its coffee are compared with
+    reference: bool,
+    /// out of [`MAX_RATING`] stars
+    rating: Option<u8>,
+    taste: Taste,
+    /// without the leading `#`
+    tags: Vec<String>,
+    /// descriptors from the flavor wheel, like "Fruity > Berry > Blueberry"
+    flavors: Vec<String>,
+    /// values of the config's `custom_fields`, by field name; unset ones are left out
+    custom: HashMap<String, Value>,
     notes: String,
+    /// paths of pictures of the shot
+    photos: Vec<String>,
+}
+
+impl Entry {
+    /// a copy of the entry taken now, for pulling the same shot again
+    fn again(&self) -> Self {
+        let now = Local::now();
+        Self {
+            dt_added: now,
+            dt_taken: now,
+            // whether it's worth a star, or how many, is for the new shot to earn
+            favorite: false,
+            reference: false,
+            rating: None,
+            photos: Vec::new(),
+            ..self.clone()
+        }
+    }
 }
 
 enum FieldType {
     Date,
-    CoffeeType,
-    GrinderType,
+    /// one of a few fixed options, cycled through in place
+    Choice,
+    /// a coffee, grinder, brewer, water or basket, picked with the selector
+    Catalog(Kind),
     ShortString,
     LongString,
+    /// a tasting score, adjusted with a slider
+    Score,
+    /// a list of tags, typed as text
+    Tags,
+    /// flavor descriptors, picked from the wheel
+    Flavors,
+    /// the field at this index in the config's `custom_fields`
+    Custom(usize),
+    /// a photo's path, typed as text
+    Photo(usize),
     Undefined,
 }
 
-impl Entry {
-    fn field_type(i: usize) -> FieldType {
-        match i {
-            0 => FieldType::Date,
-            1 => FieldType::CoffeeType,
-            2 => FieldType::GrinderType,
-            val if (val > 2 && val != 6 && val != 8) => FieldType::ShortString,
-            8 => FieldType::LongString,
-            _ => FieldType::Undefined,
-        }
-    }
-}
-
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 struct Coffee {
     name: String,
     uuid: Uuid,
+    roaster_id: Option<Uuid>,
+    roast_date: Option<NaiveDate>,
+    /// when the bag was opened
+    open_date: Option<NaiveDate>,
+    /// when the beans went into the freezer and came back out; the days in between don't count
+    /// towards their age
+    frozen_date: Option<NaiveDate>,
+    thawed_date: Option<NaiveDate>,
+    /// where the coffee was grown; free text, empty if unknown
+    country: String,
+    region: String,
+    /// washed, natural, honey…
+    process: String,
+    varietal: String,
+    roast_level: RoastLevel,
+    /// how much the current bag held when full, if it is being tracked
+    bag_size_g: Option<f64>,
+    /// what's left of it, less the dose of every entry added since
+    remaining_g: f64,
+    /// what the current bag cost, in the config's currency
+    bag_price: Option<f64>,
+    decaf: bool,
+    /// milligrams of caffeine per gram of beans, for coffees known to have more or less than
+    /// usual
+    caffeine_mg_per_g: Option<f64>,
+    /// the recipe being dialed in
+    target: Target,
+    /// finished with, so no longer offered for new entries
+    archived: bool,
 }
 
 impl Coffee {
@@ -497,14 +2063,74 @@ impl Coffee {
         Self {
             name,
             uuid: Uuid::new_v4(),
+            ..Self::default()
         }
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum RoastLevel {
+    #[default]
+    Unknown,
+    Light,
+    MediumLight,
+    Medium,
+    MediumDark,
+    Dark,
+}
+
+const ROAST_LEVELS: [RoastLevel; 6] = [
+    RoastLevel::Unknown,
+    RoastLevel::Light,
+    RoastLevel::MediumLight,
+    RoastLevel::Medium,
+    RoastLevel::MediumDark,
+    RoastLevel::Dark,
+];
+
+impl RoastLevel {
+    fn label(self) -> &'static str {
+        match self {
+            RoastLevel::Unknown => "Unknown",
+            RoastLevel::Light => "Light",
+            RoastLevel::MediumLight => "Medium-light",
+            RoastLevel::Medium => "Medium",
+            RoastLevel::MediumDark => "Medium-dark",
+            RoastLevel::Dark => "Dark",
+        }
+    }
+
+    /// how the roast level is written in the data file
+    fn key(self) -> &'static str {
+        match self {
+            RoastLevel::Unknown => "unknown",
+            RoastLevel::Light => "light",
+            RoastLevel::MediumLight => "medium_light",
+            RoastLevel::Medium => "medium",
+            RoastLevel::MediumDark => "medium_dark",
+            RoastLevel::Dark => "dark",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        ROAST_LEVELS.into_iter().find(|l| l.key() == key)
+    }
+
+    /// the level after this one, wrapping around
+    fn next(self) -> Self {
+        let i = ROAST_LEVELS.iter().position(|l| *l == self).unwrap_or(0);
+        ROAST_LEVELS[(i + 1) % ROAST_LEVELS.len()]
+    }
+}
+
+#[derive(Debug, Default, Clone)]
 struct Grinder {
     name: String,
     uuid: Uuid,
+    /// when the current burrs went in; wear is counted from that day
+    burrs_fitted: Option<NaiveDate>,
+    /// how much the burrs are good for, if known
+    burr_life_kg: Option<f64>,
 }
 
 impl Grinder {
@@ -512,55 +2138,89 @@ impl Grinder {
         Self {
             name,
             uuid: Uuid::new_v4(),
+            ..Self::default()
         }
     }
 }
 
-impl Default for App {
-    fn default() -> Self {
-        let coffees = vec![
-            Coffee::new(String::from("B&W FSL28")),
-            Coffee::new(String::from("Folgers")),
-        ];
-        let grinder = Grinder::new(String::from("Niche Zero"));
-        let now = Local::now();
+/// an espresso machine, pour-over dripper, AeroPress, moka pot…
+#[derive(Debug, Default, Clone)]
+struct Brewer {
+    name: String,
+    uuid: Uuid,
+    /// backflushes, descales and gasket changes, in the order they were logged
+    maintenance: Vec<Maintenance>,
+}
 
+impl Brewer {
+    fn new(name: String) -> Self {
         Self {
-            state: Default::default(),
-            phase: Default::default(),
-            entries: vec![
-                Entry {
-                    dt_taken: now + Duration::from_secs(0),
-                    coffee_id: coffees[0].uuid.clone(),
-                    grinder_id: grinder.uuid.clone(),
-                    dose: 18.0,
-                    output: 45.1,
-                    duration: 26.0,
-                    ..Default::default()
-                },
-                Entry {
-                    dt_taken: now + Duration::from_secs(600),
-                    coffee_id: coffees[0].uuid.clone(),
-                    grinder_id: grinder.uuid.clone(),
-                    dose: 18.0,
-                    output: 44.6,
-                    duration: 32.1,
-                    favorite: true,
-                    ..Default::default()
-                },
-                Entry {
-                    dt_taken: now + Duration::from_secs(1580),
-                    coffee_id: coffees[1].uuid.clone(),
-                    grinder_id: grinder.uuid.clone(),
-                    dose: 18.0,
-                    output: 43.9,
-                    duration: 20.9,
-                    ..Default::default()
-                },
-            ],
-            coffees: coffees,
-            grinders: vec![grinder],
-            exit: Default::default(),
+            name,
+            uuid: Uuid::new_v4(),
+            ..Self::default()
+        }
+    }
+}
+
+/// what the coffee was brewed with, e.g. "Third Wave Water light" or a local tap
+#[derive(Debug, Default, Clone)]
+struct Water {
+    name: String,
+    uuid: Uuid,
+    /// general and carbonate hardness, in ppm as CaCO3, if known
+    gh_ppm: Option<f64>,
+    kh_ppm: Option<f64>,
+    /// how it's made up, free text
+    recipe: String,
+}
+
+impl Water {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            uuid: Uuid::new_v4(),
+            ..Self::default()
+        }
+    }
+}
+
+/// the filter basket of an espresso machine's portafilter
+#[derive(Debug, Default, Clone)]
+struct Basket {
+    name: String,
+    uuid: Uuid,
+    brand: String,
+    /// the dose it's made for, if known
+    size_g: Option<f64>,
+    /// a precision basket (VST, IMS…) rather than the one that came with the machine
+    precision: bool,
+}
+
+impl Basket {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            uuid: Uuid::new_v4(),
+            ..Self::default()
+        }
+    }
+}
+
+/// who roasted a coffee; the website and city are free text and may be empty
+#[derive(Debug, Default, Clone)]
+struct Roaster {
+    name: String,
+    uuid: Uuid,
+    website: String,
+    city: String,
+}
+
+impl Roaster {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            uuid: Uuid::new_v4(),
+            ..Self::default()
         }
     }
 }
@@ -568,20 +2228,54 @@ impl Default for App {
 impl Default for AppState {
     fn default() -> Self {
         Self {
-            entry_list_state: ListState::default().with_selected(Some(0)),
+            entry_list_state: TableState::default().with_selected(Some(0)),
             command: Default::default(),
             edit: EditState {
                 list_state: ListState::default().with_selected(Some(0)),
                 ..Default::default()
             },
+            confirm_delete: false,
+            confirm_range: None,
+            confirm_quit: false,
+            new_entry: None,
+            import: None,
+            catalog: CatalogScreen::default(),
+            sync_conflict: None,
+            tag_filter: None,
+            sort: None,
+            search: None,
+            filter: None,
+            view: None,
+            period: None,
+            range_picker: None,
+            chart: None,
+            suggestion: None,
+            report: None,
+            shot_timer: None,
+            brew: None,
+            quick_log: None,
+            scale: None,
+            wizard_error: None,
+            ratio_offer: None,
+            help: None,
+            notes_preview: None,
+            toasts: VecDeque::new(),
+            page_rows: 0,
+            count: None,
+            compare_marks: Vec::new(),
+            compare: CompareView::default(),
+            dial_in: DialInView::default(),
+            stats: StatsView::default(),
         }
     }
 }
 
 fn valid_float(s: &str) -> bool {
-    if let Ok(_) = s.parse::<f64>() {
-        true
-    } else {
-        false
-    }
+    s.parse::<f64>().is_ok_and(f64::is_finite)
+}
+
+/// what's wrong with the number typed into a field, while something is. An empty one is fine, it
+/// unsets the field.
+fn number_error(s: &str) -> Option<&'static str> {
+    (!s.is_empty() && !valid_float(s)).then_some("expected a number like 18.5")
 }
@@ -0,0 +1,155 @@
+//! Looking after the machine: backflushes, descales and gasket changes are logged on the brewer
+//! they were done on. Once a task has been logged, the days and shots since the last one are
+//! counted against how often it's due, and the list view reminds about the ones that are overdue.
+
+use chrono::{Local, NaiveDate};
+use ratatui::{style::Stylize, text::Line};
+
+use crate::{freshness::DAY_FMT, App, Brewer};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Task {
+    Backflush,
+    Descale,
+    GasketChange,
+}
+
+const TASKS: [Task; 3] = [Task::Backflush, Task::Descale, Task::GasketChange];
+
+impl Task {
+    /// as it reads in "Linea Mini backflush"
+    fn name(self) -> &'static str {
+        match self {
+            Task::Backflush => "backflush",
+            Task::Descale => "descale",
+            Task::GasketChange => "gasket change",
+        }
+    }
+
+    /// how the task is written in the data file
+    pub fn key(self) -> &'static str {
+        match self {
+            Task::Backflush => "backflush",
+            Task::Descale => "descale",
+            Task::GasketChange => "gasket_change",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        TASKS.into_iter().find(|t| t.key() == key)
+    }
+
+    /// the task is due this many days after it was last done
+    fn interval_days(self) -> i64 {
+        match self {
+            Task::Backflush => 7,
+            Task::Descale => 90,
+            Task::GasketChange => 365,
+        }
+    }
+
+    /// or after this many shots, if that comes first
+    fn interval_shots(self) -> Option<usize> {
+        match self {
+            Task::Backflush => Some(100),
+            Task::Descale | Task::GasketChange => None,
+        }
+    }
+}
+
+/// one time a task was done
+#[derive(Debug, Clone, Copy)]
+pub struct Maintenance {
+    pub task: Task,
+    pub day: NaiveDate,
+}
+
+impl Brewer {
+    /// the day the task was last done, if it's been logged
+    pub(crate) fn last_done(&self, task: Task) -> Option<NaiveDate> {
+        self.maintenance
+            .iter()
+            .filter(|m| m.task == task)
+            .map(|m| m.day)
+            .max()
+    }
+}
+
+impl App {
+    /// days and shots on the brewer since the task was last done
+    fn since_done(&self, brewer: &Brewer, task: Task) -> Option<(i64, usize)> {
+        let day = brewer.last_done(task)?;
+        let days = (Local::now().date_naive() - day).num_days();
+        let shots = self
+            .data
+            .entries
+            .iter()
+            .filter(|e| e.brewer_id == Some(brewer.uuid) && e.dt_taken.date_naive() >= day)
+            .count();
+        Some((days, shots))
+    }
+
+    fn overdue(&self, brewer: &Brewer, task: Task) -> bool {
+        self.since_done(brewer, task).is_some_and(|(days, shots)| {
+            days > task.interval_days() || task.interval_shots().is_some_and(|max| shots > max)
+        })
+    }
+
+    /// e.g. "2026-10-01 (14 days, 52 shots ago, overdue)", or empty if it's never been logged
+    pub(crate) fn maintenance_text(&self, brewer: &Brewer, task: Task) -> String {
+        let (Some(day), Some((days, shots))) =
+            (brewer.last_done(task), self.since_done(brewer, task))
+        else {
+            return String::new();
+        };
+        format!(
+            "{} ({days} {}, {shots} {} ago{})",
+            day.format(DAY_FMT),
+            if days == 1 { "day" } else { "days" },
+            if shots == 1 { "shot" } else { "shots" },
+            if self.overdue(brewer, task) {
+                ", overdue"
+            } else {
+                ""
+            }
+        )
+    }
+
+    /// logs the task as done on `day`
+    pub(crate) fn log_maintenance(&mut self, i: usize, task: Task, day: NaiveDate) {
+        self.data.brewers[i]
+            .maintenance
+            .push(Maintenance { task, day });
+    }
+
+    /// takes back the latest time the task was logged, for when it was logged by mistake
+    pub(crate) fn unlog_maintenance(&mut self, i: usize, task: Task) {
+        let brewer = &mut self.data.brewers[i];
+        let latest = brewer
+            .maintenance
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.task == task)
+            .max_by_key(|(_, m)| m.day)
+            .map(|(j, _)| j);
+        if let Some(j) = latest {
+            brewer.maintenance.remove(j);
+        }
+    }
+
+    /// a reminder naming the overdue tasks, if there are any
+    pub(crate) fn maintenance_warning(&self) -> Option<Line<'static>> {
+        let due: Vec<String> = self
+            .data
+            .brewers
+            .iter()
+            .flat_map(|b| TASKS.into_iter().map(move |t| (b, t)))
+            .filter(|(b, t)| self.overdue(b, *t))
+            .map(|(b, t)| format!("{} {}", b.name, t.name()))
+            .collect();
+        if due.is_empty() {
+            return None;
+        }
+        Some(Line::from(format!(" Maintenance due: {} ", due.join(", "))).yellow())
+    }
+}
@@ -0,0 +1,153 @@
+//! Milk drinks. An espresso can be logged as the base of a flat white, latte or cappuccino along
+//! with the milk that went into it, so that straight shots and milk drinks can be told apart.
+
+use crate::{fields::BrewMethod, App};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Drink {
+    FlatWhite,
+    Latte,
+    Cappuccino,
+    Cortado,
+}
+
+const DRINKS: [Drink; 4] = [
+    Drink::FlatWhite,
+    Drink::Latte,
+    Drink::Cappuccino,
+    Drink::Cortado,
+];
+
+impl Drink {
+    pub fn label(self) -> &'static str {
+        match self {
+            Drink::FlatWhite => "Flat white",
+            Drink::Latte => "Latte",
+            Drink::Cappuccino => "Cappuccino",
+            Drink::Cortado => "Cortado",
+        }
+    }
+
+    /// how the drink is written in the data file
+    pub fn key(self) -> &'static str {
+        match self {
+            Drink::FlatWhite => "flat_white",
+            Drink::Latte => "latte",
+            Drink::Cappuccino => "cappuccino",
+            Drink::Cortado => "cortado",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        DRINKS.into_iter().find(|d| d.key() == key)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum MilkType {
+    #[default]
+    Whole,
+    SemiSkimmed,
+    Skimmed,
+    Oat,
+    Almond,
+    Soy,
+    Other,
+}
+
+const MILK_TYPES: [MilkType; 7] = [
+    MilkType::Whole,
+    MilkType::SemiSkimmed,
+    MilkType::Skimmed,
+    MilkType::Oat,
+    MilkType::Almond,
+    MilkType::Soy,
+    MilkType::Other,
+];
+
+impl MilkType {
+    pub fn label(self) -> &'static str {
+        match self {
+            MilkType::Whole => "Whole",
+            MilkType::SemiSkimmed => "Semi-skimmed",
+            MilkType::Skimmed => "Skimmed",
+            MilkType::Oat => "Oat",
+            MilkType::Almond => "Almond",
+            MilkType::Soy => "Soy",
+            MilkType::Other => "Other",
+        }
+    }
+
+    /// how the milk is written in the data file
+    pub fn key(self) -> &'static str {
+        match self {
+            MilkType::Whole => "whole",
+            MilkType::SemiSkimmed => "semi_skimmed",
+            MilkType::Skimmed => "skimmed",
+            MilkType::Oat => "oat",
+            MilkType::Almond => "almond",
+            MilkType::Soy => "soy",
+            MilkType::Other => "other",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        MILK_TYPES.into_iter().find(|m| m.key() == key)
+    }
+
+    /// the milk after this one, wrapping around
+    pub fn next(self) -> Self {
+        let i = MILK_TYPES.iter().position(|m| *m == self).unwrap_or(0);
+        MILK_TYPES[(i + 1) % MILK_TYPES.len()]
+    }
+}
+
+/// what an espresso was made into
+#[derive(Debug, Clone, Copy)]
+pub struct Milk {
+    pub drink: Drink,
+    pub milk_type: MilkType,
+    /// millilitres
+    pub amount_ml: Option<f64>,
+}
+
+/// the drink after `milk`'s, going from a straight shot through the milk drinks and back. The milk
+/// itself is kept from one drink to the next.
+pub fn next_drink(milk: Option<Milk>) -> Option<Milk> {
+    let Some(milk) = milk else {
+        return Some(Milk {
+            drink: DRINKS[0],
+            milk_type: MilkType::default(),
+            amount_ml: None,
+        });
+    };
+    let i = DRINKS.iter().position(|d| *d == milk.drink).unwrap_or(0);
+    DRINKS.get(i + 1).map(|&drink| Milk { drink, ..milk })
+}
+
+/// what an espresso is shown as when it isn't a milk drink
+pub const STRAIGHT: &str = "Straight";
+
+impl App {
+    /// how many espressos in the period went into each kind of drink, straight shots first,
+    /// leaving out the drinks never made
+    pub(crate) fn drink_counts(&self) -> Vec<(&'static str, usize)> {
+        let espressos: Vec<_> = self
+            .data
+            .entries
+            .iter()
+            .filter(|e| e.method == BrewMethod::Espresso && self.in_period(e))
+            .collect();
+        let straight = espressos.iter().filter(|e| e.milk.is_none()).count();
+        let mut counts = vec![(STRAIGHT, straight)];
+        counts.extend(DRINKS.iter().map(|&drink| {
+            let made = espressos
+                .iter()
+                .filter(|e| e.milk.is_some_and(|m| m.drink == drink))
+                .count();
+            (drink.label(), made)
+        }));
+        counts.retain(|(_, n)| *n > 0);
+        counts
+    }
+}
@@ -0,0 +1,196 @@
+//! The notes preview. `n` in an entry shows its notes over the whole screen with basic Markdown
+//! rendered, so longer notes with some structure stay readable:
+//!
+//! - `#`, `##` and `###` headings
+//! - `-`, `*` and `+` bullets, and numbered lists, indented as they're written
+//! - `**bold**`, `*italic*` and `` `code` ``
+//! - `>` quotes and fenced code blocks
+//!
+//! Everything else is shown as written. j and k scroll, and Esc, q or n close it again.
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Margin, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Clear, Padding, Paragraph, Widget, Wrap},
+};
+
+use crate::App;
+
+#[derive(Debug, Default)]
+pub struct NotesPreview {
+    /// how many lines are scrolled past
+    scroll: usize,
+}
+
+/// the spans of one line of Markdown, with its bold, italic and code marked out
+fn inline(text: &str, base: Style, accent: Color) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let marked = match c {
+            '`' => rest[1..].find('`').map(|end| (1, end, base.fg(accent))),
+            '*' | '_' if rest[1..].starts_with(c) => {
+                let marker = &rest[..2];
+                rest[2..]
+                    .find(marker)
+                    .filter(|&end| end > 0)
+                    .map(|end| (2, end, base.add_modifier(Modifier::BOLD)))
+            }
+            '*' => rest[1..]
+                .find('*')
+                .filter(|&end| end > 0 && !rest[1..].starts_with(' '))
+                .map(|end| (1, end, base.add_modifier(Modifier::ITALIC))),
+            _ => None,
+        };
+        match marked {
+            Some((len, end, style)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut plain), base));
+                }
+                spans.push(Span::styled(rest[len..len + end].to_string(), style));
+                rest = &rest[2 * len + end..];
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !plain.is_empty() {
+        spans.push(Span::styled(plain, base));
+    }
+    spans
+}
+
+/// `1. ` or `12. ` at the start of `text`, the number and what follows it
+fn numbered(text: &str) -> Option<(&str, &str)> {
+    let digits = text.find(|c: char| !c.is_ascii_digit())?;
+    let rest = text[digits..].strip_prefix(". ")?;
+    (digits > 0).then(|| (&text[..digits], rest))
+}
+
+/// the notes, Markdown rendered into lines
+pub fn render_markdown(text: &str, accent: Color) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    for raw in text.lines() {
+        let trimmed = raw.trim_start();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            lines.push(Line::from(format!("    {raw}")).fg(accent));
+            continue;
+        }
+        let indent = &raw[..raw.len() - trimmed.len()];
+        let line = if let Some(heading) = trimmed.strip_prefix("# ") {
+            Line::from(heading.to_uppercase()).fg(accent).bold()
+        } else if let Some(heading) = trimmed.strip_prefix("## ") {
+            Line::from(heading.to_string()).fg(accent).bold()
+        } else if let Some(heading) = trimmed.strip_prefix("### ") {
+            Line::from(heading.to_string()).bold()
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            let mut spans = vec![Span::styled("│ ", Style::new().fg(accent))];
+            spans.extend(inline(quote.trim_start(), Style::new().italic(), accent));
+            Line::from(spans)
+        } else if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|bullet| trimmed.strip_prefix(bullet))
+        {
+            let mut spans = vec![format!("{indent}  • ").fg(accent)];
+            spans.extend(inline(item, Style::new(), accent));
+            Line::from(spans)
+        } else if let Some((number, item)) = numbered(trimmed) {
+            let mut spans = vec![format!("{indent}  {number}. ").fg(accent)];
+            spans.extend(inline(item, Style::new(), accent));
+            Line::from(spans)
+        } else {
+            let mut spans = vec![Span::raw(indent.to_string())];
+            spans.extend(inline(trimmed, Style::new(), accent));
+            Line::from(spans)
+        };
+        lines.push(line);
+    }
+    lines
+}
+
+impl App {
+    /// shows the edited entry's notes, if it has any
+    pub(crate) fn open_notes_preview(&mut self) {
+        if self
+            .edited_entry()
+            .is_some_and(|entry| !entry.notes.trim().is_empty())
+        {
+            self.state.notes_preview = Some(NotesPreview::default());
+        } else {
+            self.info("There are no notes to show");
+        }
+    }
+
+    pub(crate) fn handle_key_events_notes_preview(&mut self, key_event: KeyEvent) {
+        let Some(preview) = &mut self.state.notes_preview else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => preview.scroll += 1,
+            KeyCode::Char('k') | KeyCode::Up => preview.scroll = preview.scroll.saturating_sub(1),
+            KeyCode::PageDown => preview.scroll += 10,
+            KeyCode::PageUp => preview.scroll = preview.scroll.saturating_sub(10),
+            KeyCode::Char('g') | KeyCode::Home => preview.scroll = 0,
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('n') => {
+                self.state.notes_preview = None
+            }
+            _ => {}
+        }
+    }
+
+    /// draws the notes over all of `area`
+    pub(crate) fn render_notes_preview(&mut self, area: Rect, buf: &mut Buffer) {
+        if self.state.notes_preview.is_none() {
+            return;
+        }
+        let Some(entry) = self.edited_entry() else {
+            return;
+        };
+        let title = format!(" Notes: {} ", self.coffee_name(entry));
+        let mut lines = vec![Line::from("")];
+        lines.extend(render_markdown(&entry.notes, self.theme.accent));
+        let popup = area.inner(Margin::new(2, 1));
+        let height = popup.height.saturating_sub(2) as usize;
+        // the rows the lines take once wrapped, about: words wrapped whole can take a few more
+        let width = usize::from(popup.width.saturating_sub(4)).max(1);
+        let rows: usize = lines.iter().map(|l| l.width().max(1).div_ceil(width)).sum();
+        let Some(preview) = &mut self.state.notes_preview else {
+            return;
+        };
+        // not past the end, so scrolling back up answers straight away
+        preview.scroll = preview.scroll.min(rows.saturating_sub(height));
+        let block = Block::bordered().title(title).border_set(border::ROUNDED);
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((preview.scroll as u16, 0))
+            .block(block.padding(Padding::horizontal(1)))
+            .render(popup, buf);
+    }
+
+    pub(crate) fn render_footer_notes_preview(&self, area: Rect, buf: &mut Buffer) {
+        let controls = Line::from(vec![
+            " Controls:".into(),
+            " Scroll ".into(),
+            "<j/k>".fg(self.theme.accent).bold(),
+            " | Top ".into(),
+            "<g>".fg(self.theme.accent).bold(),
+            " | Close ".into(),
+            "<Esc> ".fg(self.theme.accent).bold(),
+        ]);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
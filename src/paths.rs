@@ -0,0 +1,84 @@
+//! Where the data file, config and state file live by default on each platform.
+//!
+//! - Linux and other unixes: `$XDG_DATA_HOME` / `$XDG_CONFIG_HOME` / `$XDG_STATE_HOME`,
+//!   defaulting to `~/.local/share`, `~/.config` and `~/.local/state`
+//! - macOS: `~/Library/Application Support` for all three
+//! - Windows: `%APPDATA%` for all three
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+const APP_DIR: &str = "coffee-tracking";
+const CONFIG_FILE: &str = "config.json";
+const STATE_FILE: &str = "state.json";
+
+pub fn data_dir() -> PathBuf {
+    platform_dir("XDG_DATA_HOME", &[".local", "share"]).join(APP_DIR)
+}
+
+pub fn config_dir() -> PathBuf {
+    platform_dir("XDG_CONFIG_HOME", &[".config"]).join(APP_DIR)
+}
+
+pub fn config_file() -> PathBuf {
+    config_dir().join(CONFIG_FILE)
+}
+
+/// what's remembered about how the app was left, like where the panes were divided
+pub fn state_file() -> PathBuf {
+    platform_dir("XDG_STATE_HOME", &[".local", "state"])
+        .join(APP_DIR)
+        .join(STATE_FILE)
+}
+
+/// expands a leading `~` to the home directory, so paths in the config file can be written the
+/// way they would be in a shell
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// `path` with the home directory written as `~`, shorter for messages
+pub fn tilde(path: &Path) -> String {
+    match home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+        Some(rest) => Path::new("~").join(rest).display().to_string(),
+        None => path.display().to_string(),
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    env_path(var)
+}
+
+fn env_path(var: &str) -> Option<PathBuf> {
+    env::var_os(var)
+        .filter(|val| !val.is_empty())
+        .map(PathBuf::from)
+}
+
+#[cfg(target_os = "macos")]
+fn platform_dir(_xdg_var: &str, _fallback: &[&str]) -> PathBuf {
+    home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("Library")
+        .join("Application Support")
+}
+
+#[cfg(windows)]
+fn platform_dir(_xdg_var: &str, _fallback: &[&str]) -> PathBuf {
+    env_path("APPDATA")
+        .or_else(|| home_dir().map(|home| home.join("AppData").join("Roaming")))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn platform_dir(xdg_var: &str, fallback: &[&str]) -> PathBuf {
+    env_path(xdg_var)
+        .or_else(|| home_dir().map(|home| fallback.iter().fold(home, |dir, part| dir.join(part))))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
@@ -0,0 +1,237 @@
+//! Narrowing the list and the stats to a period of time. `:period 7d`, `30d`, `month` and `year`
+//! pick the last week or month, this calendar month or this year; `:period <from> <to>` takes two
+//! days; `:period clear` goes back to all time. `P` in the list, or `:period pick`, opens a small
+//! calendar for picking the first and last day of a range.
+
+use chrono::{Datelike, Days, Local, Months, NaiveDate, Weekday};
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Style, Stylize},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::{
+    freshness::{parse_day, DAY_FMT},
+    App, Entry,
+};
+
+/// the days entries are narrowed to, both included
+#[derive(Debug, Clone)]
+pub struct Period {
+    from: NaiveDate,
+    to: NaiveDate,
+    /// e.g. "last 7 days", for the title
+    label: String,
+}
+
+impl Period {
+    fn new(from: NaiveDate, to: NaiveDate, label: String) -> Self {
+        Self { from, to, label }
+    }
+
+    /// from `from` to `to` in whichever order, labelled with the days
+    fn between(a: NaiveDate, b: NaiveDate) -> Self {
+        let (from, to) = (a.min(b), a.max(b));
+        let label = format!("{} to {}", from.format(DAY_FMT), to.format(DAY_FMT));
+        Self::new(from, to, label)
+    }
+
+    /// reads the preset after `:period`, relative to `today`
+    fn preset(name: &str, today: NaiveDate) -> Option<Self> {
+        let back = |days: u64, label: &str| {
+            let from = today.checked_sub_days(Days::new(days - 1))?;
+            Some(Self::new(from, today, label.to_string()))
+        };
+        match name {
+            "7d" | "week" => back(7, "last 7 days"),
+            "30d" => back(30, "last 30 days"),
+            "month" => Some(Self::new(
+                today.with_day(1)?,
+                today,
+                today.format("%B %Y").to_string(),
+            )),
+            "year" => Some(Self::new(
+                today.with_ordinal(1)?,
+                today,
+                today.format("%Y").to_string(),
+            )),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, day: NaiveDate) -> bool {
+        self.from <= day && day <= self.to
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// the calendar popup for picking a range
+#[derive(Debug)]
+pub struct RangePicker {
+    /// the highlighted day
+    cursor: NaiveDate,
+    /// the first day of the range, once it's been picked
+    start: Option<NaiveDate>,
+}
+
+impl App {
+    /// whether the entry was brewed in the period, or there isn't one
+    pub(crate) fn in_period(&self, entry: &Entry) -> bool {
+        self.state
+            .period
+            .as_ref()
+            .is_none_or(|p| p.contains(entry.dt_taken.date_naive()))
+    }
+
+    /// handles `:period <preset>|<from> <to>|pick|clear`, ignoring what it can't read
+    pub(crate) fn period_command(&mut self, first: Option<&str>, second: Option<&str>) {
+        let today = Local::now().date_naive();
+        let period = match (first, second) {
+            (None | Some("clear"), _) => None,
+            (Some("pick"), _) => {
+                self.open_range_picker();
+                return;
+            }
+            (Some(from), Some(to)) => match (parse_day(from), parse_day(to)) {
+                (Some(from), Some(to)) => Some(Period::between(from, to)),
+                _ => return,
+            },
+            (Some(preset), None) => match Period::preset(preset, today) {
+                Some(period) => Some(period),
+                None => return,
+            },
+        };
+        self.set_period(period);
+    }
+
+    fn set_period(&mut self, period: Option<Period>) {
+        self.state.period = period;
+        self.state.entry_list_state.select_first();
+    }
+
+    pub(crate) fn open_range_picker(&mut self) {
+        let cursor = self
+            .state
+            .period
+            .as_ref()
+            .map_or_else(|| Local::now().date_naive(), |p| p.from);
+        self.state.range_picker = Some(RangePicker {
+            cursor,
+            start: None,
+        });
+    }
+
+    /// h/l move a day, j/k a week and [/] a month; Enter picks the first day, then the last
+    pub(crate) fn handle_key_events_range_picker(&mut self, key_event: KeyEvent) {
+        let Some(picker) = &mut self.state.range_picker else {
+            return;
+        };
+        let c = picker.cursor;
+        let moved = match key_event.code {
+            KeyCode::Char('h') | KeyCode::Left => c.checked_sub_days(Days::new(1)),
+            KeyCode::Char('l') | KeyCode::Right => c.checked_add_days(Days::new(1)),
+            KeyCode::Char('k') | KeyCode::Up => c.checked_sub_days(Days::new(7)),
+            KeyCode::Char('j') | KeyCode::Down => c.checked_add_days(Days::new(7)),
+            KeyCode::Char('[') => c.checked_sub_months(Months::new(1)),
+            KeyCode::Char(']') => c.checked_add_months(Months::new(1)),
+            KeyCode::Enter => {
+                match picker.start {
+                    None => picker.start = Some(c),
+                    Some(start) => {
+                        self.state.range_picker = None;
+                        self.set_period(Some(Period::between(start, c)));
+                    }
+                }
+                return;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.state.range_picker = None;
+                return;
+            }
+            _ => None,
+        };
+        if let Some(day) = moved {
+            picker.cursor = day;
+        }
+    }
+
+    /// draws the calendar for the month of the highlighted day as a popup over `area`
+    pub(crate) fn render_range_picker(&self, area: Rect, buf: &mut Buffer) {
+        let Some(picker) = &self.state.range_picker else {
+            return;
+        };
+        let cursor = picker.cursor;
+        // until the last day is picked, the range runs to the highlighted day
+        let range = picker.start.map(|start| Period::between(start, cursor));
+        let first = cursor.with_day(1).unwrap_or(cursor);
+        let mut lines = vec![
+            Line::from(format!(" {:^20} ", first.format("%B %Y").to_string())).bold(),
+            Line::from(" Mo Tu We Th Fr Sa Su ").dark_gray(),
+        ];
+        let mut week: Vec<Span> = vec![" ".into()];
+        // blanks before the first, for the days of the week before it
+        let lead = first.weekday().num_days_from_monday() as usize;
+        week.extend((0..lead).map(|_| Span::from("   ")));
+        let mut day = first;
+        while day.month() == first.month() {
+            let style = if day == cursor {
+                Style::new().reversed()
+            } else if range.as_ref().is_some_and(|r| r.contains(day)) {
+                self.theme.selected
+            } else {
+                Style::new()
+            };
+            week.push(Span::styled(format!("{:>2}", day.day()), style));
+            week.push(" ".into());
+            if day.weekday() == Weekday::Sun {
+                lines.push(Line::from(std::mem::replace(&mut week, vec![" ".into()])));
+            }
+            let Some(next) = day.succ_opt() else {
+                break;
+            };
+            day = next;
+        }
+        if week.len() > 1 {
+            lines.push(Line::from(week));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(match picker.start {
+            None => " Pick the first day",
+            Some(_) => " Now pick the last day",
+        }));
+        let [popup] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup] = Layout::horizontal([Constraint::Length(24)])
+            .flex(Flex::Center)
+            .areas(popup);
+        let block = Block::bordered()
+            .title(" Period ")
+            .border_set(border::ROUNDED);
+        Clear.render(popup, buf);
+        Paragraph::new(lines).block(block).render(popup, buf);
+    }
+
+    pub(crate) fn range_picker_controls(&self) -> Line<'static> {
+        Line::from(vec![
+            " Controls:".into(),
+            " Day ".into(),
+            "<h/l>".fg(self.theme.accent).bold(),
+            " | Week ".into(),
+            "<j/k>".fg(self.theme.accent).bold(),
+            " | Month ".into(),
+            "<[/]>".fg(self.theme.accent).bold(),
+            " | Pick ".into(),
+            "<Enter>".fg(self.theme.accent).bold(),
+            " | Cancel ".into(),
+            "<Esc> ".fg(self.theme.accent).bold(),
+        ])
+    }
+}
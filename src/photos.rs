@@ -0,0 +1,135 @@
+//! Photos attached to an entry, like the bag label, the puck or the latte art. Only the paths are
+//! stored, the pictures stay wherever they are. Each one gets a row in the edit view, followed by
+//! an empty one for attaching another, and `o` opens the highlighted one in the system's viewer.
+
+use std::{
+    io,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{Event, KeyCode, KeyEvent},
+    layout::Rect,
+    style::Stylize,
+    text::Line,
+    widgets::{Paragraph, Widget},
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use crate::{fields::Field, paths, App, Entry, InputMode};
+
+/// tried in order, the first one that runs successfully wins
+const VIEWERS: &[&[&str]] = &[
+    &["open"],
+    &["xdg-open"],
+    // `start` takes the first quoted argument as a window title
+    &["cmd.exe", "/c", "start", ""],
+];
+
+/// opens the picture at `path` in the system's image viewer
+fn view(path: &str) -> io::Result<()> {
+    let path = paths::expand_home(Path::new(path));
+    if !path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} doesn't exist", path.display()),
+        ));
+    }
+    for cmd in VIEWERS {
+        let status = Command::new(cmd[0])
+            .args(&cmd[1..])
+            .arg(&path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        if status.is_ok_and(|s| s.success()) {
+            return Ok(());
+        }
+    }
+    Err(io::Error::other("no image viewer found"))
+}
+
+/// e.g. "Photo 2"
+fn photo_label(i: usize) -> String {
+    format!("{} {}", Field::Photo(i).label(), i + 1)
+}
+
+impl App {
+    /// starts typing the path of the photo in row `i`, which is a new one past the last
+    pub(crate) fn open_photo(&mut self, i: usize) {
+        let Some(entry) = self.edited_entry() else {
+            return;
+        };
+        let path = entry.photos.get(i).cloned().unwrap_or_default();
+        self.state.edit.input = Input::new(path);
+        self.state.edit.input_mode = InputMode::Editing;
+    }
+
+    pub(crate) fn handle_key_events_photo(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Enter => self.save_input(),
+            KeyCode::Esc => self.state.edit.input_mode = InputMode::Normal,
+            _ => {
+                _ = self.state.edit.input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    /// stores the typed path; an empty one takes the photo off the entry
+    pub(crate) fn save_photo(&mut self, i: usize) {
+        let path = self.state.edit.input.value().trim().to_string();
+        let Some(entry) = self.edited_entry_mut() else {
+            return;
+        };
+        match (i < entry.photos.len(), path.is_empty()) {
+            (true, true) => _ = entry.photos.remove(i),
+            (true, false) => entry.photos[i] = path,
+            (false, false) => entry.photos.push(path),
+            (false, true) => {}
+        }
+        self.field_saved();
+    }
+
+    /// opens the photo highlighted in the edit view, if it's one
+    pub(crate) fn view_selected_photo(&self) -> io::Result<()> {
+        if let Some(Field::Photo(i)) = self.selected_field()
+            && let Some(path) = self.edited_entry().and_then(|e| e.photos.get(i))
+        {
+            view(path)?;
+        }
+        Ok(())
+    }
+
+    /// whether the edit view has an attached photo highlighted
+    pub(crate) fn photo_selected(&self) -> bool {
+        matches!(self.selected_field(), Some(Field::Photo(i))
+            if self.edited_entry().is_some_and(|e| i < e.photos.len()))
+    }
+
+    /// the row for photo `i`, or for attaching another
+    pub(crate) fn photo_line(&self, i: usize, entry: &Entry) -> Line<'static> {
+        let path = entry.photos.get(i).map_or("-", String::as_str);
+        Line::from(format!("  {}: {path}", photo_label(i)))
+    }
+
+    /// the photo being typed, with the cursor shown
+    pub(crate) fn photo_input_line(&self, i: usize) -> Line<'static> {
+        self.input_line(&photo_label(i))
+    }
+
+    pub(crate) fn render_footer_photo(&self, area: Rect, buf: &mut Buffer) {
+        let controls = Line::from(vec![
+            " Controls:".into(),
+            " Save ".into(),
+            "<Enter>".fg(self.theme.accent).bold(),
+            " | Cancel ".into(),
+            "<Esc>".fg(self.theme.accent).bold(),
+            " | Clear the path to remove the photo ".into(),
+        ]);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
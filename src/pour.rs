@@ -0,0 +1,331 @@
+//! The pour-over assistant. `:brew` followed by the pours, e.g.
+//! `:brew bloom 45g @ 0:00, 150g @ 0:45, 250g @ 1:30`, or by the name of one of the config's
+//! `pour_schedules`, opens it over the entry being edited:
+//!
+//! ```json
+//! "pour_schedules": {"v60": "bloom 45g @ 0:00, 150g @ 0:45, 250g @ 1:30"}
+//! ```
+//!
+//! Each pour is the weight the scale should read once it's done, at the time it should start.
+//! Space starts the clock, then marks each pour as it's actually started and finally the end of
+//! the drawdown. The terminal bell rings as each pour is due. Once done, what was actually
+//! poured when is added to the entry's notes, and the pours, bloom and total time are filled in.
+
+use std::{io, time::Instant};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::{
+    json::{invalid, Value},
+    timer::{bell, large},
+    App,
+};
+
+#[derive(Debug, Clone)]
+struct Pour {
+    /// e.g. "bloom", when the pour has one
+    label: Option<String>,
+    /// what the scale should read once it's done
+    grams: f64,
+    /// seconds from the start
+    at: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct PourSchedule {
+    pours: Vec<Pour>,
+}
+
+impl PourSchedule {
+    /// reads pours like `bloom 45g @ 0:00, 150g @ 0:45`, saying what's wrong if it can't
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let pours = text
+            .split(',')
+            .map(parse_pour)
+            .collect::<Result<Vec<_>, _>>()?;
+        if pours.is_empty() {
+            return Err(String::from("there are no pours"));
+        }
+        if pours.windows(2).any(|w| w[1].at < w[0].at) {
+            return Err(String::from(
+                "the pours should be in the order they're poured",
+            ));
+        }
+        Ok(Self { pours })
+    }
+}
+
+/// reads one pour, `[label] <grams>[g] @ <m:ss>`
+fn parse_pour(text: &str) -> Result<Pour, String> {
+    let Some((what, at)) = text.split_once('@') else {
+        return Err(format!("`{}` needs an `@` and a time", text.trim()));
+    };
+    let at = parse_time(at.trim()).ok_or_else(|| format!("`{}` isn't a time", at.trim()))?;
+    let mut words: Vec<&str> = what.split_whitespace().collect();
+    // "150 g" as well as "150g"
+    if words.last() == Some(&"g") {
+        words.pop();
+    }
+    let Some(grams) = words.pop() else {
+        return Err(format!("`{}` needs a weight", text.trim()));
+    };
+    let grams = grams
+        .trim_end_matches('g')
+        .parse()
+        .map_err(|_| format!("`{grams}` isn't a weight"))?;
+    let label = (!words.is_empty()).then(|| words.join(" "));
+    Ok(Pour { label, grams, at })
+}
+
+/// `m:ss` or a number of seconds
+fn parse_time(text: &str) -> Option<f64> {
+    match text.split_once(':') {
+        Some((m, s)) => Some(m.parse::<f64>().ok()? * 60.0 + s.parse::<f64>().ok()?),
+        None => text.parse().ok(),
+    }
+}
+
+/// as `m:ss`
+fn format_time(seconds: f64) -> String {
+    let seconds = seconds.max(0.0).round() as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// reads the config's `pour_schedules`, an object of schedules by name
+pub fn schedules_from_config(value: &Value) -> io::Result<Vec<(String, PourSchedule)>> {
+    match value {
+        Value::Null => Ok(Vec::new()),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(name, text)| {
+                let text = text
+                    .as_str()
+                    .ok_or_else(|| invalid(format!("pour schedule `{name}` should be a string")))?;
+                let schedule = PourSchedule::parse(text)
+                    .map_err(|e| invalid(format!("pour schedule `{name}`: {e}")))?;
+                Ok((name.clone(), schedule))
+            })
+            .collect(),
+        _ => Err(invalid("`pour_schedules` should be an object")),
+    }
+}
+
+/// a brew being guided through its pours
+#[derive(Debug)]
+pub struct BrewAssistant {
+    schedule: PourSchedule,
+    /// when the clock was started, once it has been
+    started: Option<Instant>,
+    /// the seconds at which each pour was actually started, as far as the brew has got
+    marks: Vec<f64>,
+    /// how many pours the bell has rung for
+    rung: usize,
+}
+
+impl BrewAssistant {
+    fn elapsed(&self) -> f64 {
+        self.started.map_or(0.0, |s| s.elapsed().as_secs_f64())
+    }
+
+    /// what was poured when, for the notes
+    fn log(&self, done: f64) -> String {
+        let pours: Vec<String> = self
+            .schedule
+            .pours
+            .iter()
+            .zip(&self.marks)
+            .map(|(pour, &mark)| {
+                let mut text = match &pour.label {
+                    Some(label) => format!("{label} {:.0} g", pour.grams),
+                    None => format!("{:.0} g", pour.grams),
+                };
+                text.push_str(&format!(" at {}", format_time(mark)));
+                if format_time(mark) != format_time(pour.at) {
+                    text.push_str(&format!(" (planned {})", format_time(pour.at)));
+                }
+                text
+            })
+            .collect();
+        format!("Pours: {}; done at {}", pours.join(", "), format_time(done))
+    }
+}
+
+impl App {
+    /// handles `:brew <schedule name>|<pours>` while an entry is being edited
+    pub(crate) fn brew_command(&mut self, args: &str) -> Result<(), String> {
+        if self.edited_entry().is_none() {
+            return Err(String::from("open an entry to brew it"));
+        }
+        let args = args.trim();
+        let schedule = match self.config.pour_schedules.iter().find(|(n, _)| n == args) {
+            Some((_, schedule)) => schedule.clone(),
+            None => PourSchedule::parse(args)?,
+        };
+        self.state.brew = Some(BrewAssistant {
+            schedule,
+            started: None,
+            marks: Vec::new(),
+            rung: 0,
+        });
+        Ok(())
+    }
+
+    /// Space starts the clock, marks the next pour or the end of the drawdown; Enter ends the brew
+    /// early, and Esc drops it without logging anything
+    pub(crate) fn handle_key_events_brew(&mut self, key_event: KeyEvent) {
+        let Some(brew) = &mut self.state.brew else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Char(' ') if brew.started.is_none() => {
+                brew.started = Some(Instant::now());
+                brew.marks.push(0.0);
+            }
+            KeyCode::Char(' ') if brew.marks.len() < brew.schedule.pours.len() => {
+                brew.marks.push(brew.elapsed());
+            }
+            KeyCode::Char(' ') | KeyCode::Enter if brew.started.is_some() => self.finish_brew(),
+            KeyCode::Esc => self.state.brew = None,
+            _ => {}
+        }
+    }
+
+    /// logs the brew into the edited entry's notes, filling in its pours, bloom and time
+    fn finish_brew(&mut self) {
+        let Some(brew) = self.state.brew.take() else {
+            return;
+        };
+        let done = brew.elapsed();
+        let log = brew.log(done);
+        let Some(entry) = self.edited_entry_mut() else {
+            return;
+        };
+        if !entry.notes.is_empty() {
+            entry.notes.push('\n');
+        }
+        entry.notes.push_str(&log);
+        entry.pours = brew.marks.len() as f64;
+        entry.duration = (done * 10.0).round() / 10.0;
+        if brew.schedule.pours[0]
+            .label
+            .as_deref()
+            .is_some_and(|l| l.eq_ignore_ascii_case("bloom"))
+            && let Some(&second) = brew.marks.get(1)
+        {
+            entry.bloom_time = second.round();
+        }
+        self.field_saved();
+    }
+
+    /// rings the bell once for each pour whose time has come
+    pub(crate) fn ring_due_pours(&mut self) {
+        let Some(brew) = &mut self.state.brew else {
+            return;
+        };
+        if brew.started.is_none() {
+            return;
+        }
+        let elapsed = brew.elapsed();
+        let due = brew
+            .schedule
+            .pours
+            .iter()
+            .filter(|p| p.at <= elapsed)
+            .count();
+        if due > brew.rung {
+            brew.rung = due;
+            bell();
+            let pour = &brew.schedule.pours[due - 1];
+            let text = match &pour.label {
+                Some(label) => format!("Pour: {label}, up to {:.0} g", pour.grams),
+                None => format!("Pour up to {:.0} g", pour.grams),
+            };
+            self.info(text);
+        }
+    }
+
+    /// draws the clock and the pours as a popup over `area`
+    pub(crate) fn render_brew(&self, area: Rect, buf: &mut Buffer) {
+        let Some(brew) = &self.state.brew else {
+            return;
+        };
+        let elapsed = brew.elapsed();
+        let mut lines = vec![Line::from("")];
+        lines.extend(
+            large(&format_time(elapsed))
+                .into_iter()
+                .map(|row| Line::from(row).yellow().centered()),
+        );
+        lines.push(Line::from(""));
+        let current = brew.marks.len();
+        for (i, pour) in brew.schedule.pours.iter().enumerate() {
+            let label = pour.label.as_deref().unwrap_or("pour");
+            let text = format!(
+                "{label:<10} to {:>4.0} g at {}",
+                pour.grams,
+                format_time(pour.at)
+            );
+            lines.push(match brew.marks.get(i) {
+                Some(&mark) => {
+                    Line::from(format!(" ✓ {text}  ({})", format_time(mark))).dark_gray()
+                }
+                None if i == current => Line::from(format!(" → {text}")).bold(),
+                None => Line::from(format!("   {text}")),
+            });
+        }
+        lines.push(Line::from(""));
+        lines.push(match brew.schedule.pours.get(current) {
+            _ if brew.started.is_none() => {
+                Line::from(" Space starts the clock").fg(self.theme.accent)
+            }
+            Some(pour) if pour.at > elapsed => Line::from(format!(
+                " Pour to {:.0} g in {}",
+                pour.grams,
+                format_time(pour.at - elapsed)
+            ))
+            .fg(self.theme.accent),
+            Some(pour) => Line::from(format!(" Pour to {:.0} g now", pour.grams))
+                .yellow()
+                .bold(),
+            None => Line::from(" Space when the drawdown is done").fg(self.theme.accent),
+        });
+        let [popup] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup] = Layout::horizontal([Constraint::Length(44)])
+            .flex(Flex::Center)
+            .areas(popup);
+        let block = Block::bordered()
+            .title(" Brew ")
+            .border_set(border::ROUNDED);
+        Clear.render(popup, buf);
+        Paragraph::new(lines).block(block).render(popup, buf);
+    }
+
+    pub(crate) fn render_footer_brew(&self, area: Rect, buf: &mut Buffer) {
+        let started = self
+            .state
+            .brew
+            .as_ref()
+            .is_some_and(|b| b.started.is_some());
+        let controls = Line::from(vec![
+            " Controls:".into(),
+            if started { " Next pour " } else { " Start " }.into(),
+            "<Space>".fg(self.theme.accent).bold(),
+            " | Done ".into(),
+            "<Enter>".fg(self.theme.accent).bold(),
+            " | Cancel ".into(),
+            "<Esc> ".fg(self.theme.accent).bold(),
+        ]);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
@@ -0,0 +1,114 @@
+//! Opening a new entry with some of it already filled in, as in `:new coffee=FSL28 dose=18`,
+//! which is what an alias of one's usual shot stands for, see [`crate::aliases`]. What isn't given
+//! comes from the last entry like it does for `:new` on its own.
+//!
+//! - `coffee=`, `grinder=`, `brewer=`, `water=` and `basket=` followed by part of the name,
+//!   ignoring case; quote names with spaces in them, like `coffee="La Esperanza"`
+//! - `method=` followed by the start of the method, like `pour`
+//! - `grind=`, `dose=`, `yield=`, `temperature=` and `time=` followed by a number, the weights
+//!   and temperature in the units they're shown in
+
+use uuid::Uuid;
+
+use crate::{catalog::Kind, fields::METHODS, App, Entry};
+
+/// what can be given to `:new`, in the order completion offers them
+pub const PRESETS: [&str; 11] = [
+    "coffee",
+    "grinder",
+    "brewer",
+    "water",
+    "basket",
+    "method",
+    "grind",
+    "dose",
+    "yield",
+    "temperature",
+    "time",
+];
+
+/// the kind of catalog item a preset names, if it names one
+pub fn preset_kind(field: &str) -> Option<Kind> {
+    match field {
+        "coffee" => Some(Kind::Coffee),
+        "grinder" => Some(Kind::Grinder),
+        "brewer" => Some(Kind::Brewer),
+        "water" => Some(Kind::Water),
+        "basket" => Some(Kind::Basket),
+        _ => None,
+    }
+}
+
+fn number(field: &str, value: &str) -> Result<f64, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite() && *n >= 0.0)
+        .ok_or_else(|| format!("{field}= takes a number, not {value}"))
+}
+
+impl App {
+    /// the item of `kind` whose name has `part` in it; a name that's all of `part` wins over
+    /// others, and archived coffees are left out
+    fn find_item(&self, kind: Kind, part: &str) -> Result<Uuid, String> {
+        let part = part.to_lowercase();
+        let names = self.catalog_names(kind);
+        let matching: Vec<usize> = (0..names.len())
+            .filter(|&i| !self.catalog_archived(kind, i))
+            .filter(|&i| names[i].to_lowercase().contains(&part))
+            .collect();
+        let exact = matching.iter().find(|&&i| names[i].to_lowercase() == part);
+        match (exact, &matching[..]) {
+            (Some(&i), _) | (None, &[i]) => Ok(self.catalog_uuid(kind, i)),
+            (None, []) => Err(format!("No {} is called {part}", kind.singular())),
+            (None, _) => Err(format!(
+                "More than one {} is called {part}, say more of the name",
+                kind.singular()
+            )),
+        }
+    }
+
+    /// fills in one `field=value` of the new `entry`
+    fn apply_preset(&self, entry: &mut Entry, preset: &str) -> Result<(), String> {
+        let Some((field, value)) = preset.split_once('=') else {
+            return Err(format!("Give :new fields like dose=18, not {preset}"));
+        };
+        let field = field.to_lowercase();
+        if let Some(kind) = preset_kind(&field) {
+            let uuid = self.find_item(kind, value)?;
+            kind.set_id(entry, uuid);
+            return Ok(());
+        }
+        match field.as_str() {
+            "method" => {
+                let value = value.to_lowercase();
+                entry.method = METHODS
+                    .into_iter()
+                    .find(|m| m.key() == value || m.label().to_lowercase().starts_with(&value))
+                    .ok_or_else(|| format!("No brew method is called {value}"))?;
+            }
+            "grind" => entry.grind_setting = number(&field, value)?,
+            "dose" => entry.dose = self.config.weight_unit.to_grams(number(&field, value)?),
+            "yield" => entry.output = self.config.weight_unit.to_grams(number(&field, value)?),
+            "temperature" => {
+                let degrees = number(&field, value)?;
+                entry.temperature_c = Some(self.config.temperature_unit.to_celsius(degrees));
+            }
+            "time" => entry.duration = number(&field, value)?,
+            _ => {
+                return Err(format!(":new fills in {}, not {field}", PRESETS.join(", ")));
+            }
+        }
+        Ok(())
+    }
+
+    /// `:new` with `presets` like `dose=18` filled in, opening nothing if one of them is wrong
+    pub(crate) fn new_command(&mut self, presets: &[&str]) -> Result<(), String> {
+        let mut entry = self.blank_entry();
+        for preset in presets {
+            self.apply_preset(&mut entry, preset)?;
+        }
+        self.open_new_entry(entry);
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+//! Quick-logging a repeat shot. `.` on the home screen or in the list copies the most recent entry,
+//! of the coffee being brewed, and asks for just its dose, yield and time in a small popup. Enter
+//! goes on to the next of them, keeping the last shot's value when nothing was typed, and adds the
+//! shot after the time. It doesn't go on while what's typed isn't a number.
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{Event, KeyCode, KeyEvent},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use crate::{fields::Field, number_error, weight::WeightUnit, App, Entry, SELECTED_SYMBOL};
+
+/// what's asked for, in this order
+const QUICK_FIELDS: [Field; 3] = [Field::Dose, Field::Output, Field::Duration];
+
+#[derive(Debug)]
+pub struct QuickLog {
+    entry: Entry,
+    /// which of [`QUICK_FIELDS`] is being typed
+    field: usize,
+    input: Input,
+}
+
+impl QuickLog {
+    /// what's wrong with what's typed, while something is
+    fn error(&self) -> Option<&'static str> {
+        number_error(self.input.value().trim())
+    }
+
+    /// writes what was typed, in `unit` for the weights, into the entry; an empty input keeps the
+    /// last shot's value
+    fn keep(&mut self, unit: WeightUnit) {
+        if let Ok(val) = self.input.value().trim().parse::<f64>() {
+            let field = QUICK_FIELDS[self.field];
+            let val = match field {
+                Field::Dose | Field::Output => unit.to_grams(val),
+                _ => val,
+            };
+            field.set_number(&mut self.entry, val);
+        }
+        self.input = Input::default();
+    }
+}
+
+impl App {
+    /// opens the quick log on a copy of the most recent entry, if there is one
+    pub(crate) fn open_quick_log(&mut self) {
+        let Some(last) = self.data.entries.iter().max_by_key(|e| e.dt_taken) else {
+            return;
+        };
+        self.state.quick_log = Some(QuickLog {
+            entry: last.again(),
+            field: 0,
+            input: Input::default(),
+        });
+    }
+
+    /// Enter and Tab go on to the next field, adding the shot after the last; Shift-Tab goes back
+    /// and Esc drops the shot
+    pub(crate) fn handle_key_events_quick_log(&mut self, key_event: KeyEvent) {
+        let unit = self.config.weight_unit;
+        let Some(quick) = &mut self.state.quick_log else {
+            return;
+        };
+        match key_event.code {
+            // the field stays until it's a number, saying what's wrong meanwhile
+            KeyCode::Enter | KeyCode::Tab | KeyCode::BackTab if quick.error().is_some() => {}
+            KeyCode::Enter | KeyCode::Tab => {
+                quick.keep(unit);
+                if quick.field + 1 < QUICK_FIELDS.len() {
+                    quick.field += 1;
+                } else {
+                    self.add_quick_log();
+                }
+            }
+            KeyCode::BackTab => {
+                quick.keep(unit);
+                quick.field = quick.field.saturating_sub(1);
+            }
+            KeyCode::Esc => self.state.quick_log = None,
+            _ => {
+                _ = quick.input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    pub(crate) fn add_quick_log(&mut self) {
+        let Some(quick) = self.state.quick_log.take() else {
+            return;
+        };
+        self.use_beans(quick.entry.coffee_id, quick.entry.dose);
+        self.data.entries.push(quick.entry);
+        self.mark_changed();
+        self.select_entry(self.data.entries.len() - 1);
+        self.suggest();
+    }
+
+    /// draws the three fields as a popup over `area`
+    pub(crate) fn render_quick_log(&self, area: Rect, buf: &mut Buffer) {
+        let Some(quick) = &self.state.quick_log else {
+            return;
+        };
+        let mut lines = vec![Line::from("")];
+        for (i, field) in QUICK_FIELDS.into_iter().enumerate() {
+            let name = format!("{:<10}", format!("{}:", field.label()));
+            let number = field.number(&quick.entry).unwrap_or(0.0);
+            let last = match field {
+                Field::Dose | Field::Output => self.weight(number),
+                _ => format!("{number:.1}{}", field.unit()),
+            };
+            lines.push(if i != quick.field {
+                Line::from(format!("   {name}{last}"))
+            } else if quick.input.value().is_empty() {
+                // what the last shot had, kept unless something else is typed
+                Line::from(vec![
+                    format!("{SELECTED_SYMBOL} {name}").bold(),
+                    last.dark_gray(),
+                ])
+            } else if quick.error().is_some() {
+                Line::from(vec![
+                    format!("{SELECTED_SYMBOL} {name}").bold(),
+                    quick.input.value().to_string().red(),
+                ])
+            } else {
+                Line::from(vec![
+                    format!("{SELECTED_SYMBOL} {name}").bold(),
+                    quick.input.value().to_string().fg(self.theme.accent),
+                    self.field_unit(field).into(),
+                ])
+            });
+            if let Some(error) = quick.error().filter(|_| i == quick.field) {
+                lines.push(Line::from(format!("   {error}").red()));
+            }
+        }
+        lines.push(Line::from(""));
+        let [popup] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup] = Layout::horizontal([Constraint::Length(36)])
+            .flex(Flex::Center)
+            .areas(popup);
+        let block = Block::bordered()
+            .title(format!(" Again: {} ", self.coffee_name(&quick.entry)))
+            .border_set(border::ROUNDED);
+        Clear.render(popup, buf);
+        Paragraph::new(lines).block(block).render(popup, buf);
+    }
+
+    pub(crate) fn render_footer_quick_log(&self, area: Rect, buf: &mut Buffer) {
+        let controls = Line::from(vec![
+            " Controls:".into(),
+            " Next ".into(),
+            "<Enter/Tab>".fg(self.theme.accent).bold(),
+            " | Back ".into(),
+            "<Shift-Tab>".fg(self.theme.accent).bold(),
+            " | Cancel ".into(),
+            "<Esc> ".fg(self.theme.accent).bold(),
+        ]);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
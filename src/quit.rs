@@ -0,0 +1,112 @@
+//! Quitting. `q` and `:q` quit straight away when everything has been written, and otherwise ask
+//! first: while there are changes autosave hasn't written yet, say because writing failed, or a
+//! new entry is being filled in that isn't in the log yet. The prompt saves, adding the new entry,
+//! discards, or goes back. `:wq` saves and quits without asking, and `:q!` quits without writing.
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::App;
+
+impl App {
+    /// whether quitting now would lose something
+    pub(crate) fn unsaved(&self) -> bool {
+        self.last_change.is_some()
+            || self.state.new_entry.is_some()
+            || self.state.quick_log.is_some()
+    }
+
+    /// quits, asking first if something would be lost
+    pub(crate) fn quit(&mut self) {
+        if self.unsaved() {
+            self.state.confirm_quit = true;
+        } else {
+            self.exit = true;
+        }
+    }
+
+    /// adds the entry being filled in, writes everything out and quits, unless writing fails
+    pub(crate) fn save_and_quit(&mut self) {
+        self.state.confirm_quit = false;
+        if self.state.quick_log.is_some() {
+            self.add_quick_log();
+        }
+        self.add_new_entry();
+        match self.save().and_then(|_| self.commit_data()) {
+            Ok(()) => self.exit = true,
+            Err(e) => self.error(format!("Couldn't save: {e}")),
+        }
+    }
+
+    /// quits without writing anything that hasn't been yet
+    pub(crate) fn discard_and_quit(&mut self) {
+        self.discard = true;
+        self.exit = true;
+    }
+
+    pub(crate) fn handle_key_events_confirm_quit(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('s') | KeyCode::Char('y') | KeyCode::Enter => self.save_and_quit(),
+            KeyCode::Char('d') => self.discard_and_quit(),
+            KeyCode::Char('c') | KeyCode::Char('n') | KeyCode::Esc => {
+                self.state.confirm_quit = false
+            }
+            _ => {}
+        }
+    }
+
+    pub(crate) fn render_confirm_quit(&self, area: Rect, buf: &mut Buffer) {
+        if !self.state.confirm_quit {
+            return;
+        }
+        let what = if self.state.new_entry.is_some() || self.state.quick_log.is_some() {
+            "  The new entry hasn't been added to the log yet."
+        } else {
+            "  Some changes haven't been written yet."
+        };
+        let lines = vec![
+            Line::from(what),
+            Line::from(""),
+            Line::from(vec![
+                "  Save ".into(),
+                "s".fg(self.theme.accent).bold(),
+                " / Discard ".into(),
+                "d".fg(self.theme.accent).bold(),
+                " / Cancel ".into(),
+                "c".fg(self.theme.accent).bold(),
+            ]),
+        ];
+        let [popup] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup] = Layout::horizontal([Constraint::Max(60)])
+            .flex(Flex::Center)
+            .areas(popup);
+        let block = Block::bordered()
+            .title(" Quit ")
+            .border_set(border::ROUNDED);
+        Clear.render(popup, buf);
+        Paragraph::new(lines).block(block).render(popup, buf);
+    }
+
+    pub(crate) fn render_footer_confirm_quit(&self, area: Rect, buf: &mut Buffer) {
+        let controls = Line::from(vec![
+            " Controls:".into(),
+            " Save and quit ".into(),
+            "<s>".fg(self.theme.accent).bold(),
+            " | Quit without saving ".into(),
+            "<d>".fg(self.theme.accent).bold(),
+            " | Cancel ".into(),
+            "<c/Esc> ".fg(self.theme.accent).bold(),
+        ]);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
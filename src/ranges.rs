@@ -0,0 +1,134 @@
+//! Commands on a range of the entry list, see [`crate::command`]: `:42` goes to the 42nd entry
+//! listed, `:1,5 delete` deletes the first five and `:%export csv <path>` writes every listed
+//! entry to a CSV file. The rows are the list's as it's shown, filtered and sorted, so a range
+//! covers what's on screen. Deleting asks first, like `d` in the list does.
+
+use std::ops::RangeInclusive;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::{
+    command::{Address, Range},
+    App, Phase,
+};
+
+impl App {
+    /// the rows of the list `range` covers, counting from 0
+    fn range_rows(&self, range: Range) -> Result<RangeInclusive<usize>, String> {
+        let listed = self.listed_entries().len();
+        let row = |address| match address {
+            Address::Row(0) => Err(String::from("the rows count from 1")),
+            Address::Row(row) if row > listed => Err(match listed {
+                1 => String::from("only 1 entry is listed"),
+                listed => format!("only {listed} entries are listed"),
+            }),
+            Address::Row(row) => Ok(row - 1),
+            Address::Selected => self
+                .state
+                .entry_list_state
+                .selected()
+                .filter(|&row| row < listed)
+                .ok_or_else(|| String::from("no entry is selected")),
+            Address::Last => listed
+                .checked_sub(1)
+                .ok_or_else(|| String::from("no entries are listed")),
+        };
+        let (from, to) = (row(range.from)?, row(range.to)?);
+        // `:5,1` means the same as `:1,5`
+        Ok(from.min(to)..=from.max(to))
+    }
+
+    /// the entries `range` covers, as indices into the entries, in the order they're listed;
+    /// all of the listed ones without a range
+    pub(crate) fn range_entries(&self, range: Option<Range>) -> Result<Vec<usize>, String> {
+        let listed = self.listed_entries();
+        match range {
+            Some(range) => Ok(listed[self.range_rows(range)?].to_vec()),
+            None => Ok(listed),
+        }
+    }
+
+    /// `:42` on its own: selects the row in the list, the last of the range if it's one
+    pub(crate) fn goto_row(&mut self, range: Range) -> Result<(), String> {
+        let rows = self.range_rows(range)?;
+        self.phase = Phase::ListView;
+        self.state.entry_list_state.select(Some(*rows.end()));
+        Ok(())
+    }
+
+    /// `:delete`: asks to delete the entries in `range`, or the selected one without it
+    pub(crate) fn delete_range(&mut self, range: Option<Range>) -> Result<(), String> {
+        let rows = self.range_rows(range.unwrap_or(Range {
+            from: Address::Selected,
+            to: Address::Selected,
+        }))?;
+        self.phase = Phase::ListView;
+        if rows.start() == rows.end() {
+            // the same as `d` on it
+            self.state.entry_list_state.select(Some(*rows.start()));
+            self.state.confirm_delete = true;
+        } else {
+            self.state.confirm_range = Some(rows);
+        }
+        Ok(())
+    }
+
+    /// deletes the entries at `rows` of the list, in one step `u` undoes
+    pub(crate) fn delete_rows(&mut self, rows: RangeInclusive<usize>) {
+        let mut entries = self.listed_entries()[rows.clone()].to_vec();
+        entries.sort_unstable_by(|a, b| b.cmp(a));
+        for &i in &entries {
+            self.data.entries.remove(i);
+        }
+        self.mark_changed();
+        // the entries after them moved up, so the marks may be on others now
+        self.state.compare_marks.clear();
+        self.phase = Phase::ListView;
+        // the entry that took the place of the first deleted, or the new last one
+        let listed = self.listed_entries().len();
+        let row = (*rows.start()).min(listed.saturating_sub(1));
+        self.state
+            .entry_list_state
+            .select((listed > 0).then_some(row));
+        self.info(format!("Deleted {} entries, u undoes it", entries.len()));
+    }
+
+    /// asks whether to delete the rows `:delete` was given, over the middle of `area`
+    pub(crate) fn render_confirm_range(&self, area: Rect, buf: &mut Buffer) {
+        let Some(rows) = &self.state.confirm_range else {
+            return;
+        };
+        let lines = vec![
+            Line::from(format!(
+                "  Rows {} to {} of the list",
+                rows.start() + 1,
+                rows.end() + 1
+            )),
+            Line::from(""),
+            Line::from(vec![
+                format!("  Delete these {} entries? ", rows.end() - rows.start() + 1).into(),
+                "y".fg(self.theme.accent).bold(),
+                "/".into(),
+                "n".fg(self.theme.accent).bold(),
+            ]),
+        ];
+        let [popup] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup] = Layout::horizontal([Constraint::Max(60)])
+            .flex(Flex::Center)
+            .areas(popup);
+        let block = Block::bordered()
+            .title(" Delete entries ")
+            .border_set(border::ROUNDED);
+        Clear.render(popup, buf);
+        Paragraph::new(lines).block(block).render(popup, buf);
+    }
+}
@@ -0,0 +1,107 @@
+//! Reference shots: the one shot of a coffee to measure the others against, usually the best one
+//! pulled so far. `B` in the list marks the selected entry as its coffee's reference, taking the
+//! mark off the one before, or takes it off again. The other entries of that coffee then show
+//! beside their fields how their dose, grind, time and ratio differ from the reference's.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::{fields::Field, targets::deviation, App, Entry};
+
+/// how wide the comparison with the reference is beside the edit view's fields
+pub const REFERENCE_WIDTH: u16 = 40;
+
+impl App {
+    /// marks the selected entry as its coffee's reference shot, or unmarks it if it already is
+    pub(crate) fn toggle_reference(&mut self) {
+        let Some(i) = self.selected_entry() else {
+            return;
+        };
+        let coffee_id = self.data.entries[i].coffee_id;
+        let marked = !self.data.entries[i].reference;
+        for entry in self
+            .data
+            .entries
+            .iter_mut()
+            .filter(|e| e.coffee_id == coffee_id)
+        {
+            entry.reference = false;
+        }
+        self.data.entries[i].reference = marked;
+        self.mark_changed();
+    }
+
+    /// the reference shot of the entry's coffee, if it has one
+    pub(crate) fn reference_for(&self, entry: &Entry) -> Option<&Entry> {
+        self.data
+            .entries
+            .iter()
+            .find(|e| e.reference && e.coffee_id == entry.coffee_id)
+    }
+
+    /// the entry's dose, grind, time and ratio beside how much they're off the reference's
+    pub(crate) fn render_reference_comparison(&self, entry: &Entry, area: Rect, buf: &mut Buffer) {
+        let Some(reference) = self.reference_for(entry) else {
+            return;
+        };
+        let block = Block::bordered()
+            .title(" Vs reference shot ")
+            .border_set(border::ROUNDED);
+        if entry.reference {
+            let lines = vec![
+                Line::from(""),
+                Line::from(format!(
+                    "  This is {}'s reference shot.",
+                    self.coffee_name(entry)
+                )),
+            ];
+            Paragraph::new(lines).block(block).render(area, buf);
+            return;
+        }
+        let row = |label: &str, value: String, by: f64, unit: &str| {
+            Line::from(vec![
+                format!("  {label:<8}{value:<10}").into(),
+                deviation(by, unit).dark_gray(),
+            ])
+        };
+        let ratio = |e: &Entry| (e.dose > 0.0).then(|| e.output / e.dose);
+        let unit = self.config.weight_unit;
+        let mut lines = vec![
+            Line::from(format!("  Pulled {}", self.date_text(reference.dt_taken))),
+            Line::from(""),
+            row(
+                "Dose",
+                self.weight(entry.dose),
+                unit.in_unit(entry.dose - reference.dose),
+                unit.symbol(),
+            ),
+            row(
+                "Grind",
+                format!("{:.1}", entry.grind_setting),
+                entry.grind_setting - reference.grind_setting,
+                "",
+            ),
+            row(
+                "Time",
+                format!("{:.0}{}", entry.duration, Field::Duration.unit()),
+                entry.duration - reference.duration,
+                "s",
+            ),
+        ];
+        if let (Some(ratio), Some(reference)) = (ratio(entry), ratio(reference)) {
+            lines.push(row(
+                "Ratio",
+                format!("{ratio:.1} / 1"),
+                ratio - reference,
+                "",
+            ));
+        }
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
@@ -0,0 +1,168 @@
+//! A summary of the last week's brewing: `:report week` shows it in a popup, where `y` copies it as
+//! Markdown, and `:report week <file>` writes the Markdown to a file instead.
+
+use std::{fmt::Write as _, fs, io};
+
+use chrono::{Days, Local, NaiveDate};
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::{clipboard, freshness::DAY_FMT, paths, tasting, App};
+
+/// how many days a week's report takes in, today included
+const WEEK_DAYS: u64 = 7;
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    from: NaiveDate,
+    to: NaiveDate,
+    /// each figure's label and value, in the order they're shown
+    rows: Vec<(&'static str, String)>,
+}
+
+impl Report {
+    fn title(&self) -> String {
+        format!(
+            "Week of {} to {}",
+            self.from.format(DAY_FMT),
+            self.to.format(DAY_FMT)
+        )
+    }
+
+    pub fn markdown(&self) -> String {
+        let mut out = format!("# {}\n\n", self.title());
+        for (label, value) in &self.rows {
+            _ = writeln!(out, "- **{label}:** {value}");
+        }
+        out
+    }
+}
+
+impl App {
+    /// the figures for the days from `from` to `to`, both included
+    fn report(&self, from: NaiveDate, to: NaiveDate) -> Report {
+        let within = |day: NaiveDate| from <= day && day <= to;
+        let entries: Vec<_> = self
+            .data
+            .entries
+            .iter()
+            .filter(|e| within(e.dt_taken.date_naive()))
+            .collect();
+        let grams = entries.iter().fold(0.0, |total, e| total + e.dose);
+        let ratios: Vec<f64> = entries
+            .iter()
+            .filter(|e| e.dose > 0.0)
+            .map(|e| e.output / e.dose)
+            .collect();
+        let ratio = match ratios.len() {
+            0 => String::from("-"),
+            n => format!("1:{:.1}", ratios.iter().sum::<f64>() / n as f64),
+        };
+        // the latest of the highest rated
+        let best = entries
+            .iter()
+            .filter(|e| e.rating.is_some())
+            .max_by_key(|e| (e.rating, e.dt_taken));
+        let best = best.map_or(String::from("none rated"), |e| {
+            format!(
+                "{} on {} {}",
+                self.coffee_name(e),
+                e.dt_taken.format(DAY_FMT),
+                tasting::stars(e.rating, "")
+            )
+        });
+        let opened: Vec<&str> = self
+            .data
+            .coffees
+            .iter()
+            .filter(|c| c.open_date.is_some_and(within))
+            .map(|c| c.name.as_str())
+            .collect();
+        let opened = if opened.is_empty() {
+            String::from("none")
+        } else {
+            opened.join(", ")
+        };
+        Report {
+            from,
+            to,
+            rows: vec![
+                ("Shots", entries.len().to_string()),
+                ("Coffee used", format!("{grams:.1} g")),
+                ("Average ratio", ratio),
+                ("Best shot", best),
+                ("Coffees opened", opened),
+            ],
+        }
+    }
+
+    /// handles `:report week [file]`
+    pub(crate) fn report_command(&mut self, args: &[&str]) -> io::Result<()> {
+        let Some((&"week", path)) = args.split_first() else {
+            return Ok(());
+        };
+        let to = Local::now().date_naive();
+        let from = to.checked_sub_days(Days::new(WEEK_DAYS - 1)).unwrap_or(to);
+        let report = self.report(from, to);
+        if path.is_empty() {
+            self.state.report = Some(report);
+            return Ok(());
+        }
+        let path = paths::expand_home(path.join(" ").as_ref());
+        fs::write(path, report.markdown())
+    }
+
+    pub(crate) fn handle_key_events_report(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('y') => {
+                if let Some(report) = &self.state.report {
+                    let copied = clipboard::copy(&report.markdown());
+                    self.report_result(copied, "Copied the report");
+                }
+            }
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => self.state.report = None,
+            _ => {}
+        }
+    }
+
+    pub(crate) fn render_report(&self, area: Rect, buf: &mut Buffer) {
+        let Some(report) = &self.state.report else {
+            return;
+        };
+        let lines: Vec<Line> = report
+            .rows
+            .iter()
+            .map(|(label, value)| Line::from(vec![format!("  {label:<16}").bold(), value.into()]))
+            .collect();
+        let [popup] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup] = Layout::horizontal([Constraint::Max(72)])
+            .flex(Flex::Center)
+            .areas(popup);
+        let block = Block::bordered()
+            .title(format!(" {} ", report.title()))
+            .border_set(border::ROUNDED);
+        Clear.render(popup, buf);
+        Paragraph::new(lines).block(block).render(popup, buf);
+    }
+
+    pub(crate) fn render_footer_report(&self, area: Rect, buf: &mut Buffer) {
+        let controls = Line::from(vec![
+            " Controls:".into(),
+            " Copy as Markdown ".into(),
+            "<y>".fg(self.theme.accent).bold(),
+            " | Close ".into(),
+            "<Esc> ".fg(self.theme.accent).bold(),
+        ]);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
@@ -0,0 +1,165 @@
+//! Reading a smart scale while logging a new entry. Talking Bluetooth to the Acaia, Felicita and
+//! Bookoo scales needs a BLE stack this program doesn't have, so it reads from a bridge program
+//! instead: the config's `scale_command` is run with `sh -c` and should print a line for every
+//! reading, the weight in grams and, once the scale's timer runs, its seconds:
+//!
+//! ```text
+//! 18.1
+//! 0.4 1.2
+//! 36.2 28.1
+//! ```
+//!
+//! `:scale` in the new entry's form starts it and `:scale off` stops it. Until the scale's timer
+//! first starts the weight is taken as the dose; after that it's the yield, and the timer the
+//! duration. The bridge is stopped once the entry is added or dropped.
+
+use std::{
+    io::{self, BufRead, BufReader},
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+use ratatui::{style::Stylize, text::Line};
+
+use crate::{App, Phase};
+
+#[derive(Debug, Clone, Copy)]
+struct Reading {
+    grams: f64,
+    /// the scale's timer, when it's running
+    seconds: Option<f64>,
+}
+
+impl Reading {
+    /// reads `<grams> [<seconds>]`, skipping a line with anything but finite numbers in it
+    fn parse(line: &str) -> Option<Self> {
+        let mut numbers = line
+            .split_whitespace()
+            .map(|n| n.parse::<f64>().ok().filter(|n| n.is_finite()));
+        let grams = numbers.next()??;
+        let seconds = match numbers.next() {
+            Some(seconds) => Some(seconds?).filter(|&s| s > 0.0),
+            None => None,
+        };
+        Some(Self { grams, seconds })
+    }
+}
+
+/// the bridge program, while it runs
+#[derive(Debug)]
+pub struct Scale {
+    child: Child,
+    readings: Receiver<Reading>,
+    last: Option<Reading>,
+    /// whether the scale's timer has run, after which its weight is no longer a dose
+    timed: bool,
+}
+
+impl Scale {
+    fn connect(command: &str) -> io::Result<Self> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdout = child.stdout.take().unwrap();
+        let (sender, readings) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if let Some(reading) = Reading::parse(&line)
+                    && sender.send(reading).is_err()
+                {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            child,
+            readings,
+            last: None,
+            timed: false,
+        })
+    }
+}
+
+impl Drop for Scale {
+    fn drop(&mut self) {
+        _ = self.child.kill();
+        _ = self.child.wait();
+    }
+}
+
+impl App {
+    /// handles `:scale [off]` in the new entry's form
+    pub(crate) fn scale_command(&mut self, arg: Option<&str>) -> io::Result<()> {
+        if arg == Some("off") || !matches!(self.phase, Phase::NewEntry) {
+            self.state.scale = None;
+            return Ok(());
+        }
+        let Some(command) = &self.config.scale_command else {
+            return Err(io::Error::other("set `scale_command` in the config first"));
+        };
+        self.state.scale = Some(Scale::connect(command)?);
+        Ok(())
+    }
+
+    /// fills the new entry in from the readings that came in since the last call, and stops the
+    /// bridge once there's no new entry or it has quit
+    pub(crate) fn read_scale(&mut self) {
+        let Some(scale) = &mut self.state.scale else {
+            return;
+        };
+        let mut gone = !matches!(self.phase, Phase::NewEntry);
+        let mut readings = Vec::new();
+        loop {
+            match scale.readings.try_recv() {
+                Ok(reading) => readings.push(reading),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    gone = true;
+                    break;
+                }
+            }
+        }
+        scale.last = readings.last().copied().or(scale.last);
+        if let Some(entry) = self.state.new_entry.as_mut() {
+            for reading in readings {
+                match reading.seconds {
+                    Some(seconds) => {
+                        scale.timed = true;
+                        entry.output = reading.grams;
+                        entry.duration = (seconds * 10.0).round() / 10.0;
+                    }
+                    None if !scale.timed && reading.grams > 0.0 => entry.dose = reading.grams,
+                    None => {}
+                }
+            }
+        }
+        if gone {
+            self.state.scale = None;
+        }
+    }
+
+    /// "Scale: 36.2 g, 28.1 s" for the footer, while the bridge runs
+    pub(crate) fn scale_line(&self) -> Option<Line<'static>> {
+        let scale = self.state.scale.as_ref()?;
+        let text = match scale.last {
+            None => String::from(" Scale: waiting for a reading"),
+            Some(Reading {
+                grams,
+                seconds: None,
+            }) => format!(" Scale: {}", self.weight(grams)),
+            Some(Reading {
+                grams,
+                seconds: Some(seconds),
+            }) => format!(" Scale: {}, {seconds:.1} s", self.weight(grams)),
+        };
+        Some(Line::from(text).fg(self.theme.accent))
+    }
+}
@@ -0,0 +1,125 @@
+//! Moving through the entry list and an entry's fields a page at a time: Ctrl-d and Ctrl-u move
+//! half a page, PageDown and PageUp a whole one. Both show a scrollbar on their right border once
+//! they're longer than the screen.
+//!
+//! In the entry list, as in vim, a number typed before `j` or `k` moves that many entries and one
+//! before `G` or `g` goes to that entry; `G` on its own goes to the last. The number keys don't
+//! switch tabs there, Tab and Shift-Tab still do.
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::{Margin, Rect},
+    style::Style,
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget},
+};
+
+use crate::App;
+
+/// how many rows down, or up when negative, a paging key moves in a list showing `page` rows
+pub(crate) fn page_move(key_event: KeyEvent, page: u16) -> Option<i32> {
+    let page = i32::from(page.max(2));
+    let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+    match key_event.code {
+        KeyCode::Char('d') if ctrl => Some(page / 2),
+        KeyCode::Char('u') if ctrl => Some(-page / 2),
+        KeyCode::PageDown => Some(page),
+        KeyCode::PageUp => Some(-page),
+        _ => None,
+    }
+}
+
+/// the rows a bordered list in `area` has room for, less its header's
+pub(crate) fn visible_rows(area: Rect, header: u16) -> u16 {
+    area.height.saturating_sub(2 + header)
+}
+
+impl App {
+    /// moves the entry list's selection for the paging keys, the counts and the motions they
+    /// count, returning whether the key was one of them
+    pub(crate) fn move_in_entry_list(&mut self, key_event: KeyEvent) -> bool {
+        let count = self.state.count.take();
+        let rows = match key_event.code {
+            KeyCode::Char(c @ '0'..='9')
+                if key_event.modifiers.is_empty() && (c != '0' || count.is_some()) =>
+            {
+                let digit = c as usize - '0' as usize;
+                // no further than the list goes, however many digits are typed
+                let most = self.listed_entries().len().max(1);
+                let count = count.unwrap_or(0).saturating_mul(10).saturating_add(digit);
+                self.state.count = Some(count.min(most));
+                return true;
+            }
+            _ if key_event.modifiers.contains(KeyModifiers::CONTROL)
+                && !matches!(key_event.code, KeyCode::Char('d' | 'u')) =>
+            {
+                return false;
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                i64::try_from(count.unwrap_or(1)).unwrap_or(i64::MAX)
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                -i64::try_from(count.unwrap_or(1)).unwrap_or(i64::MAX)
+            }
+            KeyCode::Char('G') | KeyCode::Char('g') => {
+                let last = self.listed_entries().len().saturating_sub(1);
+                let to = match (key_event.code, count) {
+                    (_, Some(n)) => n.saturating_sub(1).min(last),
+                    (KeyCode::Char('G'), None) => last,
+                    _ => 0,
+                };
+                self.state.entry_list_state.select(Some(to));
+                return true;
+            }
+            _ => match page_move(key_event, self.state.page_rows) {
+                Some(rows) => i64::from(rows),
+                None => return false,
+            },
+        };
+        let state = &mut self.state.entry_list_state;
+        let by = rows.unsigned_abs().min(u64::from(u16::MAX)) as u16;
+        match state.selected() {
+            None => state.select_first(),
+            Some(_) if rows > 0 => state.scroll_down_by(by),
+            Some(_) => state.scroll_up_by(by),
+        }
+        true
+    }
+
+    /// moves the edited entry's field selection by a page, for the paging keys
+    pub(crate) fn page_fields(&mut self, key_event: KeyEvent) -> bool {
+        let Some(rows) = page_move(key_event, self.state.page_rows) else {
+            return false;
+        };
+        let state = &mut self.state.edit.list_state;
+        if rows > 0 {
+            state.scroll_down_by(rows.unsigned_abs() as u16);
+        } else {
+            state.scroll_up_by(rows.unsigned_abs() as u16);
+        }
+        true
+    }
+
+    /// draws a scrollbar over the right border of the bordered list in `area`, if its `total`
+    /// rows don't fit in the `visible` ones
+    pub(crate) fn render_scrollbar(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        total: usize,
+        visible: u16,
+        selected: Option<usize>,
+    ) {
+        if total <= usize::from(visible) {
+            return;
+        }
+        let mut state = ScrollbarState::new(total)
+            .viewport_content_length(usize::from(visible))
+            .position(selected.unwrap_or(0));
+        Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .thumb_style(Style::new().fg(self.theme.accent))
+            .render(area.inner(Margin::new(0, 1)), buf, &mut state);
+    }
+}
@@ -0,0 +1,67 @@
+//! Searching the entry list like in vim: `/` followed by some text jumps to the next entry whose
+//! coffee, grinder or notes have it in them, ignoring case, and `n` and `N` go on to the next and
+//! previous ones. Matching entries stay highlighted until Esc.
+
+use ratatui::style::{Color, Style};
+
+use crate::{App, Entry};
+
+/// how entries matching the search are shown in the list
+pub const MATCH_STYLE: Style = Style::new().fg(Color::Yellow);
+
+impl App {
+    /// whether the entry has the search in it, when searching
+    pub(crate) fn search_match(&self, entry: &Entry) -> bool {
+        let Some(query) = &self.state.search else {
+            return false;
+        };
+        [
+            self.coffee_name(entry),
+            self.grinder_name(entry),
+            &entry.notes,
+        ]
+        .iter()
+        .any(|text| text.to_lowercase().contains(query))
+    }
+
+    /// handles `/<text>`; on its own, `/` searches for the last text again
+    pub(crate) fn search(&mut self, query: &str) {
+        let query = query.trim().to_lowercase();
+        if !query.is_empty() {
+            self.state.search = Some(query);
+        }
+        self.next_match(true);
+    }
+
+    /// selects the next entry matching the search, or the previous one when not `forward`,
+    /// wrapping around the ends of the list
+    pub(crate) fn next_match(&mut self, forward: bool) {
+        let listed = self.listed_entries();
+        let n = listed.len();
+        if self.state.search.is_none() || n == 0 {
+            return;
+        }
+        let current = self
+            .state
+            .entry_list_state
+            .selected()
+            .unwrap_or(0)
+            .min(n - 1);
+        let found = (1..=n)
+            .map(|step| {
+                if forward {
+                    (current + step) % n
+                } else {
+                    (current + n - step) % n
+                }
+            })
+            .find(|&row| self.search_match(&self.data.entries[listed[row]]));
+        if let Some(row) = found {
+            self.state.entry_list_state.select(Some(row));
+        }
+    }
+
+    pub(crate) fn clear_search(&mut self) {
+        self.state.search = None;
+    }
+}
@@ -0,0 +1,207 @@
+//! The popup for picking an entry's coffee, grinder, brewer, water or basket. Typing narrows the list down to the names
+//! that contain the typed letters in order, best matches first, so "eg" finds "Ethiopia Guji".
+//! When what's typed isn't the name of an existing one, the last option creates it.
+
+use std::cmp::Reverse;
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, List, ListState, Paragraph, StatefulWidget, Widget},
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use crate::{catalog::Kind, App, InputMode, SELECTED_SYMBOL};
+
+#[derive(Debug, Default)]
+pub struct Selector {
+    filter: Input,
+    list_state: ListState,
+}
+
+/// how well `name` matches `pattern`, ignoring case: `None` unless every character of the
+/// pattern appears in the name in order, otherwise higher for matches that start earlier and run
+/// together
+fn fuzzy_score(pattern: &str, name: &str) -> Option<i64> {
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut prev: Option<usize> = None;
+    for c in pattern.to_lowercase().chars() {
+        let found = pos + name[pos..].iter().position(|&n| n == c)?;
+        score -= match prev {
+            Some(prev) if found == prev + 1 => 0,
+            Some(prev) => (found - prev) as i64,
+            None => found as i64,
+        };
+        let word_start = found == 0 || !name[found - 1].is_alphanumeric();
+        if word_start {
+            score += 2;
+        }
+        prev = Some(found);
+        pos = found + 1;
+    }
+    Some(score)
+}
+
+impl App {
+    /// opens the selector on the entry being edited, starting at its current item
+    pub(crate) fn open_selector(&mut self, kind: Kind) {
+        self.state.edit.selector = Selector::default();
+        let current = self.edited_entry().and_then(|e| kind.id(e));
+        let selected = self
+            .selector_matches(kind)
+            .into_iter()
+            .position(|i| Some(self.catalog_uuid(kind, i)) == current)
+            .or(Some(0));
+        self.state.edit.selector.list_state.select(selected);
+    }
+
+    /// indices of the items matching the filter, best match first. Archived coffees are left out
+    /// unless the entry already has one.
+    fn selector_matches(&self, kind: Kind) -> Vec<usize> {
+        let pattern = self.state.edit.selector.filter.value();
+        let names = self.catalog_names(kind);
+        let current = self.edited_entry().and_then(|e| kind.id(e));
+        let mut matches: Vec<(i64, usize)> = names
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                !self.catalog_archived(kind, *i) || Some(self.catalog_uuid(kind, *i)) == current
+            })
+            .filter_map(|(i, name)| fuzzy_score(pattern, name).map(|score| (score, i)))
+            .collect();
+        // stable, so equally good matches keep their catalog order
+        matches.sort_by_key(|(score, _)| Reverse(*score));
+        matches.into_iter().map(|(_, i)| i).collect()
+    }
+
+    /// the name the "create new" option would add, if what's typed would make a valid new name
+    fn selector_new_name(&self, kind: Kind) -> Option<String> {
+        let name = self.state.edit.selector.filter.value().trim();
+        self.check_catalog_name(kind, name, None)
+            .is_ok()
+            .then(|| name.to_string())
+    }
+
+    pub(crate) fn handle_key_events_selector(&mut self, kind: Kind, key_event: KeyEvent) {
+        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        let selector = &mut self.state.edit.selector;
+        match key_event.code {
+            KeyCode::Esc => self.state.edit.input_mode = InputMode::Normal,
+            KeyCode::Down => selector.list_state.select_next(),
+            KeyCode::Up => selector.list_state.select_previous(),
+            KeyCode::Char('n') if ctrl => selector.list_state.select_next(),
+            KeyCode::Char('p') if ctrl => selector.list_state.select_previous(),
+            KeyCode::Enter => {
+                let matches = self.selector_matches(kind);
+                let Some(selected) = self.state.edit.selector.list_state.selected() else {
+                    return;
+                };
+                let i = match matches.get(selected) {
+                    Some(&i) => i,
+                    None => match self.selector_new_name(kind) {
+                        Some(name) => {
+                            self.save_catalog_name(kind, None, name);
+                            self.catalog_names(kind).len() - 1
+                        }
+                        None => return,
+                    },
+                };
+                let uuid = self.catalog_uuid(kind, i);
+                if let Some(entry) = self.edited_entry_mut() {
+                    kind.set_id(entry, uuid);
+                    self.field_saved();
+                }
+            }
+            _ => {
+                if selector
+                    .filter
+                    .handle_event(&Event::Key(key_event))
+                    .is_some()
+                {
+                    selector.list_state.select(Some(0));
+                }
+            }
+        }
+    }
+
+    /// draws the selector as a popup over `area`
+    pub(crate) fn render_selector(&mut self, kind: Kind, area: Rect, buf: &mut Buffer) {
+        let names = self.catalog_names(kind);
+        let mut items: Vec<Line> = self
+            .selector_matches(kind)
+            .into_iter()
+            .map(|i| Line::from(format!("  {}", names[i])))
+            .collect();
+        if let Some(name) = self.selector_new_name(kind) {
+            items.push(Line::from(vec![
+                format!("  Create new {} ", kind.singular()).italic(),
+                format!("\"{name}\"").into(),
+            ]));
+        }
+        let empty = items.is_empty();
+        // filter line and borders around the list
+        let height = items.len().max(1) as u16 + 4;
+        let [popup] = Layout::vertical([Constraint::Max(height)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup] = Layout::horizontal([Constraint::Max(60)])
+            .flex(Flex::Center)
+            .areas(popup);
+        let block = Block::bordered()
+            .title(format!(" Choose {} ", kind.singular()))
+            .border_set(border::ROUNDED);
+        let inner = block.inner(popup);
+        Clear.render(popup, buf);
+        block.render(popup, buf);
+        let [filter_area, _, list_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+        let filter = self.state.edit.selector.filter.value();
+        Paragraph::new(Line::from(vec![
+            "  > ".fg(self.theme.accent).bold(),
+            filter.into(),
+        ]))
+        .render(filter_area, buf);
+        if empty {
+            Paragraph::new(format!("  No {} match.", kind.plural().to_lowercase()))
+                .render(list_area, buf);
+            return;
+        }
+        let list = List::new(items)
+            .highlight_style(self.theme.selected)
+            .highlight_symbol(SELECTED_SYMBOL);
+        StatefulWidget::render(
+            list,
+            list_area,
+            buf,
+            &mut self.state.edit.selector.list_state,
+        );
+    }
+
+    pub(crate) fn render_footer_selector(&self, area: Rect, buf: &mut Buffer) {
+        let controls = Line::from(vec![
+            " Controls:".into(),
+            " Filter ".into(),
+            "<type>".fg(self.theme.accent).bold(),
+            " | Next ".into(),
+            "<Down>".fg(self.theme.accent).bold(),
+            " | Previous ".into(),
+            "<Up>".fg(self.theme.accent).bold(),
+            " | Choose ".into(),
+            "<Enter>".fg(self.theme.accent).bold(),
+            " | Cancel ".into(),
+            "<Esc> ".fg(self.theme.accent).bold(),
+        ]);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
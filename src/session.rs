@@ -0,0 +1,32 @@
+//! Reading and writing the state file, see [`crate::paths::state_file`]: what's remembered from
+//! one session to the next about how the app was used on this machine, like where the divider
+//! was left or the commands typed. Each thing is kept under its own key, so writing one leaves
+//! the others as they were.
+
+use std::{fs, io};
+
+use crate::{
+    json::{self, Value},
+    paths,
+};
+
+/// what's kept under `key`; a missing or unreadable state file only means there's nothing
+pub fn load(key: &str) -> Option<Value> {
+    let text = fs::read_to_string(paths::state_file()).ok()?;
+    json::parse(&text).ok()?.remove(key)
+}
+
+/// keeps `value` under `key`, leaving whatever else is in the state file
+pub fn save(key: &str, value: Value) -> io::Result<()> {
+    let path = paths::state_file();
+    let mut state = match fs::read_to_string(&path) {
+        Ok(text) => json::parse(&text).unwrap_or(Value::Object(Vec::new())),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Value::Object(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    state.set(key, value);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, state.to_string_pretty())
+}
@@ -0,0 +1,139 @@
+//! `:set`, for changing a few of the config's settings while running, vi style:
+//!
+//! - `:set units=oz` or `units=g`, what doses and outputs are shown and typed in
+//! - `:set temperature=f` or `temperature=c`, the same for brew temperatures
+//! - `:set dateformat=%d %b %H:%M`, how dates are shown, a chrono format
+//! - `:set relative-dates`, showing recent dates as "3 h ago", `norelative-dates` to stop and
+//!   `relative-dates!` to switch
+//!
+//! `:set` on its own says what they all are and `:set units?` what one is. Each change is
+//! written back into the config file, so it's kept for next time.
+
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, Local,
+};
+
+use crate::{config, json::Value, temperature::TemperatureUnit, weight::WeightUnit, App, DATE_FMT};
+
+/// the options `:set` knows, for completion
+pub const OPTIONS: [&str; 4] = ["units", "temperature", "dateformat", "relative-dates"];
+
+/// whether `format` is one chrono can show a date in
+pub fn valid_date_format(format: &str) -> bool {
+    !format.is_empty() && StrftimeItems::new(format).all(|item| !matches!(item, Item::Error))
+}
+
+impl App {
+    /// a date the way the settings say to show it
+    pub(crate) fn date_text(&self, date: DateTime<Local>) -> String {
+        if self.config.relative_dates
+            && let Some(ago) = relative(date, Local::now())
+        {
+            return ago;
+        }
+        let format = self.config.date_format.as_deref().unwrap_or(DATE_FMT);
+        date.format(format).to_string()
+    }
+
+    /// what an option is set to, as `:set` would take it
+    fn option_text(&self, name: &str) -> Option<String> {
+        let config = &self.config;
+        Some(match name {
+            "units" => format!("units={}", config.weight_unit.key()),
+            "temperature" => format!("temperature={}", config.temperature_unit.key()),
+            "dateformat" => format!(
+                "dateformat={}",
+                config.date_format.as_deref().unwrap_or(DATE_FMT)
+            ),
+            "relative-dates" if config.relative_dates => String::from("relative-dates"),
+            "relative-dates" => String::from("norelative-dates"),
+            _ => return None,
+        })
+    }
+
+    /// handles `:set`, with everything typed after it
+    pub(crate) fn set_command(&mut self, text: &str) -> Result<(), String> {
+        let text = text.trim();
+        if text.is_empty() {
+            let all: Vec<String> = OPTIONS.iter().filter_map(|o| self.option_text(o)).collect();
+            self.info(all.join("  "));
+            return Ok(());
+        }
+        if let Some(name) = text.strip_suffix('?') {
+            let value = self
+                .option_text(name)
+                .ok_or_else(|| format!("there's no option `{name}`"))?;
+            self.info(value);
+            return Ok(());
+        }
+        // the value is the rest of the text, spaces and all, so a date format can have them
+        let (key, value) = match text.split_once('=') {
+            Some((name, value)) => {
+                let value = value.trim();
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .unwrap_or(value);
+                (name.trim(), Some(value))
+            }
+            None => (text, None),
+        };
+        let (option, config_key, saved) = match (key, value) {
+            ("units", Some(value)) => {
+                let unit =
+                    WeightUnit::from_key(value).ok_or_else(|| String::from("units are g or oz"))?;
+                self.config.weight_unit = unit;
+                ("units", "weight_unit", Value::from(unit.key()))
+            }
+            ("temperature", Some(value)) => {
+                let unit = TemperatureUnit::from_key(value)
+                    .ok_or_else(|| String::from("the temperature is c or f"))?;
+                self.config.temperature_unit = unit;
+                ("temperature", "temperature_unit", Value::from(unit.key()))
+            }
+            ("dateformat", Some(format)) => {
+                if !valid_date_format(format) {
+                    return Err(format!("`{format}` isn't a date format like %d %b %H:%M"));
+                }
+                self.config.date_format = Some(format.to_string());
+                ("dateformat", "date_format", Value::from(format))
+            }
+            ("relative-dates" | "norelative-dates" | "relative-dates!", None) => {
+                self.config.relative_dates = match key {
+                    "relative-dates" => true,
+                    "norelative-dates" => false,
+                    _ => !self.config.relative_dates,
+                };
+                let on = Value::from(self.config.relative_dates);
+                ("relative-dates", "relative_dates", on)
+            }
+            ("units" | "temperature" | "dateformat", None) => {
+                return Err(format!("`{key}` needs a value, like {key}=..."));
+            }
+            _ => return Err(format!("there's no option `{key}`")),
+        };
+        config::save_setting(config_key, saved)
+            .map_err(|e| format!("Set, but couldn't write it to the config: {e}"))?;
+        if let Some(value) = self.option_text(option) {
+            self.info(value);
+        }
+        Ok(())
+    }
+}
+
+/// `date` relative to `now`, e.g. "just now", "25 min ago", "3 h ago", "yesterday 08:12" or
+/// "4 days ago"; `None` once it's a week or more ago, or in the future
+fn relative(date: DateTime<Local>, now: DateTime<Local>) -> Option<String> {
+    let ago = now.signed_duration_since(date);
+    let days = (now.date_naive() - date.date_naive()).num_days();
+    Some(match days {
+        _ if ago.num_seconds() < 0 => return None,
+        _ if ago.num_minutes() < 1 => String::from("just now"),
+        0 if ago.num_hours() < 1 => format!("{} min ago", ago.num_minutes()),
+        0 => format!("{} h ago", ago.num_hours()),
+        1 => format!("yesterday {}", date.format("%H:%M")),
+        2..7 => format!("{days} days ago"),
+        _ => return None,
+    })
+}
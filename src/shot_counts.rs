@@ -0,0 +1,76 @@
+//! The number of shots each coffee had in the stats' period, as horizontal bars, or the number each
+//! roaster's coffees had. Coffees without a roaster are counted together.
+
+use std::collections::HashMap;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Direction, Rect},
+    style::{Style, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Bar, BarChart, BarGroup, Block, Widget},
+};
+
+use crate::App;
+
+const NO_ROASTER: &str = "No roaster";
+
+impl App {
+    /// the name of each coffee, or each roaster, with its number of shots, most first
+    fn shot_counts(&self, by_roaster: bool) -> Vec<(String, u64)> {
+        let mut counts: HashMap<String, u64> = HashMap::new();
+        for entry in self.data.entries.iter().filter(|e| self.in_period(e)) {
+            let coffee = self.data.coffees.iter().find(|c| c.uuid == entry.coffee_id);
+            let name = if by_roaster {
+                coffee
+                    .and_then(|c| c.roaster_id)
+                    .and_then(|id| self.data.roasters.iter().find(|r| r.uuid == id))
+                    .map_or(NO_ROASTER, |r| r.name.as_str())
+            } else {
+                self.coffee_name(entry)
+            };
+            *counts.entry(name.to_string()).or_default() += 1;
+        }
+        let mut counts: Vec<(String, u64)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+
+    pub(crate) fn render_shot_counts(&self, by_roaster: bool, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title(format!(
+                "{}- shots by {} ",
+                self.title(),
+                if by_roaster { "roaster" } else { "coffee" }
+            ))
+            .border_set(border::ROUNDED);
+        let counts = self.shot_counts(by_roaster);
+        if counts.is_empty() {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            Line::from("  No shots in this period.")
+                .dark_gray()
+                .render(inner, buf);
+            return;
+        }
+        let bars: Vec<Bar> = counts
+            .iter()
+            .map(|(name, n)| {
+                Bar::default()
+                    .label(Line::from(name.clone()))
+                    .value(*n)
+                    .text_value(n.to_string())
+            })
+            .collect();
+        BarChart::default()
+            .block(block)
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_gap(0)
+            .bar_style(Style::new().fg(self.theme.accent))
+            .value_style(Style::new().black().bg(self.theme.accent))
+            .data(BarGroup::default().bars(&bars))
+            .render(area, buf);
+    }
+}
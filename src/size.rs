@@ -0,0 +1,46 @@
+//! The smallest terminal the screens are laid out for. Below it, rather than drawing fields over
+//! each other or off the edge, only a note saying how big the terminal is and how big it needs
+//! to be is shown, until the terminal is resized. The keys still work, so `q` still quits.
+//!
+//! Above it the screens make the most of the room: the selected entry's details go beside the
+//! list from [`crate::SPLIT_MIN_WIDTH`] columns and under it on narrower screens that are tall
+//! enough, and the comparison with the reference shot beside an entry's fields from
+//! [`REFERENCE_MIN_WIDTH`].
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Paragraph, Widget, Wrap},
+};
+
+use crate::App;
+
+pub const MIN_WIDTH: u16 = 80;
+pub const MIN_HEIGHT: u16 = 24;
+/// below this many columns an entry's fields take the whole width, without the reference shot
+pub const REFERENCE_MIN_WIDTH: u16 = 110;
+
+/// whether `area` is too small for the screens
+pub fn too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+impl App {
+    /// says how big the terminal is and how big it should be, in the middle of `area`
+    pub(crate) fn render_too_small(&self, area: Rect, buf: &mut Buffer) {
+        let lines = vec![
+            Line::from("Terminal too small").bold(),
+            Line::from(format!("{}x{}", area.width, area.height)).fg(self.theme.accent),
+            Line::from(format!("need {MIN_WIDTH}x{MIN_HEIGHT}")),
+        ];
+        let [middle] = Layout::vertical([Constraint::Length(lines.len() as u16)])
+            .flex(Flex::Center)
+            .areas(area);
+        Paragraph::new(lines)
+            .centered()
+            .wrap(Wrap { trim: true })
+            .render(middle, buf);
+    }
+}
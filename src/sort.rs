@@ -0,0 +1,168 @@
+//! Sorting the entry list. `s` moves on to the next field to sort by, going back to the order the
+//! entries were added after the last, and `S` turns the order around; `:sort <field> [asc|desc]`
+//! picks one directly and `:sort` on its own goes back to the order they were added.
+
+use crate::{App, Entry};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortKey {
+    Date,
+    Coffee,
+    Grinder,
+    Dose,
+    Yield,
+    Ratio,
+    Duration,
+    Rating,
+}
+
+pub const SORT_KEYS: [SortKey; 8] = [
+    SortKey::Date,
+    SortKey::Coffee,
+    SortKey::Grinder,
+    SortKey::Dose,
+    SortKey::Yield,
+    SortKey::Ratio,
+    SortKey::Duration,
+    SortKey::Rating,
+];
+
+impl SortKey {
+    /// as typed after `:sort` and shown in the title
+    pub fn key(self) -> &'static str {
+        match self {
+            SortKey::Date => "date",
+            SortKey::Coffee => "coffee",
+            SortKey::Grinder => "grinder",
+            SortKey::Dose => "dose",
+            SortKey::Yield => "yield",
+            SortKey::Ratio => "ratio",
+            SortKey::Duration => "duration",
+            SortKey::Rating => "rating",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        SORT_KEYS.into_iter().find(|k| k.key() == key)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Sort {
+    key: SortKey,
+    descending: bool,
+}
+
+impl Sort {
+    /// reads a field and an optional `asc` or `desc`, as typed after `:sort`
+    pub fn parse(field: &str, order: Option<&str>) -> Option<Self> {
+        Some(Self {
+            key: SortKey::from_key(field)?,
+            descending: matches!(order, Some("desc" | "descending")),
+        })
+    }
+
+    /// e.g. "ratio desc", which [`Sort::parse`] reads back
+    pub fn spec(self) -> String {
+        let order = if self.descending { "desc" } else { "asc" };
+        format!("{} {order}", self.key.key())
+    }
+
+    /// e.g. "by ratio, descending", for the title
+    pub fn describe(self) -> String {
+        let order = if self.descending {
+            "descending"
+        } else {
+            "ascending"
+        };
+        format!("by {}, {order}", self.key.key())
+    }
+}
+
+fn ratio(entry: &Entry) -> f64 {
+    if entry.dose > 0.0 {
+        entry.output / entry.dose
+    } else {
+        0.0
+    }
+}
+
+impl App {
+    /// orders the entries at `rows` by the list's sort, leaving them as they are when unsorted
+    pub(crate) fn sort_entries(&self, rows: &mut [usize]) {
+        let Some(sort) = self.state.sort else {
+            return;
+        };
+        let entries = &self.data.entries;
+        rows.sort_by(|&a, &b| {
+            let (a, b) = (&entries[a], &entries[b]);
+            let order = match sort.key {
+                SortKey::Date => a.dt_taken.cmp(&b.dt_taken),
+                SortKey::Coffee => self
+                    .coffee_name(a)
+                    .to_lowercase()
+                    .cmp(&self.coffee_name(b).to_lowercase()),
+                SortKey::Grinder => self
+                    .grinder_name(a)
+                    .to_lowercase()
+                    .cmp(&self.grinder_name(b).to_lowercase()),
+                SortKey::Dose => a.dose.total_cmp(&b.dose),
+                SortKey::Yield => a.output.total_cmp(&b.output),
+                SortKey::Ratio => ratio(a).total_cmp(&ratio(b)),
+                SortKey::Duration => a.duration.total_cmp(&b.duration),
+                // unrated entries count as rated lowest
+                SortKey::Rating => a.rating.cmp(&b.rating),
+            };
+            if sort.descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+    }
+
+    /// sorts the list, keeping the same entry selected; it's no longer the saved view it was
+    pub(crate) fn set_sort(&mut self, sort: Option<Sort>) {
+        let selected = self.selected_entry();
+        self.state.sort = sort;
+        self.state.view = None;
+        if let Some(idx) = selected {
+            self.select_entry(idx);
+        }
+    }
+
+    /// sorts by the field after the current one, ascending, or by none after the last
+    pub(crate) fn next_sort(&mut self) {
+        let next = match self.state.sort {
+            None => Some(SORT_KEYS[0]),
+            Some(sort) => {
+                let i = SORT_KEYS.iter().position(|k| *k == sort.key).unwrap_or(0);
+                SORT_KEYS.get(i + 1).copied()
+            }
+        };
+        self.set_sort(next.map(|key| Sort {
+            key,
+            descending: false,
+        }));
+    }
+
+    pub(crate) fn reverse_sort(&mut self) {
+        if let Some(sort) = self.state.sort {
+            self.set_sort(Some(Sort {
+                descending: !sort.descending,
+                ..sort
+            }));
+        }
+    }
+
+    /// handles `:sort [field] [asc|desc]`, ignoring fields it doesn't know
+    pub(crate) fn sort_command(&mut self, field: Option<&str>, order: Option<&str>) {
+        let Some(field) = field else {
+            self.set_sort(None);
+            return;
+        };
+        if let Some(sort) = Sort::parse(field, order) {
+            self.set_sort(Some(sort));
+        }
+    }
+}
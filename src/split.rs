@@ -0,0 +1,121 @@
+//! The divider between the entry list and the selected entry's details, shown side by side from
+//! [`crate::SPLIT_MIN_WIDTH`] columns. Narrower screens with the rows for it get the details under
+//! the list instead. Ctrl-h moves the divider left and Ctrl-l right, and the share of the screen
+//! the details were left with is kept in the state file, see [`crate::session`], for the next
+//! session.
+//! It's kept apart from the data, since how wide a terminal is belongs to the machine and not the
+//! coffee log.
+
+use std::io;
+
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+};
+
+use crate::{json::Value, session, App, SPLIT_MIN_WIDTH};
+
+/// the details' share of the screen before the divider has been moved
+const DEFAULT_SHARE: f64 = 0.3;
+const MIN_SHARE: f64 = 0.2;
+const MAX_SHARE: f64 = 0.6;
+/// how far each key press moves the divider
+const STEP: f64 = 0.02;
+/// the fewest rows the list view needs for the details to go under the list
+const STACKED_MIN_HEIGHT: u16 = 30;
+/// the percentage of the rows the details take under the list
+const STACKED_PERCENT: u16 = 40;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Split {
+    /// the share of the width the details take
+    details: f64,
+    /// whether the details were last drawn beside the list, the only time the divider moves
+    beside: bool,
+}
+
+impl Default for Split {
+    fn default() -> Self {
+        Self {
+            details: DEFAULT_SHARE,
+            beside: false,
+        }
+    }
+}
+
+impl Split {
+    /// the split left at the end of the last session; a missing or unreadable state file only
+    /// means starting from the default
+    pub fn load() -> Self {
+        let share = session::load("details_share").and_then(|share| share.as_f64());
+        match share {
+            Some(share) => Self {
+                details: share.clamp(MIN_SHARE, MAX_SHARE),
+                ..Self::default()
+            },
+            None => Self::default(),
+        }
+    }
+
+    /// writes the split into the state file, keeping whatever else is in it
+    fn save(&self) -> io::Result<()> {
+        session::save("details_share", Value::from(self.details))
+    }
+
+    /// whether the details were last drawn beside the list
+    pub fn beside(&self) -> bool {
+        self.beside
+    }
+
+    /// how many of `width` columns the details take
+    pub fn details_width(&self, width: u16) -> u16 {
+        (f64::from(width) * self.details).round() as u16
+    }
+
+    /// the list's part of `area` and the details', beside the list on a wide screen, under it on
+    /// a narrow but tall one, and left out when there's room for neither
+    pub fn areas(&mut self, area: Rect) -> (Rect, Option<Rect>) {
+        self.beside = area.width >= SPLIT_MIN_WIDTH;
+        if self.beside {
+            let [list, details] = Layout::horizontal([
+                Constraint::Fill(1),
+                Constraint::Length(self.details_width(area.width)),
+            ])
+            .areas(area);
+            (list, Some(details))
+        } else if area.height >= STACKED_MIN_HEIGHT {
+            let [list, details] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Percentage(STACKED_PERCENT)])
+                    .areas(area);
+            (list, Some(details))
+        } else {
+            (area, None)
+        }
+    }
+}
+
+impl App {
+    /// moves the divider for Ctrl-h and Ctrl-l, returning whether the key was one of them. While
+    /// the details aren't beside the list there's no divider, and the keys do nothing.
+    pub(crate) fn resize_split(&mut self, key_event: KeyEvent) -> bool {
+        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        let step = match key_event.code {
+            KeyCode::Char('h') if ctrl => STEP,
+            KeyCode::Char('l') if ctrl => -STEP,
+            _ => return false,
+        };
+        if !self.split.beside {
+            return true;
+        }
+        // rounded, so the state file says 0.36 and not 0.36000000000000004
+        let details = ((self.split.details + step) * 100.0).round() / 100.0;
+        let details = details.clamp(MIN_SHARE, MAX_SHARE);
+        if details != self.split.details {
+            self.split.details = details;
+            if let Err(e) = self.split.save() {
+                self.error(format!("Couldn't keep the split for next time: {e}"));
+            }
+        }
+        true
+    }
+}
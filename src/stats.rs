@@ -0,0 +1,349 @@
+//! The stats screen, opened with `:stats` or its tab, for figures worked out from the whole log.
+//! It starts with a table of how each coffee has been brewed; Enter on one of them lists its shots,
+//! `h` shows how its shot times spread out, `b` charts how many shots each coffee or roaster had,
+//! `c` shows what goes along with a good rating, and `m` what the coffee cost. `P` picks the period
+//! looked at.
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Cell, Paragraph, Row, StatefulWidget, Table, TableState, Widget},
+};
+use uuid::Uuid;
+
+use crate::{keymap::STATS_KEYS, tasting::MAX_RATING, App, Phase, SELECTED_SYMBOL};
+
+#[derive(Debug, Default)]
+pub struct StatsView {
+    coffees: TableState,
+    sub: SubView,
+    history_state: TableState,
+}
+
+/// what the stats screen is showing
+#[derive(Debug, Default, Clone, Copy)]
+pub enum SubView {
+    /// the table of coffees and the figures under it
+    #[default]
+    Summary,
+    /// the shots of a coffee, after Enter on its row
+    History(Uuid),
+    /// the number of shots of each coffee, or of each roaster's coffees, as bars
+    ShotCounts { by_roaster: bool },
+    /// a histogram of a coffee's shot times
+    TimeHistogram(Uuid),
+    /// how the rating goes along with the other parameters
+    Correlations,
+    /// what the coffee cost, by month, coffee and roaster
+    Costs,
+}
+
+/// how one coffee has been brewed
+struct CoffeeStats {
+    coffee_id: Uuid,
+    shots: usize,
+    dose: f64,
+    /// of the shots with a dose
+    ratio: Option<f64>,
+    time: f64,
+    favorites: usize,
+    /// of the rated shots
+    rating: Option<f64>,
+}
+
+/// the mean of `values`, if there are any
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (total, n) = values.fold((0.0, 0), |(total, n), v| (total + v, n + 1));
+    (n > 0).then(|| total / f64::from(n))
+}
+
+impl App {
+    pub(crate) fn open_stats(&mut self) {
+        self.state.stats = StatsView {
+            coffees: TableState::default().with_selected(Some(0)),
+            ..Default::default()
+        };
+        self.phase = Phase::Stats;
+    }
+
+    /// the coffees brewed in the period, most brewed first
+    fn coffee_stats(&self) -> Vec<CoffeeStats> {
+        let mut stats: Vec<CoffeeStats> = self
+            .data
+            .coffees
+            .iter()
+            .filter_map(|coffee| {
+                let shots: Vec<_> = self
+                    .data
+                    .entries
+                    .iter()
+                    .filter(|e| e.coffee_id == coffee.uuid && self.in_period(e))
+                    .collect();
+                // coffees without shots in the period have no mean dose, and are left out
+                Some(CoffeeStats {
+                    coffee_id: coffee.uuid,
+                    shots: shots.len(),
+                    dose: mean(shots.iter().map(|e| e.dose))?,
+                    ratio: mean(
+                        shots
+                            .iter()
+                            .filter(|e| e.dose > 0.0)
+                            .map(|e| e.output / e.dose),
+                    ),
+                    time: mean(shots.iter().map(|e| e.duration))?,
+                    favorites: shots.iter().filter(|e| e.favorite).count(),
+                    rating: mean(shots.iter().filter_map(|e| e.rating.map(f64::from))),
+                })
+            })
+            .collect();
+        stats.sort_by_key(|s| std::cmp::Reverse(s.shots));
+        stats
+    }
+
+    /// indices into the entries of the shots of the coffee being looked at, newest first
+    fn history_entries(&self, coffee_id: Uuid) -> Vec<usize> {
+        let mut rows: Vec<usize> = (0..self.data.entries.len())
+            .filter(|&i| {
+                let entry = &self.data.entries[i];
+                entry.coffee_id == coffee_id && self.in_period(entry)
+            })
+            .collect();
+        rows.sort_by_key(|&i| std::cmp::Reverse(self.data.entries[i].dt_taken));
+        rows
+    }
+
+    /// the coffee of the selected row of the table
+    fn selected_stats_coffee(&self) -> Option<Uuid> {
+        let row = self.state.stats.coffees.selected()?;
+        self.coffee_stats().get(row).map(|s| s.coffee_id)
+    }
+
+    pub(crate) fn handle_key_events_stats(&mut self, key_event: KeyEvent) {
+        if self.state.range_picker.is_some() {
+            self.handle_key_events_range_picker(key_event);
+            return;
+        }
+        if key_event.code == KeyCode::Char('P') {
+            self.open_range_picker();
+            return;
+        }
+        let view = &mut self.state.stats;
+        match view.sub {
+            SubView::Summary => match key_event.code {
+                KeyCode::Char('q') | KeyCode::Esc => self.phase = Phase::ListView,
+                KeyCode::Char('j') | KeyCode::Down => view.coffees.select_next(),
+                KeyCode::Char('k') | KeyCode::Up => view.coffees.select_previous(),
+                KeyCode::Char('b') => view.sub = SubView::ShotCounts { by_roaster: false },
+                KeyCode::Char('c') => view.sub = SubView::Correlations,
+                KeyCode::Char('m') => view.sub = SubView::Costs,
+                KeyCode::Enter => {
+                    if let Some(coffee_id) = self.selected_stats_coffee() {
+                        self.state.stats.sub = SubView::History(coffee_id);
+                        self.state.stats.history_state =
+                            TableState::default().with_selected(Some(0));
+                    }
+                }
+                KeyCode::Char('h') => {
+                    if let Some(coffee_id) = self.selected_stats_coffee() {
+                        self.state.stats.sub = SubView::TimeHistogram(coffee_id);
+                    }
+                }
+                _ => {}
+            },
+            SubView::History(_) => match key_event.code {
+                KeyCode::Char('q') | KeyCode::Esc => view.sub = SubView::Summary,
+                KeyCode::Char('j') | KeyCode::Down => view.history_state.select_next(),
+                KeyCode::Char('k') | KeyCode::Up => view.history_state.select_previous(),
+                _ => {}
+            },
+            SubView::TimeHistogram(_) | SubView::Correlations | SubView::Costs => {
+                if matches!(key_event.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    view.sub = SubView::Summary;
+                }
+            }
+            SubView::ShotCounts { by_roaster } => match key_event.code {
+                KeyCode::Char('q') | KeyCode::Esc => view.sub = SubView::Summary,
+                KeyCode::Char('g') => {
+                    view.sub = SubView::ShotCounts {
+                        by_roaster: !by_roaster,
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+
+    fn coffee_stats_table(&self, stats: &[CoffeeStats]) -> Table<'static> {
+        let header = Row::new(
+            [
+                "Coffee", "Shots", "Dose", "Ratio", "Time", "Favorite", "Rating",
+            ]
+            .map(Cell::from),
+        )
+        .bold();
+        let rows = stats.iter().map(|s| {
+            let name = self
+                .data
+                .coffees
+                .iter()
+                .find(|c| c.uuid == s.coffee_id)
+                .map_or("", |c| c.name.as_str());
+            Row::new([
+                Cell::from(name.to_string()),
+                Cell::from(s.shots.to_string()),
+                Cell::from(format!("{:.1} g", s.dose)),
+                Cell::from(s.ratio.map_or(String::from("-"), |r| format!("{r:.1} / 1"))),
+                Cell::from(format!("{:.0} sec", s.time)),
+                Cell::from(format!(
+                    "{:.0}%",
+                    100.0 * s.favorites as f64 / s.shots as f64
+                )),
+                Cell::from(
+                    s.rating
+                        .map_or(String::from("-"), |r| format!("{r:.1} / {MAX_RATING}")),
+                ),
+            ])
+        });
+        Table::new(
+            rows,
+            [
+                Constraint::Min(12),
+                Constraint::Length(6),
+                Constraint::Length(7),
+                Constraint::Length(7),
+                Constraint::Length(7),
+                Constraint::Length(8),
+                Constraint::Length(7),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(self.theme.selected)
+        .highlight_symbol(SELECTED_SYMBOL)
+    }
+
+    pub(crate) fn render_stats(&mut self, area: Rect, buf: &mut Buffer) {
+        match self.state.stats.sub {
+            SubView::Summary => self.render_stats_summary(area, buf),
+            SubView::History(coffee_id) => self.render_coffee_history(coffee_id, area, buf),
+            SubView::ShotCounts { by_roaster } => self.render_shot_counts(by_roaster, area, buf),
+            SubView::TimeHistogram(coffee_id) => self.render_time_histogram(coffee_id, area, buf),
+            SubView::Correlations => self.render_correlations(area, buf),
+            SubView::Costs => self.render_costs(area, buf),
+        }
+        self.render_range_picker(area, buf);
+    }
+
+    fn render_coffee_history(&mut self, coffee_id: Uuid, area: Rect, buf: &mut Buffer) {
+        let name = self
+            .data
+            .coffees
+            .iter()
+            .find(|c| c.uuid == coffee_id)
+            .map_or("", |c| c.name.as_str());
+        let block = Block::bordered()
+            .title(format!("{}- {name} ", self.title()))
+            .border_set(border::ROUNDED);
+        let table = self.entry_table(&self.history_entries(coffee_id));
+        StatefulWidget::render(
+            table.block(block),
+            area,
+            buf,
+            &mut self.state.stats.history_state,
+        );
+    }
+
+    fn render_stats_summary(&mut self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title(self.title())
+            .border_set(border::ROUNDED);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        let stats = self.coffee_stats();
+        // a blank line under the table, and the rest of the figures under that
+        let table_height = if stats.is_empty() {
+            0
+        } else {
+            stats.len() as u16 + 2
+        };
+        let [table_area, rest] =
+            Layout::vertical([Constraint::Max(table_height), Constraint::Fill(1)]).areas(inner);
+        if !stats.is_empty() {
+            StatefulWidget::render(
+                self.coffee_stats_table(&stats),
+                table_area,
+                buf,
+                &mut self.state.stats.coffees,
+            );
+        }
+        let mut lines = vec![Line::from("  Spend by month").bold()];
+        let spend = self.monthly_spend();
+        if spend.is_empty() {
+            lines.push(Line::from(
+                "    Nothing yet: give a coffee a bag size and a price to see what its shots cost.",
+            ));
+        }
+        for month in spend.iter().rev() {
+            lines.push(Line::from(format!(
+                "    {:<16} {:>10}  ({} {}, {} each)",
+                month.month.format("%B %Y").to_string(),
+                self.money(month.total),
+                month.shots,
+                if month.shots == 1 { "shot" } else { "shots" },
+                self.money(month.total / month.shots as f64),
+            )));
+        }
+        let drinks = self.drink_counts();
+        if !drinks.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from("  Espressos by drink").bold());
+        }
+        for (drink, count) in drinks {
+            lines.push(Line::from(format!("    {drink:<16} {count:>10}")));
+        }
+        lines.push(Line::from(""));
+        lines.extend(self.consistency_lines());
+        lines.extend(self.consumption_lines());
+        lines.extend(self.caffeine_lines());
+        Paragraph::new(lines).render(rest, buf);
+    }
+
+    pub(crate) fn render_footer_stats(&self, area: Rect, buf: &mut Buffer) {
+        let controls = match self.state.stats.sub {
+            _ if self.state.range_picker.is_some() => self.range_picker_controls(),
+            SubView::Summary => self.controls(STATS_KEYS, area.width),
+            SubView::TimeHistogram(_) | SubView::Correlations | SubView::Costs => Line::from(vec![
+                " Controls:".into(),
+                " Period ".into(),
+                "<P>".fg(self.theme.accent).bold(),
+                " | Back ".into(),
+                "<q> ".fg(self.theme.accent).bold(),
+            ]),
+            SubView::History(_) => Line::from(vec![
+                " Controls:".into(),
+                " Next ".into(),
+                "<j>".fg(self.theme.accent).bold(),
+                " | Previous ".into(),
+                "<k>".fg(self.theme.accent).bold(),
+                " | Period ".into(),
+                "<P>".fg(self.theme.accent).bold(),
+                " | Back ".into(),
+                "<q> ".fg(self.theme.accent).bold(),
+            ]),
+            SubView::ShotCounts { .. } => Line::from(vec![
+                " Controls:".into(),
+                " Coffees/roasters ".into(),
+                "<g>".fg(self.theme.accent).bold(),
+                " | Period ".into(),
+                "<P>".fg(self.theme.accent).bold(),
+                " | Back ".into(),
+                "<q> ".fg(self.theme.accent).bold(),
+            ]),
+        };
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
@@ -0,0 +1,885 @@
+//! Reading and writing the coffee log. The default backend is a JSON file in the data directory,
+//! which can be encrypted with a passphrase (see [`crypto`]); an SQLite database can be used
+//! instead (see [`sqlite`]).
+
+mod migrate;
+mod sqlite;
+
+use std::{
+    fmt, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local, NaiveDate};
+use uuid::Uuid;
+
+use crate::{
+    crypto::{self, Cipher},
+    dial_in::DialInSession,
+    fields::BrewMethod,
+    filter::Filter,
+    freshness::{self, DAY_FMT},
+    json::{self, invalid, FromJson, ToJson, Value},
+    maintenance::{Maintenance, Task},
+    milk::{Drink, Milk, MilkType},
+    sort::Sort,
+    targets::Target,
+    tasting::{Taste, MAX_RATING, MAX_SCORE},
+    views::SavedView,
+    Basket, Brewer, Coffee, Entry, Grinder, RoastLevel, Roaster, Water,
+};
+
+const DATA_FILE: &str = "data.json";
+const DB_FILE: &str = "data.db";
+/// number of `<file>.N` copies kept next to the data file
+const BACKUP_COUNT: usize = 5;
+
+pub use sqlite::SqliteStorage;
+
+/// a place the dataset can be loaded from and written back to
+pub trait Storage: fmt::Debug {
+    /// reads the whole dataset, returning an empty one if nothing has been written yet
+    fn load(&self) -> io::Result<Dataset>;
+    /// replaces whatever is stored with `data`
+    fn save(&self, data: &Dataset) -> io::Result<()>;
+    /// location of the backing file, for messages
+    fn path(&self) -> &Path;
+}
+
+/// everything that gets persisted between sessions
+#[derive(Debug, Default, Clone)]
+pub struct Dataset {
+    pub(crate) entries: Vec<Entry>,
+    pub(crate) coffees: Vec<Coffee>,
+    pub(crate) grinders: Vec<Grinder>,
+    pub(crate) brewers: Vec<Brewer>,
+    pub(crate) roasters: Vec<Roaster>,
+    pub(crate) waters: Vec<Water>,
+    pub(crate) baskets: Vec<Basket>,
+    pub(crate) dial_in_sessions: Vec<DialInSession>,
+    pub(crate) views: Vec<SavedView>,
+}
+
+/// how and where the data is stored, shared by all profiles
+#[derive(Debug)]
+pub struct Options {
+    pub data_dir: PathBuf,
+    pub sqlite: bool,
+    pub encrypt: bool,
+}
+
+impl Options {
+    /// the unnamed default profile lives directly in the data directory, named ones each get a
+    /// directory under `profiles/`
+    pub fn profile_dir(&self, profile: Option<&str>) -> PathBuf {
+        match profile {
+            None => self.data_dir.clone(),
+            Some(name) => self.data_dir.join("profiles").join(name),
+        }
+    }
+}
+
+/// profile names end up in paths, so keep them to letters, digits, `-` and `_`
+pub fn valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// picks the backend for a profile. If encryption is on, or the JSON data file is already
+/// encrypted, `passphrase` is called to ask for the passphrase; its argument is true when the file
+/// isn't encrypted yet, so the caller can have the new passphrase confirmed.
+pub fn open(
+    options: &Options,
+    profile: Option<&str>,
+    passphrase: impl FnOnce(bool) -> io::Result<String>,
+) -> io::Result<Box<dyn Storage>> {
+    if let Some(name) = profile
+        && !valid_profile_name(name)
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid profile name `{name}`"),
+        ));
+    }
+    let dir = options.profile_dir(profile);
+    if options.sqlite {
+        if options.encrypt {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "encryption is only supported for the JSON data file",
+            ));
+        }
+        return Ok(Box::new(SqliteStorage::new(dir.join(DB_FILE))));
+    }
+    let path = dir.join(DATA_FILE);
+    let salt = match fs::read(&path) {
+        Ok(bytes) => crypto::salt_of(&bytes),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+    let cipher = if options.encrypt || salt.is_some() {
+        Some(Cipher::new(passphrase(salt.is_none())?, salt)?)
+    } else {
+        None
+    };
+    Ok(Box::new(JsonStorage { path, cipher }))
+}
+
+fn backup_path(path: &Path, n: usize) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".{n}"));
+    backup.into()
+}
+
+/// shifts `<file>.1`… up by one, dropping the oldest, then copies the live file to `<file>.1`
+pub fn rotate_backups(path: &Path) -> io::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    for n in (1..BACKUP_COUNT).rev() {
+        let from = backup_path(path, n);
+        if from.exists() {
+            fs::rename(&from, backup_path(path, n + 1))?;
+        }
+    }
+    fs::copy(path, backup_path(path, 1))?;
+    Ok(())
+}
+
+/// replaces the live file with backup `n`. The file being replaced is rotated in as backup 1, so
+/// a restore can itself be rolled back.
+pub fn restore_backup(path: &Path, n: usize) -> io::Result<()> {
+    let backup = backup_path(path, n);
+    if !backup.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no backup {}", backup.display()),
+        ));
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".restore");
+    fs::copy(&backup, &tmp)?;
+    rotate_backups(path)?;
+    fs::rename(&tmp, path)
+}
+
+/// whether the file at `path` is encrypted, reading no more of it than the header
+fn encrypted_on_disk(path: &Path) -> bool {
+    let mut head = Vec::new();
+    fs::File::open(path)
+        .and_then(|file| file.take(64).read_to_end(&mut head))
+        .is_ok_and(|_| crypto::is_encrypted(&head))
+}
+
+/// encrypts the plain copies of the data file kept from before encryption was turned on, and
+/// removes what a restore or save that didn't finish left behind
+fn encrypt_backups(path: &Path, cipher: &Cipher) -> io::Result<()> {
+    for n in 1..=BACKUP_COUNT {
+        let backup = backup_path(path, n);
+        match fs::read(&backup) {
+            Ok(bytes) if !crypto::is_encrypted(&bytes) => {
+                fs::write(&backup, cipher.encrypt(&bytes)?)?;
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    for leftover in [".restore", ".tmp"] {
+        let mut leftover_path = path.as_os_str().to_owned();
+        leftover_path.push(leftover);
+        match fs::remove_file(leftover_path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// the default backend: the whole dataset as one pretty-printed JSON document, optionally
+/// encrypted
+#[derive(Debug)]
+pub struct JsonStorage {
+    path: PathBuf,
+    cipher: Option<Cipher>,
+}
+
+impl Storage for JsonStorage {
+    fn load(&self) -> io::Result<Dataset> {
+        let mut bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Dataset::default()),
+            Err(e) => return Err(e),
+        };
+        // a plain file is still readable with a cipher set, it gets encrypted on the next save
+        if crypto::is_encrypted(&bytes) {
+            let cipher = self
+                .cipher
+                .as_ref()
+                .ok_or_else(|| invalid("the data file is encrypted"))?;
+            bytes = cipher.decrypt(&bytes)?;
+        }
+        let contents = String::from_utf8(bytes).map_err(|e| invalid(format!("data file: {e}")))?;
+        Dataset::from_json(&json::parse(&contents)?)
+    }
+
+    /// goes through a temporary file so that a failed write never leaves a truncated data file
+    /// behind. The first encrypted save also encrypts the backups of the plain file.
+    fn save(&self, data: &Dataset) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let first_encrypted = self.cipher.is_some() && !encrypted_on_disk(&self.path);
+        let mut tmp = self.path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let mut bytes = data.to_json().to_string_pretty().into_bytes();
+        if let Some(cipher) = &self.cipher {
+            bytes = cipher.encrypt(&bytes)?;
+        }
+        fs::write(&tmp, bytes)?;
+        fs::rename(&tmp, &self.path)?;
+        if first_encrypted && let Some(cipher) = &self.cipher {
+            encrypt_backups(&self.path, cipher)?;
+        }
+        Ok(())
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+fn field<'a>(value: &'a Value, key: &str) -> io::Result<&'a Value> {
+    value
+        .get(key)
+        .ok_or_else(|| invalid(format!("missing field `{key}`")))
+}
+
+fn get_str<'a>(value: &'a Value, key: &str) -> io::Result<&'a str> {
+    field(value, key)?
+        .as_str()
+        .ok_or_else(|| invalid(format!("field `{key}` should be a string")))
+}
+
+fn get_f64(value: &Value, key: &str) -> io::Result<f64> {
+    field(value, key)?
+        .as_f64()
+        .ok_or_else(|| invalid(format!("field `{key}` should be a number")))
+}
+
+fn get_opt_f64(value: &Value, key: &str) -> io::Result<Option<f64>> {
+    match field(value, key)? {
+        Value::Null => Ok(None),
+        _ => get_f64(value, key).map(Some),
+    }
+}
+
+fn get_bool(value: &Value, key: &str) -> io::Result<bool> {
+    field(value, key)?
+        .as_bool()
+        .ok_or_else(|| invalid(format!("field `{key}` should be a boolean")))
+}
+
+fn get_uuid(value: &Value, key: &str) -> io::Result<Uuid> {
+    Uuid::parse_str(get_str(value, key)?)
+        .map_err(|e| invalid(format!("field `{key}` is not a valid uuid: {e}")))
+}
+
+fn get_opt_uuid(value: &Value, key: &str) -> io::Result<Option<Uuid>> {
+    match field(value, key)? {
+        Value::Null => Ok(None),
+        _ => get_uuid(value, key).map(Some),
+    }
+}
+
+fn get_datetime(value: &Value, key: &str) -> io::Result<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(get_str(value, key)?)
+        .map(|dt| dt.with_timezone(&Local))
+        .map_err(|e| invalid(format!("field `{key}` is not a valid date: {e}")))
+}
+
+fn get_opt_datetime(value: &Value, key: &str) -> io::Result<Option<DateTime<Local>>> {
+    match field(value, key)? {
+        Value::Null => Ok(None),
+        _ => get_datetime(value, key).map(Some),
+    }
+}
+
+fn get_opt_day(value: &Value, key: &str) -> io::Result<Option<NaiveDate>> {
+    match field(value, key)? {
+        Value::Null => Ok(None),
+        _ => freshness::parse_day(get_str(value, key)?)
+            .map(Some)
+            .ok_or_else(|| invalid(format!("field `{key}` is not a valid day"))),
+    }
+}
+
+fn day_to_json(day: Option<NaiveDate>) -> Value {
+    day.map_or(Value::Null, |d| d.format(DAY_FMT).to_string().into())
+}
+
+fn get_opt_score(value: &Value, key: &str) -> io::Result<Option<u8>> {
+    get_opt_whole(value, key, 0, MAX_SCORE)
+}
+
+/// an optional whole number from `min` to `max`, like a score or rating
+fn get_opt_whole(value: &Value, key: &str, min: u8, max: u8) -> io::Result<Option<u8>> {
+    match get_opt_f64(value, key)? {
+        None => Ok(None),
+        Some(n) if (f64::from(min)..=f64::from(max)).contains(&n) && n.fract() == 0.0 => {
+            Ok(Some(n as u8))
+        }
+        Some(_) => Err(invalid(format!(
+            "field `{key}` should be a whole number from {min} to {max}"
+        ))),
+    }
+}
+
+fn get_vec<T: FromJson>(value: &Value, key: &str) -> io::Result<Vec<T>> {
+    field(value, key)?
+        .as_array()
+        .ok_or_else(|| invalid(format!("field `{key}` should be an array")))?
+        .iter()
+        .map(T::from_json)
+        .collect()
+}
+
+fn get_strings(value: &Value, key: &str) -> io::Result<Vec<String>> {
+    field(value, key)?
+        .as_array()
+        .ok_or_else(|| invalid(format!("field `{key}` should be an array")))?
+        .iter()
+        .map(|v| {
+            v.as_str()
+                .map(String::from)
+                .ok_or_else(|| invalid(format!("field `{key}` should only hold strings")))
+        })
+        .collect()
+}
+
+fn to_array<T: ToJson>(items: &[T]) -> Value {
+    Value::Array(items.iter().map(ToJson::to_json).collect())
+}
+
+impl ToJson for Dataset {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("version".into(), f64::from(migrate::CURRENT_VERSION).into()),
+            ("coffees".into(), to_array(&self.coffees)),
+            ("grinders".into(), to_array(&self.grinders)),
+            ("brewers".into(), to_array(&self.brewers)),
+            ("roasters".into(), to_array(&self.roasters)),
+            ("waters".into(), to_array(&self.waters)),
+            ("baskets".into(), to_array(&self.baskets)),
+            ("dial_in_sessions".into(), to_array(&self.dial_in_sessions)),
+            ("views".into(), to_array(&self.views)),
+            ("entries".into(), to_array(&self.entries)),
+        ])
+    }
+}
+
+impl FromJson for Dataset {
+    /// accepts documents in any older format version, upgrading them first
+    fn from_json(value: &Value) -> io::Result<Self> {
+        let value = &migrate::upgrade(value.clone())?;
+        Ok(Self {
+            entries: get_vec(value, "entries")?,
+            coffees: get_vec(value, "coffees")?,
+            grinders: get_vec(value, "grinders")?,
+            brewers: get_vec(value, "brewers")?,
+            roasters: get_vec(value, "roasters")?,
+            waters: get_vec(value, "waters")?,
+            baskets: get_vec(value, "baskets")?,
+            dial_in_sessions: get_vec(value, "dial_in_sessions")?,
+            views: get_vec(value, "views")?,
+        })
+    }
+}
+
+impl ToJson for Entry {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("dt_added".into(), self.dt_added.to_rfc3339().into()),
+            ("dt_taken".into(), self.dt_taken.to_rfc3339().into()),
+            ("coffee_id".into(), self.coffee_id.to_string().into()),
+            ("grinder_id".into(), self.grinder_id.to_string().into()),
+            (
+                "brewer_id".into(),
+                self.brewer_id
+                    .map_or(Value::Null, |id| id.to_string().into()),
+            ),
+            (
+                "water_id".into(),
+                self.water_id
+                    .map_or(Value::Null, |id| id.to_string().into()),
+            ),
+            (
+                "basket_id".into(),
+                self.basket_id
+                    .map_or(Value::Null, |id| id.to_string().into()),
+            ),
+            ("method".into(), self.method.key().into()),
+            ("grind_setting".into(), self.grind_setting.into()),
+            ("duration".into(), self.duration.into()),
+            ("dose".into(), self.dose.into()),
+            ("output".into(), self.output.into()),
+            (
+                "tds_percent".into(),
+                self.tds_percent.map_or(Value::Null, Value::from),
+            ),
+            (
+                "temperature_c".into(),
+                self.temperature_c.map_or(Value::Null, Value::from),
+            ),
+            ("bloom_time".into(), self.bloom_time.into()),
+            ("pours".into(), self.pours.into()),
+            (
+                "preinfusion_s".into(),
+                self.preinfusion_s.map_or(Value::Null, Value::from),
+            ),
+            (
+                "pressure_bar".into(),
+                self.pressure_bar.map_or(Value::Null, Value::from),
+            ),
+            (
+                "milk".into(),
+                self.milk.as_ref().map_or(Value::Null, ToJson::to_json),
+            ),
+            ("favorite".into(), self.favorite.into()),
+            ("reference".into(), self.reference.into()),
+            (
+                "rating".into(),
+                self.rating.map_or(Value::Null, |r| f64::from(r).into()),
+            ),
+            ("taste".into(), self.taste.to_json()),
+            (
+                "tags".into(),
+                Value::Array(self.tags.iter().map(|t| t.as_str().into()).collect()),
+            ),
+            (
+                "flavors".into(),
+                Value::Array(self.flavors.iter().map(|f| f.as_str().into()).collect()),
+            ),
+            ("custom".into(), {
+                // sorted, so that the file doesn't change with the map's order
+                let mut custom: Vec<(String, Value)> = self
+                    .custom
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect();
+                custom.sort_by(|a, b| a.0.cmp(&b.0));
+                Value::Object(custom)
+            }),
+            ("notes".into(), self.notes.as_str().into()),
+            (
+                "photos".into(),
+                Value::Array(self.photos.iter().map(|p| p.as_str().into()).collect()),
+            ),
+        ])
+    }
+}
+
+impl FromJson for Entry {
+    fn from_json(value: &Value) -> io::Result<Self> {
+        Ok(Self {
+            dt_added: get_datetime(value, "dt_added")?,
+            dt_taken: get_datetime(value, "dt_taken")?,
+            coffee_id: get_uuid(value, "coffee_id")?,
+            grinder_id: get_uuid(value, "grinder_id")?,
+            brewer_id: get_opt_uuid(value, "brewer_id")?,
+            water_id: get_opt_uuid(value, "water_id")?,
+            basket_id: get_opt_uuid(value, "basket_id")?,
+            method: {
+                let key = get_str(value, "method")?;
+                BrewMethod::from_key(key)
+                    .ok_or_else(|| invalid(format!("unknown brew method `{key}`")))?
+            },
+            grind_setting: get_f64(value, "grind_setting")?,
+            duration: get_f64(value, "duration")?,
+            dose: get_f64(value, "dose")?,
+            output: get_f64(value, "output")?,
+            tds_percent: get_opt_f64(value, "tds_percent")?,
+            temperature_c: get_opt_f64(value, "temperature_c")?,
+            bloom_time: get_f64(value, "bloom_time")?,
+            pours: get_f64(value, "pours")?,
+            preinfusion_s: get_opt_f64(value, "preinfusion_s")?,
+            pressure_bar: get_opt_f64(value, "pressure_bar")?,
+            milk: match field(value, "milk")? {
+                Value::Null => None,
+                milk => Some(Milk::from_json(milk)?),
+            },
+            favorite: get_bool(value, "favorite")?,
+            reference: get_bool(value, "reference")?,
+            rating: get_opt_whole(value, "rating", 1, MAX_RATING)?,
+            taste: Taste::from_json(field(value, "taste")?)?,
+            tags: get_strings(value, "tags")?,
+            flavors: get_strings(value, "flavors")?,
+            custom: match field(value, "custom")? {
+                Value::Object(fields) => fields.iter().cloned().collect(),
+                _ => return Err(invalid("field `custom` should be an object")),
+            },
+            notes: get_str(value, "notes")?.to_string(),
+            photos: get_strings(value, "photos")?,
+        })
+    }
+}
+
+impl ToJson for Target {
+    fn to_json(&self) -> Value {
+        let number = |n: Option<f64>| n.map_or(Value::Null, Value::from);
+        Value::Object(vec![
+            ("dose".into(), number(self.dose)),
+            ("ratio".into(), number(self.ratio)),
+            ("time".into(), number(self.time)),
+            ("grind".into(), number(self.grind)),
+        ])
+    }
+}
+
+impl FromJson for Target {
+    fn from_json(value: &Value) -> io::Result<Self> {
+        Ok(Self {
+            dose: get_opt_f64(value, "dose")?,
+            ratio: get_opt_f64(value, "ratio")?,
+            time: get_opt_f64(value, "time")?,
+            grind: get_opt_f64(value, "grind")?,
+        })
+    }
+}
+
+impl ToJson for Milk {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("drink".into(), self.drink.key().into()),
+            ("milk_type".into(), self.milk_type.key().into()),
+            (
+                "amount_ml".into(),
+                self.amount_ml.map_or(Value::Null, Value::from),
+            ),
+        ])
+    }
+}
+
+impl FromJson for Milk {
+    fn from_json(value: &Value) -> io::Result<Self> {
+        Ok(Self {
+            drink: {
+                let key = get_str(value, "drink")?;
+                Drink::from_key(key).ok_or_else(|| invalid(format!("unknown drink `{key}`")))?
+            },
+            milk_type: {
+                let key = get_str(value, "milk_type")?;
+                MilkType::from_key(key)
+                    .ok_or_else(|| invalid(format!("unknown milk type `{key}`")))?
+            },
+            amount_ml: get_opt_f64(value, "amount_ml")?,
+        })
+    }
+}
+
+impl ToJson for Taste {
+    fn to_json(&self) -> Value {
+        let score = |s: Option<u8>| s.map_or(Value::Null, |s| f64::from(s).into());
+        Value::Object(vec![
+            ("acidity".into(), score(self.acidity)),
+            ("sweetness".into(), score(self.sweetness)),
+            ("body".into(), score(self.body)),
+            ("bitterness".into(), score(self.bitterness)),
+            ("aftertaste".into(), score(self.aftertaste)),
+        ])
+    }
+}
+
+impl FromJson for Taste {
+    fn from_json(value: &Value) -> io::Result<Self> {
+        Ok(Self {
+            acidity: get_opt_score(value, "acidity")?,
+            sweetness: get_opt_score(value, "sweetness")?,
+            body: get_opt_score(value, "body")?,
+            bitterness: get_opt_score(value, "bitterness")?,
+            aftertaste: get_opt_score(value, "aftertaste")?,
+        })
+    }
+}
+
+impl ToJson for Coffee {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("uuid".into(), self.uuid.to_string().into()),
+            ("name".into(), self.name.as_str().into()),
+            (
+                "roaster_id".into(),
+                self.roaster_id
+                    .map_or(Value::Null, |id| id.to_string().into()),
+            ),
+            ("roast_date".into(), day_to_json(self.roast_date)),
+            ("open_date".into(), day_to_json(self.open_date)),
+            ("frozen_date".into(), day_to_json(self.frozen_date)),
+            ("thawed_date".into(), day_to_json(self.thawed_date)),
+            ("country".into(), self.country.as_str().into()),
+            ("region".into(), self.region.as_str().into()),
+            ("process".into(), self.process.as_str().into()),
+            ("varietal".into(), self.varietal.as_str().into()),
+            ("roast_level".into(), self.roast_level.key().into()),
+            (
+                "bag_size_g".into(),
+                self.bag_size_g.map_or(Value::Null, Value::from),
+            ),
+            ("remaining_g".into(), self.remaining_g.into()),
+            (
+                "bag_price".into(),
+                self.bag_price.map_or(Value::Null, Value::from),
+            ),
+            ("target".into(), self.target.to_json()),
+            ("archived".into(), self.archived.into()),
+            ("decaf".into(), self.decaf.into()),
+            (
+                "caffeine_mg_per_g".into(),
+                self.caffeine_mg_per_g.map_or(Value::Null, Value::from),
+            ),
+        ])
+    }
+}
+
+impl FromJson for Coffee {
+    fn from_json(value: &Value) -> io::Result<Self> {
+        Ok(Self {
+            uuid: get_uuid(value, "uuid")?,
+            name: get_str(value, "name")?.to_string(),
+            roaster_id: get_opt_uuid(value, "roaster_id")?,
+            roast_date: get_opt_day(value, "roast_date")?,
+            open_date: get_opt_day(value, "open_date")?,
+            frozen_date: get_opt_day(value, "frozen_date")?,
+            thawed_date: get_opt_day(value, "thawed_date")?,
+            country: get_str(value, "country")?.to_string(),
+            region: get_str(value, "region")?.to_string(),
+            process: get_str(value, "process")?.to_string(),
+            varietal: get_str(value, "varietal")?.to_string(),
+            roast_level: {
+                let key = get_str(value, "roast_level")?;
+                RoastLevel::from_key(key)
+                    .ok_or_else(|| invalid(format!("unknown roast level `{key}`")))?
+            },
+            bag_size_g: get_opt_f64(value, "bag_size_g")?,
+            remaining_g: get_f64(value, "remaining_g")?,
+            bag_price: get_opt_f64(value, "bag_price")?,
+            target: Target::from_json(field(value, "target")?)?,
+            archived: get_bool(value, "archived")?,
+            decaf: get_bool(value, "decaf")?,
+            caffeine_mg_per_g: get_opt_f64(value, "caffeine_mg_per_g")?,
+        })
+    }
+}
+
+impl ToJson for Grinder {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("uuid".into(), self.uuid.to_string().into()),
+            ("name".into(), self.name.as_str().into()),
+            ("burrs_fitted".into(), day_to_json(self.burrs_fitted)),
+            (
+                "burr_life_kg".into(),
+                self.burr_life_kg.map_or(Value::Null, Value::from),
+            ),
+        ])
+    }
+}
+
+impl FromJson for Grinder {
+    fn from_json(value: &Value) -> io::Result<Self> {
+        Ok(Self {
+            uuid: get_uuid(value, "uuid")?,
+            name: get_str(value, "name")?.to_string(),
+            burrs_fitted: get_opt_day(value, "burrs_fitted")?,
+            burr_life_kg: get_opt_f64(value, "burr_life_kg")?,
+        })
+    }
+}
+
+impl ToJson for Brewer {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("uuid".into(), self.uuid.to_string().into()),
+            ("name".into(), self.name.as_str().into()),
+            ("maintenance".into(), to_array(&self.maintenance)),
+        ])
+    }
+}
+
+impl FromJson for Brewer {
+    fn from_json(value: &Value) -> io::Result<Self> {
+        Ok(Self {
+            uuid: get_uuid(value, "uuid")?,
+            name: get_str(value, "name")?.to_string(),
+            maintenance: get_vec(value, "maintenance")?,
+        })
+    }
+}
+
+impl ToJson for Maintenance {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("task".into(), self.task.key().into()),
+            ("day".into(), day_to_json(Some(self.day))),
+        ])
+    }
+}
+
+impl FromJson for Maintenance {
+    fn from_json(value: &Value) -> io::Result<Self> {
+        Ok(Self {
+            task: {
+                let key = get_str(value, "task")?;
+                Task::from_key(key)
+                    .ok_or_else(|| invalid(format!("unknown maintenance task `{key}`")))?
+            },
+            day: get_opt_day(value, "day")?
+                .ok_or_else(|| invalid("field `day` should be a day"))?,
+        })
+    }
+}
+
+impl ToJson for Roaster {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("uuid".into(), self.uuid.to_string().into()),
+            ("name".into(), self.name.as_str().into()),
+            ("website".into(), self.website.as_str().into()),
+            ("city".into(), self.city.as_str().into()),
+        ])
+    }
+}
+
+impl FromJson for Roaster {
+    fn from_json(value: &Value) -> io::Result<Self> {
+        Ok(Self {
+            uuid: get_uuid(value, "uuid")?,
+            name: get_str(value, "name")?.to_string(),
+            website: get_str(value, "website")?.to_string(),
+            city: get_str(value, "city")?.to_string(),
+        })
+    }
+}
+
+impl ToJson for Water {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("uuid".into(), self.uuid.to_string().into()),
+            ("name".into(), self.name.as_str().into()),
+            (
+                "gh_ppm".into(),
+                self.gh_ppm.map_or(Value::Null, Value::from),
+            ),
+            (
+                "kh_ppm".into(),
+                self.kh_ppm.map_or(Value::Null, Value::from),
+            ),
+            ("recipe".into(), self.recipe.as_str().into()),
+        ])
+    }
+}
+
+impl FromJson for Water {
+    fn from_json(value: &Value) -> io::Result<Self> {
+        Ok(Self {
+            uuid: get_uuid(value, "uuid")?,
+            name: get_str(value, "name")?.to_string(),
+            gh_ppm: get_opt_f64(value, "gh_ppm")?,
+            kh_ppm: get_opt_f64(value, "kh_ppm")?,
+            recipe: get_str(value, "recipe")?.to_string(),
+        })
+    }
+}
+
+impl ToJson for Basket {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("uuid".into(), self.uuid.to_string().into()),
+            ("name".into(), self.name.as_str().into()),
+            ("brand".into(), self.brand.as_str().into()),
+            (
+                "size_g".into(),
+                self.size_g.map_or(Value::Null, Value::from),
+            ),
+            ("precision".into(), self.precision.into()),
+        ])
+    }
+}
+
+impl FromJson for Basket {
+    fn from_json(value: &Value) -> io::Result<Self> {
+        Ok(Self {
+            uuid: get_uuid(value, "uuid")?,
+            name: get_str(value, "name")?.to_string(),
+            brand: get_str(value, "brand")?.to_string(),
+            size_g: get_opt_f64(value, "size_g")?,
+            precision: get_bool(value, "precision")?,
+        })
+    }
+}
+
+impl ToJson for DialInSession {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("uuid".into(), self.uuid.to_string().into()),
+            ("coffee_id".into(), self.coffee_id.to_string().into()),
+            ("started".into(), self.started.to_rfc3339().into()),
+            (
+                "dialed".into(),
+                self.dialed.map_or(Value::Null, |dt| dt.to_rfc3339().into()),
+            ),
+        ])
+    }
+}
+
+impl FromJson for DialInSession {
+    fn from_json(value: &Value) -> io::Result<Self> {
+        Ok(Self {
+            uuid: get_uuid(value, "uuid")?,
+            coffee_id: get_uuid(value, "coffee_id")?,
+            started: get_datetime(value, "started")?,
+            dialed: get_opt_datetime(value, "dialed")?,
+        })
+    }
+}
+
+/// the filter and sort are kept as they're typed, an empty string for none
+impl ToJson for SavedView {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("name".into(), self.name.as_str().into()),
+            (
+                "filter".into(),
+                self.filter.as_ref().map_or("", Filter::text).into(),
+            ),
+            (
+                "sort".into(),
+                self.sort.map(Sort::spec).unwrap_or_default().into(),
+            ),
+        ])
+    }
+}
+
+impl FromJson for SavedView {
+    fn from_json(value: &Value) -> io::Result<Self> {
+        let name = get_str(value, "name")?.to_string();
+        let filter = match get_str(value, "filter")? {
+            "" => None,
+            text => Some(Filter::parse(text).map_err(|e| invalid(format!("view `{name}`: {e}")))?),
+        };
+        let sort = match get_str(value, "sort")? {
+            "" => None,
+            spec => {
+                let mut words = spec.split_whitespace();
+                let sort = words
+                    .next()
+                    .and_then(|field| Sort::parse(field, words.next()));
+                Some(sort.ok_or_else(|| invalid(format!("view `{name}`: bad sort `{spec}`")))?)
+            }
+        };
+        Ok(Self { name, filter, sort })
+    }
+}
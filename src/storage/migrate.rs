@@ -0,0 +1,336 @@
+//! Upgrading data written by older versions of the app.
+//!
+//! The document carries a `version` number. When loading, every migration from that version up
+//! to [`CURRENT_VERSION`] is applied to the raw JSON before it is turned into a `Dataset`. When a
+//! field is added to one of the stored types, bump `CURRENT_VERSION` and add a migration that
+//! fills the field in on existing records.
+
+use std::io;
+
+use crate::json::{invalid, Value};
+
+pub const CURRENT_VERSION: u32 = 29;
+
+type Migration = fn(&mut Value) -> io::Result<()>;
+
+/// `MIGRATIONS[n]` upgrades a document from version `n` to version `n + 1`
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize] = [
+    v0_unversioned,
+    v1_brewers,
+    v2_brew_methods,
+    v3_roasters,
+    v4_roast_dates,
+    v5_coffee_origins,
+    v6_bag_inventory,
+    v7_waters,
+    v8_baskets,
+    v9_tasting_scores,
+    v10_ratings,
+    v11_tags,
+    v12_temperatures,
+    v13_preinfusion,
+    v14_tds,
+    v15_bag_prices,
+    v16_burrs,
+    v17_custom_fields,
+    v18_targets,
+    v19_dial_in_sessions,
+    v20_photos,
+    v21_flavors,
+    v22_archived_coffees,
+    v23_milk,
+    v24_maintenance,
+    v25_frozen_coffees,
+    v26_caffeine,
+    v27_views,
+    v28_reference_shots,
+];
+
+/// brings `doc` up to the current version, refusing documents written by a newer version
+pub fn upgrade(mut doc: Value) -> io::Result<Value> {
+    let version = match doc.get("version") {
+        None => 0,
+        Some(Value::Number(n)) if *n >= 0.0 && n.fract() == 0.0 => *n as u32,
+        Some(_) => return Err(invalid("`version` should be a whole number")),
+    };
+    if version > CURRENT_VERSION {
+        return Err(invalid(format!(
+            "data was written by a newer version of the app (format {version}, this build reads up to {CURRENT_VERSION})"
+        )));
+    }
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(&mut doc)?;
+    }
+    doc.set("version", Value::from(f64::from(CURRENT_VERSION)));
+    Ok(doc)
+}
+
+/// files from before versioning have the same layout as version 1, they only lack the number
+fn v0_unversioned(_doc: &mut Value) -> io::Result<()> {
+    Ok(())
+}
+
+/// the records in the document's `key` array
+fn records<'a>(doc: &'a mut Value, key: &str) -> io::Result<&'a mut Vec<Value>> {
+    match doc {
+        Value::Object(fields) => match fields.iter_mut().find(|(k, _)| k == key) {
+            Some((_, Value::Array(records))) => Ok(records),
+            _ => Err(invalid(format!("`{key}` should be an array"))),
+        },
+        _ => Err(invalid("the data file should hold an object")),
+    }
+}
+
+fn entries(doc: &mut Value) -> io::Result<&mut Vec<Value>> {
+    records(doc, "entries")
+}
+
+/// version 2 added brewers, which entries can optionally refer to
+fn v1_brewers(doc: &mut Value) -> io::Result<()> {
+    doc.set("brewers", Value::Array(Vec::new()));
+    for entry in entries(doc)? {
+        entry.set("brewer_id", Value::Null);
+    }
+    Ok(())
+}
+
+/// version 3 added the brew method, and the fields only some methods have. Everything logged
+/// before then was espresso.
+fn v2_brew_methods(doc: &mut Value) -> io::Result<()> {
+    for entry in entries(doc)? {
+        entry.set("method", "espresso".into());
+        for key in ["bloom_time", "pours", "pressure"] {
+            entry.set(key, 0.0.into());
+        }
+    }
+    Ok(())
+}
+
+/// version 4 added roasters, which coffees can optionally refer to
+fn v3_roasters(doc: &mut Value) -> io::Result<()> {
+    doc.set("roasters", Value::Array(Vec::new()));
+    for coffee in records(doc, "coffees")? {
+        coffee.set("roaster_id", Value::Null);
+    }
+    Ok(())
+}
+
+/// version 5 added the roast and open dates of coffees, which older ones don't know
+fn v4_roast_dates(doc: &mut Value) -> io::Result<()> {
+    for coffee in records(doc, "coffees")? {
+        coffee.set("roast_date", Value::Null);
+        coffee.set("open_date", Value::Null);
+    }
+    Ok(())
+}
+
+/// version 6 added where a coffee comes from, how it was processed and how dark it was roasted
+fn v5_coffee_origins(doc: &mut Value) -> io::Result<()> {
+    for coffee in records(doc, "coffees")? {
+        for key in ["country", "region", "process", "varietal"] {
+            coffee.set(key, "".into());
+        }
+        coffee.set("roast_level", "unknown".into());
+    }
+    Ok(())
+}
+
+/// version 7 added keeping track of how much is left in a coffee's bag, which starts off untracked
+fn v6_bag_inventory(doc: &mut Value) -> io::Result<()> {
+    for coffee in records(doc, "coffees")? {
+        coffee.set("bag_size_g", Value::Null);
+        coffee.set("remaining_g", 0.0.into());
+    }
+    Ok(())
+}
+
+/// version 8 added waters, which entries can optionally refer to
+fn v7_waters(doc: &mut Value) -> io::Result<()> {
+    doc.set("waters", Value::Array(Vec::new()));
+    for entry in entries(doc)? {
+        entry.set("water_id", Value::Null);
+    }
+    Ok(())
+}
+
+/// version 9 added espresso baskets, which entries can optionally refer to
+fn v8_baskets(doc: &mut Value) -> io::Result<()> {
+    doc.set("baskets", Value::Array(Vec::new()));
+    for entry in entries(doc)? {
+        entry.set("basket_id", Value::Null);
+    }
+    Ok(())
+}
+
+/// version 10 added tasting scores, none of which older entries have
+fn v9_tasting_scores(doc: &mut Value) -> io::Result<()> {
+    for entry in entries(doc)? {
+        let taste = ["acidity", "sweetness", "body", "bitterness", "aftertaste"]
+            .into_iter()
+            .map(|key| (key.to_string(), Value::Null))
+            .collect();
+        entry.set("taste", Value::Object(taste));
+    }
+    Ok(())
+}
+
+/// version 11 added an overall rating, which older entries don't have
+fn v10_ratings(doc: &mut Value) -> io::Result<()> {
+    for entry in entries(doc)? {
+        entry.set("rating", Value::Null);
+    }
+    Ok(())
+}
+
+/// version 12 added tags on entries
+fn v11_tags(doc: &mut Value) -> io::Result<()> {
+    for entry in entries(doc)? {
+        entry.set("tags", Value::Array(Vec::new()));
+    }
+    Ok(())
+}
+
+/// version 13 added the brew temperature, which older entries didn't record
+fn v12_temperatures(doc: &mut Value) -> io::Result<()> {
+    for entry in entries(doc)? {
+        entry.set("temperature_c", Value::Null);
+    }
+    Ok(())
+}
+
+/// version 14 added pre-infusion time and made pressure optional, as `pressure_bar`. A pressure
+/// of 0 was never measured, so it becomes unset.
+fn v13_preinfusion(doc: &mut Value) -> io::Result<()> {
+    for entry in entries(doc)? {
+        let pressure = match entry.remove("pressure") {
+            Some(Value::Number(bar)) if bar > 0.0 => Value::Number(bar),
+            _ => Value::Null,
+        };
+        entry.set("preinfusion_s", Value::Null);
+        entry.set("pressure_bar", pressure);
+    }
+    Ok(())
+}
+
+/// version 15 added the TDS, for working out extraction yield
+fn v14_tds(doc: &mut Value) -> io::Result<()> {
+    for entry in entries(doc)? {
+        entry.set("tds_percent", Value::Null);
+    }
+    Ok(())
+}
+
+/// version 16 added the price of a coffee's bag
+fn v15_bag_prices(doc: &mut Value) -> io::Result<()> {
+    for coffee in records(doc, "coffees")? {
+        coffee.set("bag_price", Value::Null);
+    }
+    Ok(())
+}
+
+/// version 17 added when a grinder's burrs were fitted and how long they last
+fn v16_burrs(doc: &mut Value) -> io::Result<()> {
+    for grinder in records(doc, "grinders")? {
+        grinder.set("burrs_fitted", Value::Null);
+        grinder.set("burr_life_kg", Value::Null);
+    }
+    Ok(())
+}
+
+/// version 18 added values for the custom fields set up in the config
+fn v17_custom_fields(doc: &mut Value) -> io::Result<()> {
+    for entry in entries(doc)? {
+        entry.set("custom", Value::Object(Vec::new()));
+    }
+    Ok(())
+}
+
+/// version 19 added the recipe a coffee is dialed in towards, with nothing set
+fn v18_targets(doc: &mut Value) -> io::Result<()> {
+    for coffee in records(doc, "coffees")? {
+        let target = ["dose", "ratio", "time", "grind"]
+            .into_iter()
+            .map(|key| (key.to_string(), Value::Null))
+            .collect();
+        coffee.set("target", Value::Object(target));
+    }
+    Ok(())
+}
+
+/// version 20 added dial-in sessions, of which there are none yet
+fn v19_dial_in_sessions(doc: &mut Value) -> io::Result<()> {
+    doc.set("dial_in_sessions", Value::Array(Vec::new()));
+    Ok(())
+}
+
+/// version 21 added photos to entries
+fn v20_photos(doc: &mut Value) -> io::Result<()> {
+    for entry in entries(doc)? {
+        entry.set("photos", Value::Array(Vec::new()));
+    }
+    Ok(())
+}
+
+/// version 22 added flavor descriptors to entries
+fn v21_flavors(doc: &mut Value) -> io::Result<()> {
+    for entry in entries(doc)? {
+        entry.set("flavors", Value::Array(Vec::new()));
+    }
+    Ok(())
+}
+
+/// version 23 added archiving coffees, none of which are yet
+fn v22_archived_coffees(doc: &mut Value) -> io::Result<()> {
+    for coffee in records(doc, "coffees")? {
+        coffee.set("archived", Value::Bool(false));
+    }
+    Ok(())
+}
+
+/// version 24 added the milk drink an espresso went into, which older entries don't have
+fn v23_milk(doc: &mut Value) -> io::Result<()> {
+    for entry in entries(doc)? {
+        entry.set("milk", Value::Null);
+    }
+    Ok(())
+}
+
+/// version 25 added a log of the maintenance done on each brewer
+fn v24_maintenance(doc: &mut Value) -> io::Result<()> {
+    for brewer in records(doc, "brewers")? {
+        brewer.set("maintenance", Value::Array(Vec::new()));
+    }
+    Ok(())
+}
+
+/// version 26 added freezing coffees, which none of the existing ones have been
+fn v25_frozen_coffees(doc: &mut Value) -> io::Result<()> {
+    for coffee in records(doc, "coffees")? {
+        coffee.set("frozen_date", Value::Null);
+        coffee.set("thawed_date", Value::Null);
+    }
+    Ok(())
+}
+
+/// version 27 added marking coffees as decaf and giving them their own caffeine content
+fn v26_caffeine(doc: &mut Value) -> io::Result<()> {
+    for coffee in records(doc, "coffees")? {
+        coffee.set("decaf", Value::Bool(false));
+        coffee.set("caffeine_mg_per_g", Value::Null);
+    }
+    Ok(())
+}
+
+/// version 28 added saved views of the entry list
+fn v27_views(doc: &mut Value) -> io::Result<()> {
+    doc.set("views", Value::Array(Vec::new()));
+    Ok(())
+}
+
+/// version 29 added marking an entry as its coffee's reference shot
+fn v28_reference_shots(doc: &mut Value) -> io::Result<()> {
+    for entry in entries(doc)? {
+        entry.set("reference", Value::Bool(false));
+    }
+    Ok(())
+}
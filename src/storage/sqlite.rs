@@ -0,0 +1,254 @@
+//! SQLite backend. There is no SQLite binding among our dependencies, so this drives the
+//! `sqlite3` command line shell, which has to be on the `PATH`.
+//!
+//! Each table has the columns needed for keys and foreign keys, plus a `data` column holding the
+//! full record as JSON. That way new fields on `Entry` and friends don't need a schema change.
+//! A missing reference, like the nil coffee of an entry logged before any coffee was added, is
+//! stored as NULL so it doesn't break the foreign key. Saving drops and recreates the tables, so a
+//! file made with an older schema gets the current one the next time it's saved.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use uuid::Uuid;
+
+use crate::json::{self, invalid, FromJson, ToJson};
+
+use super::{migrate::CURRENT_VERSION, Dataset, Storage};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS roasters (
+    uuid TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS coffees (
+    uuid TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    roaster_id TEXT REFERENCES roasters (uuid),
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS grinders (
+    uuid TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS brewers (
+    uuid TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS waters (
+    uuid TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS baskets (
+    uuid TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS entries (
+    id INTEGER PRIMARY KEY,
+    dt_taken TEXT NOT NULL,
+    coffee_id TEXT REFERENCES coffees (uuid),
+    grinder_id TEXT REFERENCES grinders (uuid),
+    brewer_id TEXT REFERENCES brewers (uuid),
+    water_id TEXT REFERENCES waters (uuid),
+    basket_id TEXT REFERENCES baskets (uuid),
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS dial_in_sessions (
+    uuid TEXT PRIMARY KEY,
+    coffee_id TEXT REFERENCES coffees (uuid),
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS views (
+    name TEXT PRIMARY KEY,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS entries_coffee_id ON entries (coffee_id);
+CREATE INDEX IF NOT EXISTS entries_grinder_id ON entries (grinder_id);
+";
+
+/// assembles the whole dataset into one JSON document on the SQLite side so it can be read with
+/// the same code as the JSON backend. The format version is kept in `PRAGMA user_version`.
+const SELECT_ALL: &str = "
+SELECT json_object(
+    'version', (SELECT user_version FROM pragma_user_version),
+    'coffees', (SELECT json_group_array(json(data)) FROM (SELECT data FROM coffees ORDER BY rowid)),
+    'grinders', (SELECT json_group_array(json(data)) FROM (SELECT data FROM grinders ORDER BY rowid)),
+    'brewers', (SELECT json_group_array(json(data)) FROM (SELECT data FROM brewers ORDER BY rowid)),
+    'roasters', (SELECT json_group_array(json(data)) FROM (SELECT data FROM roasters ORDER BY rowid)),
+    'waters', (SELECT json_group_array(json(data)) FROM (SELECT data FROM waters ORDER BY rowid)),
+    'baskets', (SELECT json_group_array(json(data)) FROM (SELECT data FROM baskets ORDER BY rowid)),
+    'dial_in_sessions', (SELECT json_group_array(json(data)) FROM (SELECT data FROM dial_in_sessions ORDER BY rowid)),
+    'views', (SELECT json_group_array(json(data)) FROM (SELECT data FROM views ORDER BY rowid)),
+    'entries', (SELECT json_group_array(json(data)) FROM (SELECT data FROM entries ORDER BY id))
+);
+";
+
+#[derive(Debug)]
+pub struct SqliteStorage {
+    path: PathBuf,
+}
+
+impl SqliteStorage {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// feeds `sql` to the sqlite3 shell and returns what it printed
+    fn run(&self, sql: &str) -> io::Result<String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut child = Command::new("sqlite3")
+            .arg("-bail")
+            .arg(&self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| io::Error::new(e.kind(), format!("could not run sqlite3: {e}")))?;
+        child.stdin.take().unwrap().write_all(sql.as_bytes())?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "sqlite3: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        String::from_utf8(output.stdout).map_err(|e| invalid(format!("sqlite3 output: {e}")))
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn load(&self) -> io::Result<Dataset> {
+        let out = self.run(&format!("{SCHEMA}{SELECT_ALL}"))?;
+        Dataset::from_json(&json::parse(out.trim())?)
+    }
+
+    /// rewrites every table inside a single transaction; with `-bail` the shell stops at the first
+    /// failing statement (e.g. a foreign key violation) and the transaction is never committed
+    fn save(&self, data: &Dataset) -> io::Result<()> {
+        // the tables referring to others go first
+        let mut sql = String::from("PRAGMA foreign_keys = ON;\nBEGIN;\n");
+        for table in [
+            "entries",
+            "dial_in_sessions",
+            "views",
+            "coffees",
+            "roasters",
+            "grinders",
+            "brewers",
+            "waters",
+            "baskets",
+        ] {
+            sql.push_str(&format!("DROP TABLE IF EXISTS {table};\n"));
+        }
+        sql.push_str(SCHEMA);
+        for roaster in &data.roasters {
+            sql.push_str(&format!(
+                "INSERT INTO roasters (uuid, name, data) VALUES ({}, {}, {});\n",
+                quote(&roaster.uuid.to_string()),
+                quote(&roaster.name),
+                quote(&roaster.to_json().to_string()),
+            ));
+        }
+        for coffee in &data.coffees {
+            sql.push_str(&format!(
+                "INSERT INTO coffees (uuid, name, roaster_id, data) \
+                 VALUES ({}, {}, {}, {});\n",
+                quote(&coffee.uuid.to_string()),
+                quote(&coffee.name),
+                reference(coffee.roaster_id),
+                quote(&coffee.to_json().to_string()),
+            ));
+        }
+        for grinder in &data.grinders {
+            sql.push_str(&format!(
+                "INSERT INTO grinders (uuid, name, data) VALUES ({}, {}, {});\n",
+                quote(&grinder.uuid.to_string()),
+                quote(&grinder.name),
+                quote(&grinder.to_json().to_string()),
+            ));
+        }
+        for brewer in &data.brewers {
+            sql.push_str(&format!(
+                "INSERT INTO brewers (uuid, name, data) VALUES ({}, {}, {});\n",
+                quote(&brewer.uuid.to_string()),
+                quote(&brewer.name),
+                quote(&brewer.to_json().to_string()),
+            ));
+        }
+        for item in &data.waters {
+            sql.push_str(&format!(
+                "INSERT INTO waters (uuid, name, data) VALUES ({}, {}, {});\n",
+                quote(&item.uuid.to_string()),
+                quote(&item.name),
+                quote(&item.to_json().to_string()),
+            ));
+        }
+        for item in &data.baskets {
+            sql.push_str(&format!(
+                "INSERT INTO baskets (uuid, name, data) VALUES ({}, {}, {});\n",
+                quote(&item.uuid.to_string()),
+                quote(&item.name),
+                quote(&item.to_json().to_string()),
+            ));
+        }
+        for session in &data.dial_in_sessions {
+            sql.push_str(&format!(
+                "INSERT INTO dial_in_sessions (uuid, coffee_id, data) VALUES ({}, {}, {});\n",
+                quote(&session.uuid.to_string()),
+                reference(Some(session.coffee_id)),
+                quote(&session.to_json().to_string()),
+            ));
+        }
+        for view in &data.views {
+            sql.push_str(&format!(
+                "INSERT INTO views (name, data) VALUES ({}, {});\n",
+                quote(&view.name),
+                quote(&view.to_json().to_string()),
+            ));
+        }
+        for entry in &data.entries {
+            sql.push_str(&format!(
+                "INSERT INTO entries \
+                 (dt_taken, coffee_id, grinder_id, brewer_id, water_id, basket_id, data) \
+                 VALUES ({}, {}, {}, {}, {}, {}, {});\n",
+                quote(&entry.dt_taken.to_rfc3339()),
+                reference(Some(entry.coffee_id)),
+                reference(Some(entry.grinder_id)),
+                reference(entry.brewer_id),
+                reference(entry.water_id),
+                reference(entry.basket_id),
+                quote(&entry.to_json().to_string()),
+            ));
+        }
+        sql.push_str(&format!(
+            "PRAGMA user_version = {CURRENT_VERSION};\nCOMMIT;\n"
+        ));
+        self.run(&sql).map(|_| ())
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// SQL string literal
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// a foreign key's value, NULL for none or the nil id of a coffee or grinder that was never picked
+fn reference(uuid: Option<Uuid>) -> String {
+    uuid.filter(|uuid| !uuid.is_nil())
+        .map_or(String::from("NULL"), |uuid| quote(&uuid.to_string()))
+}
@@ -0,0 +1,103 @@
+//! What to change for the next shot, worked out from how far an entry was from its coffee's
+//! targets with a few rules of thumb: a shot that ran fast wants a finer grind and a slow one a
+//! coarser grind, and a ratio that's off wants the shot stopped sooner or later. It's shown in an
+//! entry's details, after adding an entry, and for `:suggest`.
+
+use ratatui::{style::Stylize, text::Line};
+
+use crate::{App, Entry, Phase};
+
+/// how far off each can be and still count as on target
+const DOSE_TOLERANCE_G: f64 = 0.2;
+const RATIO_TOLERANCE: f64 = 0.1;
+const TIME_TOLERANCE_S: f64 = 2.0;
+/// how many seconds of shot time a half step of grind setting is taken to move
+const SECS_PER_HALF_STEP: f64 = 4.0;
+
+impl App {
+    /// e.g. "grind 0.5 finer, extend shot 3 s to 40.0 g", if the entry can be compared with any of
+    /// its coffee's targets
+    pub(crate) fn suggestion(&self, entry: &Entry) -> Option<String> {
+        let target = self
+            .data
+            .coffees
+            .iter()
+            .find(|c| c.uuid == entry.coffee_id)?
+            .target;
+        let mut changes = Vec::new();
+        // whether any target could be compared with, since a time or yield left at zero wasn't
+        // measured
+        let mut compared = false;
+        if let Some(dose) = target.dose {
+            compared = true;
+            let off = entry.dose - dose;
+            if off.abs() > DOSE_TOLERANCE_G {
+                let way = if off > 0.0 { "less" } else { "more" };
+                changes.push(format!("dose {:.1} g {way}", off.abs()));
+            }
+        }
+        if let Some(time) = target.time
+            && entry.duration > 0.0
+        {
+            compared = true;
+            let off = entry.duration - time;
+            if off.abs() > TIME_TOLERANCE_S {
+                let steps = (off.abs() / SECS_PER_HALF_STEP).round().max(1.0) * 0.5;
+                let way = if off < 0.0 { "finer" } else { "coarser" };
+                changes.push(format!("grind {steps:.1} {way}"));
+            }
+        }
+        if let Some(ratio) = target.ratio
+            && entry.dose > 0.0
+            && entry.output > 0.0
+        {
+            compared = true;
+            let off = entry.output / entry.dose - ratio;
+            if off.abs() > RATIO_TOLERANCE {
+                let output = ratio * entry.dose;
+                // grams a second over the shot, to say how much longer or shorter to run it
+                let flow = (entry.duration > 0.0).then(|| entry.output / entry.duration);
+                changes.push(match flow {
+                    Some(flow) if off < 0.0 => format!(
+                        "extend shot {:.0} s to {output:.1} g",
+                        (output - entry.output) / flow
+                    ),
+                    Some(flow) => format!(
+                        "stop {:.0} s sooner, at {output:.1} g",
+                        (entry.output - output) / flow
+                    ),
+                    _ => format!("stop at {output:.1} g"),
+                });
+            }
+        }
+        if !compared {
+            return None;
+        }
+        Some(if changes.is_empty() {
+            String::from("on target, keep it as it is")
+        } else {
+            changes.join(", ")
+        })
+    }
+
+    /// the suggestion, for the footer's second line
+    pub(crate) fn suggestion_line(&self) -> Line<'static> {
+        Line::from(vec![
+            " Next time: ".fg(self.theme.accent).bold(),
+            self.state.suggestion.clone().unwrap_or_default().into(),
+        ])
+    }
+
+    /// handles `:suggest`, for the entry being edited or else the one selected in the list
+    pub(crate) fn suggest(&mut self) {
+        let idx = match self.phase {
+            Phase::EditEntry(idx) => Some(idx),
+            _ => self.selected_entry(),
+        };
+        self.state.suggestion = idx.and_then(|i| self.data.entries.get(i)).map(|entry| {
+            self.suggestion(entry).unwrap_or_else(|| {
+                String::from("give the coffee a target dose, ratio or time to get suggestions")
+            })
+        });
+    }
+}
@@ -0,0 +1,265 @@
+//! Keeping the coffee log in sync between machines, with one of two backends:
+//!
+//! - with `git_sync` set in the config file, the data directory is a git repository. The data is
+//!   committed on `:w` and on quitting, and `:sync` pulls from and pushes to the `git_remote`.
+//! - with `webdav_url` set, `:sync` exchanges the data file with a copy in that WebDAV folder.
+//!
+//! Either way, finding the data changed on both sides stops the sync with a dialog asking which
+//! version to keep. The outcome of the last sync is shown in the footer.
+
+mod git;
+mod webdav;
+
+use std::io;
+
+use chrono::{DateTime, Local};
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::{keymap::SYNC_CONFLICT_KEYS, undo::History, App, AppState, Phase};
+
+use self::{
+    git::{Pull, Repo},
+    webdav::{Outcome, Remote},
+};
+
+/// which side to keep when resolving a conflict
+#[derive(Debug, Clone, Copy)]
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+enum Backend {
+    Git(Repo),
+    WebDav(Remote),
+}
+
+/// a sync that stopped halfway, waiting for the user to pick a side
+#[derive(Debug)]
+pub struct SyncConflict {
+    files: Vec<String>,
+    /// whether to push once the conflict is resolved
+    push: bool,
+}
+
+/// how the last sync went, for the footer
+#[derive(Debug)]
+pub struct SyncStatus {
+    at: DateTime<Local>,
+    outcome: Result<&'static str, String>,
+}
+
+impl App {
+    fn sync_backend(&self) -> io::Result<Option<Backend>> {
+        if self.config.git_sync {
+            let repo = Repo::open(
+                &self.storage_options.data_dir,
+                self.config.git_remote.as_deref(),
+            )?;
+            return Ok(Some(Backend::Git(repo)));
+        }
+        if let Some(url) = &self.config.webdav_url {
+            let path = self.storage.path();
+            let file = path
+                .strip_prefix(&self.storage_options.data_dir)
+                .unwrap_or(path);
+            return Ok(Some(Backend::WebDav(Remote::new(
+                url,
+                file,
+                self.config.webdav_user.clone(),
+                self.config.webdav_password.clone(),
+            ))));
+        }
+        Ok(None)
+    }
+
+    /// commits the data directory if git sync is on
+    pub(crate) fn commit_data(&self) -> io::Result<()> {
+        if let Some(Backend::Git(repo)) = self.sync_backend()? {
+            repo.commit()?;
+        }
+        Ok(())
+    }
+
+    /// handles `:sync [pull|push]`; without an argument it pulls and then pushes
+    pub(crate) fn sync(&mut self, pull: bool, push: bool) {
+        let outcome = self.try_sync(pull, push);
+        self.record_sync(outcome);
+    }
+
+    fn record_sync(&mut self, outcome: io::Result<&'static str>) {
+        match &outcome {
+            Ok(done) => self.info(format!("Sync: {done}")),
+            Err(e) => self.error(format!("Couldn't sync: {e}")),
+        }
+        self.sync_status = Some(SyncStatus {
+            at: Local::now(),
+            outcome: outcome.map_err(|e| e.to_string()),
+        });
+    }
+
+    fn try_sync(&mut self, pull: bool, push: bool) -> io::Result<&'static str> {
+        let backend = self.sync_backend()?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "set `git_sync` or `webdav_url` in the config file to sync",
+            )
+        })?;
+        if self.last_change.is_some() {
+            self.save()?;
+        }
+        let conflict = |files| SyncConflict { files, push };
+        match backend {
+            Backend::Git(repo) => {
+                repo.commit()?;
+                if pull {
+                    match repo.pull()? {
+                        Pull::Merged => self.reload()?,
+                        Pull::Conflict(files) => return Ok(self.start_conflict(conflict(files))),
+                    }
+                }
+                if push {
+                    repo.push()?;
+                }
+                Ok("synced")
+            }
+            Backend::WebDav(remote) => {
+                match webdav::sync(&remote, self.storage.path(), pull, push)? {
+                    Outcome::UpToDate => Ok("up to date"),
+                    Outcome::Uploaded => Ok("uploaded"),
+                    Outcome::Downloaded => {
+                        self.reload()?;
+                        Ok("downloaded")
+                    }
+                    Outcome::Conflict => {
+                        let file = self.storage.path().file_name().unwrap_or_default();
+                        let files = vec![file.to_string_lossy().into_owned()];
+                        Ok(self.start_conflict(conflict(files)))
+                    }
+                }
+            }
+        }
+    }
+
+    fn start_conflict(&mut self, conflict: SyncConflict) -> &'static str {
+        self.state.sync_conflict = Some(conflict);
+        self.phase = Phase::SyncConflict;
+        "conflict"
+    }
+
+    /// picks up whatever a pull left in the data file
+    fn reload(&mut self) -> io::Result<()> {
+        self.data = self.storage.load()?;
+        self.history = History::new(&self.data);
+        self.last_change = None;
+        self.phase = Phase::ListView;
+        self.state = AppState::default();
+        Ok(())
+    }
+
+    /// settles the pending conflict keeping `side`, or cancels the sync if `side` is `None`
+    pub(crate) fn resolve_sync_conflict(&mut self, side: Option<Side>) {
+        let outcome = self.try_resolve_sync_conflict(side);
+        self.record_sync(outcome);
+    }
+
+    fn try_resolve_sync_conflict(&mut self, side: Option<Side>) -> io::Result<&'static str> {
+        let Some(conflict) = self.state.sync_conflict.take() else {
+            return Ok("synced");
+        };
+        self.phase = Phase::ListView;
+        let backend = self
+            .sync_backend()?
+            .ok_or_else(|| io::Error::other("sync is off"))?;
+        match (backend, side) {
+            (Backend::Git(repo), Some(side)) => {
+                repo.resolve(&conflict.files, side)?;
+                self.reload()?;
+                if conflict.push {
+                    repo.push()?;
+                }
+            }
+            (Backend::Git(repo), None) => {
+                repo.abort_merge()?;
+                return Ok("cancelled");
+            }
+            (Backend::WebDav(remote), Some(side)) => {
+                webdav::resolve(&remote, self.storage.path(), side)?;
+                self.reload()?;
+            }
+            (Backend::WebDav(_), None) => return Ok("cancelled"),
+        }
+        Ok("synced")
+    }
+
+    pub(crate) fn handle_key_events_sync_conflict(&mut self, key_event: KeyEvent) {
+        let side = match key_event.code {
+            KeyCode::Char('m') => Some(Side::Ours),
+            KeyCode::Char('t') => Some(Side::Theirs),
+            KeyCode::Char('q') | KeyCode::Esc => None,
+            _ => return,
+        };
+        self.resolve_sync_conflict(side);
+    }
+
+    pub(crate) fn render_sync_conflict(&mut self, area: Rect, buf: &mut Buffer) {
+        self.render_list_view(area, buf);
+        let Some(conflict) = &self.state.sync_conflict else {
+            return;
+        };
+        let mut lines = vec![
+            Line::from("  The coffee log was changed both here and on the server:"),
+            Line::from(""),
+        ];
+        for file in &conflict.files {
+            lines.push(Line::from(format!("    {file}")));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(
+            "  Keep this machine's version, take the server's, or cancel the sync",
+        ));
+        lines.push(Line::from("  and leave both as they are."));
+        let height = lines.len() as u16 + 3;
+        let [popup] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup] = Layout::horizontal([Constraint::Max(72)])
+            .flex(Flex::Center)
+            .areas(popup);
+        let block = Block::bordered()
+            .title(" Sync conflict ")
+            .border_set(border::ROUNDED);
+        Clear.render(popup, buf);
+        Paragraph::new(lines).block(block).render(popup, buf);
+    }
+
+    pub(crate) fn render_footer_sync_conflict(&self, area: Rect, buf: &mut Buffer) {
+        let controls = self.controls(SYNC_CONFLICT_KEYS, area.width);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+
+    /// the outcome of the last sync, at the right end of the footer's second line
+    pub(crate) fn render_sync_status(&self, area: Rect, buf: &mut Buffer) {
+        let Some(status) = &self.sync_status else {
+            return;
+        };
+        let at = status.at.format("%H:%M");
+        let line = match &status.outcome {
+            Ok(outcome) => Line::from(format!("Last sync {at}: {outcome} ")),
+            Err(e) => Line::from(format!("Sync failed {at}: {e} ").red()),
+        };
+        let [_, row] = Layout::vertical([Constraint::Length(1), Constraint::Length(1)]).areas(area);
+        Paragraph::new(line)
+            .alignment(Alignment::Right)
+            .render(row, buf);
+    }
+}
@@ -0,0 +1,166 @@
+//! Syncing through a git repository in the data directory. There is no git library among our
+//! dependencies, so this drives the `git` command, which has to be on the `PATH`.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use chrono::Local;
+
+use super::Side;
+
+const REMOTE: &str = "origin";
+/// keeps backups and half-written files out of the repository
+const GITIGNORE: &str = "*.[0-9]\n*.tmp\n*.restore\n";
+/// a line-by-line merge of two edited data files could easily produce something that isn't valid
+/// JSON any more, so have git treat every change on both sides as a conflict
+const GITATTRIBUTES: &str = "*.json merge=binary\n*.db merge=binary\n";
+
+/// how a pull went
+#[derive(Debug)]
+pub enum Pull {
+    /// nothing new on the remote, or it merged cleanly
+    Merged,
+    /// the merge stopped on these files, which were changed on both sides
+    Conflict(Vec<String>),
+}
+
+#[derive(Debug)]
+pub struct Repo {
+    dir: PathBuf,
+    /// commits need an author, so use a placeholder where git hasn't been set up with one
+    identity: Vec<&'static str>,
+}
+
+impl Repo {
+    /// opens the repository in `dir`, creating it first if needed, and points `origin` at
+    /// `remote` when one is given
+    pub fn open(dir: &Path, remote: Option<&str>) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let mut repo = Self {
+            dir: dir.to_path_buf(),
+            identity: Vec::new(),
+        };
+        if !dir.join(".git").exists() {
+            repo.run(&["init", "--quiet"])?;
+            fs::write(dir.join(".gitignore"), GITIGNORE)?;
+            fs::write(dir.join(".gitattributes"), GITATTRIBUTES)?;
+        }
+        if !repo.git(&["config", "user.email"])?.status.success() {
+            repo.identity = vec![
+                "-c",
+                "user.name=coffee-tracking",
+                "-c",
+                "user.email=coffee-tracking@localhost",
+            ];
+        }
+        if let Some(url) = remote {
+            match repo.run(&["remote", "get-url", REMOTE]) {
+                Ok(current) if current.trim() == url => {}
+                Ok(_) => _ = repo.run(&["remote", "set-url", REMOTE, url])?,
+                Err(_) => _ = repo.run(&["remote", "add", REMOTE, url])?,
+            }
+        }
+        Ok(repo)
+    }
+
+    fn git(&self, args: &[&str]) -> io::Result<Output> {
+        Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(&self.identity)
+            .args(args)
+            .output()
+            .map_err(|e| io::Error::new(e.kind(), format!("could not run git: {e}")))
+    }
+
+    /// runs git and returns what it printed, failing if it did
+    fn run(&self, args: &[&str]) -> io::Result<String> {
+        let output = self.git(args)?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "git {}: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// commits everything in the data directory, returning whether there was anything to commit
+    pub fn commit(&self) -> io::Result<bool> {
+        self.run(&["add", "--all"])?;
+        if self.git(&["diff", "--cached", "--quiet"])?.status.success() {
+            return Ok(false);
+        }
+        let message = format!(
+            "Update coffee log, {}",
+            Local::now().format("%Y-%m-%d %H:%M")
+        );
+        self.run(&["commit", "--quiet", "--message", &message])?;
+        Ok(true)
+    }
+
+    /// fetches the remote and merges its copy of the current branch
+    pub fn pull(&self) -> io::Result<Pull> {
+        let branch = self.run(&["symbolic-ref", "--short", "HEAD"])?;
+        let branch = branch.trim();
+        self.run(&["fetch", "--quiet", REMOTE])?;
+        let upstream = format!("{REMOTE}/{branch}");
+        // the remote may not have the branch yet, in which case the push creates it
+        if !self
+            .git(&["rev-parse", "--verify", "--quiet", &upstream])?
+            .status
+            .success()
+        {
+            return Ok(Pull::Merged);
+        }
+        let merge = self.git(&[
+            "merge",
+            "--quiet",
+            "--no-edit",
+            "--allow-unrelated-histories",
+            &upstream,
+        ])?;
+        if merge.status.success() {
+            return Ok(Pull::Merged);
+        }
+        let conflicted = self.run(&["diff", "--name-only", "--diff-filter=U"])?;
+        let conflicted: Vec<String> = conflicted.lines().map(String::from).collect();
+        if conflicted.is_empty() {
+            return Err(io::Error::other(format!(
+                "git merge: {}",
+                String::from_utf8_lossy(&merge.stderr).trim()
+            )));
+        }
+        Ok(Pull::Conflict(conflicted))
+    }
+
+    pub fn push(&self) -> io::Result<()> {
+        self.run(&["push", "--quiet", "--set-upstream", REMOTE, "HEAD"])
+            .map(|_| ())
+    }
+
+    /// finishes a conflicted merge by taking one side's version of each conflicted file
+    pub fn resolve(&self, files: &[String], side: Side) -> io::Result<()> {
+        let side = match side {
+            Side::Ours => "--ours",
+            Side::Theirs => "--theirs",
+        };
+        for file in files {
+            // a file deleted on one side has no version to check out there
+            if self.git(&["checkout", side, "--", file])?.status.success() {
+                self.run(&["add", "--", file])?;
+            } else {
+                self.run(&["rm", "--quiet", "--", file])?;
+            }
+        }
+        self.run(&["commit", "--quiet", "--no-edit"]).map(|_| ())
+    }
+
+    pub fn abort_merge(&self) -> io::Result<()> {
+        self.run(&["merge", "--abort"]).map(|_| ())
+    }
+}
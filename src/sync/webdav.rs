@@ -0,0 +1,305 @@
+//! Syncing the data file with a copy on a WebDAV server such as Nextcloud, for machines without
+//! git. Like the git backend this drives an external command, `curl`.
+//!
+//! Next to the data file, `<file>.webdav` remembers the server's ETag and a hash of the local
+//! file as of the last sync. Comparing against those tells which side changed since; if both did,
+//! it's a conflict for the user to resolve.
+
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use crate::crypto;
+
+use super::Side;
+
+/// what a sync did
+#[derive(Debug)]
+pub enum Outcome {
+    UpToDate,
+    Uploaded,
+    Downloaded,
+    /// both copies changed since the last sync
+    Conflict,
+}
+
+#[derive(Debug)]
+pub struct Remote {
+    /// the remote copy of the data file
+    url: String,
+    user: Option<String>,
+    password: Option<String>,
+}
+
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    etag: Option<String>,
+}
+
+/// the `<file>.webdav` state
+#[derive(Debug, Default, PartialEq)]
+struct Synced {
+    etag: Option<String>,
+    hash: Option<String>,
+}
+
+impl Remote {
+    /// `base` is the WebDAV folder the data directory is mirrored into; `file` is the data file's
+    /// path relative to the data directory
+    pub fn new(base: &str, file: &Path, user: Option<String>, password: Option<String>) -> Self {
+        let mut url = base.trim_end_matches('/').to_string();
+        for part in file.iter() {
+            url.push('/');
+            url.push_str(&part.to_string_lossy());
+        }
+        Self {
+            url,
+            user,
+            password,
+        }
+    }
+
+    /// runs curl against `url`, with the credentials passed on stdin so they don't show up in the
+    /// process list. The body is written to `output` if given, and thrown away otherwise.
+    fn curl(&self, url: &str, args: &[OsString], output: Option<&Path>) -> io::Result<Response> {
+        let scratch = env::temp_dir().join(format!("coffee-tracking-dav-{}", std::process::id()));
+        let mut child = Command::new("curl")
+            .args([
+                "--silent",
+                "--show-error",
+                "--config",
+                "-",
+                "--dump-header",
+                "-",
+            ])
+            .arg("--output")
+            .arg(output.unwrap_or(&scratch))
+            .args(args)
+            .arg(url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| io::Error::new(e.kind(), format!("could not run curl: {e}")))?;
+        let mut stdin = child.stdin.take().unwrap();
+        if let Some(user) = &self.user {
+            let credentials = format!("{user}:{}", self.password.as_deref().unwrap_or_default());
+            writeln!(
+                stdin,
+                "user = \"{}\"",
+                credentials.replace('\\', "\\\\").replace('"', "\\\"")
+            )?;
+        }
+        drop(stdin);
+        let out = child.wait_with_output()?;
+        if output.is_none() {
+            _ = fs::remove_file(&scratch);
+        }
+        if !out.status.success() {
+            return Err(io::Error::other(
+                String::from_utf8_lossy(&out.stderr).trim().to_string(),
+            ));
+        }
+        parse_headers(&String::from_utf8_lossy(&out.stdout))
+    }
+
+    fn head(&self) -> io::Result<Option<String>> {
+        let response = self.curl(&self.url, &["--head".into()], None)?;
+        match response.status {
+            404 => Ok(None),
+            200..=299 => Ok(Some(response.etag.unwrap_or_default())),
+            status => Err(http_error("HEAD", status)),
+        }
+    }
+
+    /// downloads the remote copy over `local`, returning its ETag
+    fn get(&self, local: &Path) -> io::Result<String> {
+        let mut tmp = local.as_os_str().to_owned();
+        tmp.push(".download");
+        let tmp = PathBuf::from(tmp);
+        if let Some(dir) = local.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let response = self.curl(&self.url, &[], Some(&tmp))?;
+        if !(200..=299).contains(&response.status) {
+            _ = fs::remove_file(&tmp);
+            return Err(http_error("GET", response.status));
+        }
+        fs::rename(&tmp, local)?;
+        Ok(response.etag.unwrap_or_default())
+    }
+
+    /// uploads `local`, only replacing the remote copy if it still has `expected` as its ETag (or
+    /// doesn't exist, for `Some(None)`). Returns `None` if it had changed.
+    fn put(&self, local: &Path, expected: Option<Option<&str>>) -> io::Result<Option<String>> {
+        let mut args: Vec<OsString> = vec!["--upload-file".into(), local.into()];
+        match expected {
+            Some(Some(etag)) => {
+                args.extend(["--header".into(), format!("If-Match: {etag}").into()])
+            }
+            Some(None) => args.extend(["--header".into(), "If-None-Match: *".into()]),
+            None => {}
+        }
+        let mut response = self.curl(&self.url, &args, None)?;
+        // the folders above the file may not exist yet on the first upload
+        if response.status == 409 {
+            self.make_parents()?;
+            response = self.curl(&self.url, &args, None)?;
+        }
+        match response.status {
+            412 => Ok(None),
+            200..=299 => match response.etag {
+                Some(etag) => Ok(Some(etag)),
+                // not every server sends the new ETag back
+                None => Ok(Some(self.head()?.unwrap_or_default())),
+            },
+            status => Err(http_error("PUT", status)),
+        }
+    }
+
+    fn make_parents(&self) -> io::Result<()> {
+        let path_start = self.url.find("://").map_or(0, |i| i + 3);
+        let path_start = path_start + self.url[path_start..].find('/').unwrap_or(0);
+        let slashes: Vec<usize> = self.url[path_start..]
+            .match_indices('/')
+            .map(|(i, _)| path_start + i)
+            .skip(1)
+            .collect();
+        for end in slashes {
+            // fails harmlessly with 405 for folders that already exist
+            self.curl(
+                &self.url[..=end],
+                &["--request".into(), "MKCOL".into()],
+                None,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn http_error(method: &str, status: u16) -> io::Error {
+    io::Error::other(format!("WebDAV {method} failed with HTTP status {status}"))
+}
+
+/// picks the status and ETag out of curl's header dump. With redirects or `100 Continue` there
+/// are several responses in it, the last one is what counts.
+fn parse_headers(headers: &str) -> io::Result<Response> {
+    let mut response = None;
+    for line in headers.lines() {
+        if line.starts_with("HTTP/") {
+            let status = line
+                .split_whitespace()
+                .nth(1)
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| io::Error::other(format!("bad status line: {line}")))?;
+            response = Some(Response { status, etag: None });
+        } else if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("etag")
+            && let Some(response) = &mut response
+        {
+            response.etag = Some(value.trim().to_string());
+        }
+    }
+    response.ok_or_else(|| io::Error::other("no response from the WebDAV server"))
+}
+
+fn state_path(local: &Path) -> PathBuf {
+    let mut path = local.as_os_str().to_owned();
+    path.push(".webdav");
+    path.into()
+}
+
+impl Synced {
+    fn load(local: &Path) -> Self {
+        let contents = fs::read_to_string(state_path(local)).unwrap_or_default();
+        let mut lines = contents
+            .lines()
+            .map(|l| Some(l.to_string()).filter(|l| !l.is_empty()));
+        Self {
+            etag: lines.next().flatten(),
+            hash: lines.next().flatten(),
+        }
+    }
+
+    fn save(&self, local: &Path) -> io::Result<()> {
+        fs::write(
+            state_path(local),
+            format!(
+                "{}\n{}\n",
+                self.etag.as_deref().unwrap_or_default(),
+                self.hash.as_deref().unwrap_or_default()
+            ),
+        )
+    }
+}
+
+fn hash_of(local: &Path) -> io::Result<Option<String>> {
+    match fs::read(local) {
+        Ok(bytes) => Ok(Some(
+            crypto::sha256(&[&bytes])
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect(),
+        )),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// brings `local` and the remote copy up to date with each other, as far as `pull` and `push`
+/// allow
+pub fn sync(remote: &Remote, local: &Path, pull: bool, push: bool) -> io::Result<Outcome> {
+    let synced = Synced::load(local);
+    let hash = hash_of(local)?;
+    let etag = remote.head()?;
+    let local_changed = hash != synced.hash;
+    let remote_changed = etag != synced.etag;
+    let etag = match (local_changed, remote_changed) {
+        (false, false) => return Ok(Outcome::UpToDate),
+        (true, true) => return Ok(Outcome::Conflict),
+        (true, false) if push && hash.is_some() => {
+            match remote.put(local, Some(etag.as_deref()))? {
+                Some(etag) => etag,
+                // someone else uploaded in the meantime
+                None => return Ok(Outcome::Conflict),
+            }
+        }
+        (false, true) if pull && etag.is_some() => remote.get(local)?,
+        // deleted on the server, put it back
+        (false, true) if push && hash.is_some() => match remote.put(local, Some(None))? {
+            Some(etag) => etag,
+            None => return Ok(Outcome::Conflict),
+        },
+        _ => return Ok(Outcome::UpToDate),
+    };
+    let outcome = if hash_of(local)? == hash {
+        Outcome::Uploaded
+    } else {
+        Outcome::Downloaded
+    };
+    Synced {
+        etag: Some(etag),
+        hash: hash_of(local)?,
+    }
+    .save(local)?;
+    Ok(outcome)
+}
+
+/// settles a conflict by overwriting one copy with the other
+pub fn resolve(remote: &Remote, local: &Path, side: Side) -> io::Result<()> {
+    let etag = match side {
+        Side::Ours => remote.put(local, None)?.unwrap_or_default(),
+        Side::Theirs => remote.get(local)?,
+    };
+    Synced {
+        etag: Some(etag),
+        hash: hash_of(local)?,
+    }
+    .save(local)
+}
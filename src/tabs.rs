@@ -0,0 +1,142 @@
+//! The tab bar over the top-level screens: the home screen, the entry list, the coffees, grinders
+//! and brewers, and the stats. Tab and Shift-Tab go to the next and previous screen and `1` to `6`
+//! straight to one, whenever the screen isn't in the middle of something else, like typing or a
+//! popup. Screens opened from one of these, like an entry's details, stay under its tab. In the
+//! entry list the numbers are counts for moving, see [`crate::scroll`].
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::Rect,
+    style::{Style, Stylize},
+    widgets::{Tabs, Widget},
+};
+
+use crate::{catalog::Kind, App, Phase};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tab {
+    Home,
+    Entries,
+    Coffees,
+    Grinders,
+    Brewers,
+    Stats,
+}
+
+/// in the order they're shown, and numbered from 1
+const TABS: [Tab; 6] = [
+    Tab::Home,
+    Tab::Entries,
+    Tab::Coffees,
+    Tab::Grinders,
+    Tab::Brewers,
+    Tab::Stats,
+];
+
+impl Tab {
+    fn title(self) -> &'static str {
+        match self {
+            Tab::Home => "Home",
+            Tab::Entries => "Entries",
+            Tab::Coffees => "Coffees",
+            Tab::Grinders => "Grinders",
+            Tab::Brewers => "Brewers",
+            Tab::Stats => "Stats",
+        }
+    }
+
+    fn index(self) -> usize {
+        TABS.iter().position(|&t| t == self).unwrap_or(0)
+    }
+}
+
+impl App {
+    /// the tab the current screen is under, if it's under one
+    pub(crate) fn tab(&self) -> Option<Tab> {
+        match self.phase {
+            Phase::Dashboard => Some(Tab::Home),
+            Phase::ListView
+            | Phase::EditEntry(_)
+            | Phase::NewEntry
+            | Phase::Wizard(_)
+            | Phase::Compare(..) => Some(Tab::Entries),
+            Phase::EditCoffee => Some(Tab::Coffees),
+            Phase::EditGrinder => Some(Tab::Grinders),
+            Phase::EditBrewer => Some(Tab::Brewers),
+            Phase::Stats => Some(Tab::Stats),
+            Phase::Import
+            | Phase::SyncConflict
+            | Phase::EditRoaster
+            | Phase::EditWater
+            | Phase::EditBasket
+            | Phase::DialIn => None,
+        }
+    }
+
+    pub(crate) fn open_tab(&mut self, tab: Tab) {
+        match tab {
+            Tab::Home => self.phase = Phase::Dashboard,
+            Tab::Entries => self.phase = Phase::ListView,
+            Tab::Coffees => self.open_catalog(Kind::Coffee),
+            Tab::Grinders => self.open_catalog(Kind::Grinder),
+            Tab::Brewers => self.open_catalog(Kind::Brewer),
+            Tab::Stats => self.open_stats(),
+        }
+    }
+
+    /// whether the keys for switching tabs would be taken from the screen: only on the tabs
+    /// themselves, and not while a popup is open or something is being typed
+    fn switching_tabs(&self) -> bool {
+        match self.phase {
+            Phase::ListView => {
+                !self.state.confirm_delete
+                    && self.state.confirm_range.is_none()
+                    && self.state.range_picker.is_none()
+            }
+            Phase::EditCoffee | Phase::EditGrinder | Phase::EditBrewer => self.catalog_browsing(),
+            Phase::Stats => self.state.range_picker.is_none(),
+            Phase::Dashboard => true,
+            _ => false,
+        }
+    }
+
+    /// switches tabs for Tab, Shift-Tab and the tabs' numbers, returning whether it did
+    pub(crate) fn handle_key_events_tabs(&mut self, key_event: KeyEvent) -> bool {
+        let Some(current) = self.tab().filter(|_| self.switching_tabs()) else {
+            return false;
+        };
+        let i = current.index();
+        let next = match key_event.code {
+            KeyCode::Tab => TABS[(i + 1) % TABS.len()],
+            KeyCode::BackTab => TABS[(i + TABS.len() - 1) % TABS.len()],
+            KeyCode::Char(c @ '1'..='9') if current != Tab::Entries => {
+                match TABS.get(c as usize - '1' as usize) {
+                    Some(&tab) => tab,
+                    None => return false,
+                }
+            }
+            _ => return false,
+        };
+        if next != current {
+            self.open_tab(next);
+        }
+        true
+    }
+
+    pub(crate) fn render_tab_bar(&self, area: Rect, buf: &mut Buffer) {
+        let Some(current) = self.tab() else {
+            return;
+        };
+        let titles = TABS
+            .iter()
+            .enumerate()
+            .map(|(i, tab)| format!("{} {}", i + 1, tab.title()));
+        Tabs::new(titles)
+            .select(current.index())
+            .style(Style::new().dark_gray())
+            .highlight_style(Style::new().fg(self.theme.accent).bold())
+            .divider("|")
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,84 @@
+//! Free-form tags on entries, like `#channeling` or `#decaf`, for pulling up every shot that had
+//! something in common. Tags are typed with or without the `#` and stored without it, lowercased
+//! so that `#Decaf` and `#decaf` are the same tag.
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{Event, KeyCode, KeyEvent},
+    layout::Rect,
+    style::Stylize,
+    text::Line,
+    widgets::{Paragraph, Widget},
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use crate::{App, Entry, InputMode};
+
+/// the tags in `text`, separated by spaces or commas, without duplicates
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text.split([' ', ',']) {
+        let tag = tag.trim_start_matches('#').to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// e.g. "#channeling #newburrs"
+pub fn format_tags(tags: &[String]) -> String {
+    tags.iter()
+        .map(|t| format!("#{t}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl Entry {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+impl App {
+    /// starts editing an entry's tags as text
+    pub(crate) fn open_tags(&mut self, tags: &[String]) {
+        let mut text = format_tags(tags);
+        if !text.is_empty() {
+            // ready for the next tag
+            text.push(' ');
+        }
+        self.state.edit.input = Input::new(text);
+        self.state.edit.input_mode = InputMode::Editing;
+    }
+
+    pub(crate) fn handle_key_events_tags(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Enter => self.save_input(),
+            KeyCode::Esc => self.state.edit.input_mode = InputMode::Normal,
+            _ => {
+                _ = self.state.edit.input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    /// lists only the entries tagged `tag`, or all of them for `None`
+    pub(crate) fn filter_by_tag(&mut self, tag: Option<&str>) {
+        self.state.tag_filter = tag.and_then(|t| parse_tags(t).into_iter().next());
+        self.state.entry_list_state.select_first();
+    }
+
+    pub(crate) fn render_footer_tags(&self, area: Rect, buf: &mut Buffer) {
+        let controls = Line::from(vec![
+            " Controls:".into(),
+            " Separate tags with ".into(),
+            "<Space>".fg(self.theme.accent).bold(),
+            " | Save ".into(),
+            "<Enter>".fg(self.theme.accent).bold(),
+            " | Cancel ".into(),
+            "<Esc> ".fg(self.theme.accent).bold(),
+        ]);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
@@ -0,0 +1,131 @@
+//! The recipe each coffee is being dialed in towards. Any of the target dose, ratio, time and grind
+//! setting can be set on a coffee, and entries of that coffee show how far off they were.
+//!
+//! When the coffee has a target ratio, changing an entry's dose offers the output that would hit
+//! it, and changing the output offers the dose; `=` takes the offer and any other key lets it go.
+
+use ratatui::{style::Stylize, text::Line};
+
+use crate::{fields::Field, App, Entry};
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Target {
+    /// grams in
+    pub dose: Option<f64>,
+    /// output over dose
+    pub ratio: Option<f64>,
+    /// seconds
+    pub time: Option<f64>,
+    pub grind: Option<f64>,
+}
+
+/// a value for the other side of the ratio, offered after the dose or output was changed
+#[derive(Debug, Clone, Copy)]
+pub struct RatioOffer {
+    field: Field,
+    value: f64,
+    ratio: f64,
+}
+
+/// e.g. "+3.1 s" or "−0.2 ratio", with a proper minus sign
+pub(crate) fn deviation(by: f64, unit: &str) -> String {
+    // so that a hair under zero doesn't show as "−0.0"
+    let by = (by * 10.0).round() / 10.0;
+    let sign = if by > 0.0 {
+        "+"
+    } else if by < 0.0 {
+        "−"
+    } else {
+        "±"
+    };
+    format!("{sign}{:.1} {unit}", by.abs())
+}
+
+impl App {
+    /// how the entry differs from its coffee's targets, e.g. "+3.1 s, −0.2 ratio", if the
+    /// coffee has any
+    pub(crate) fn target_deviation(&self, entry: &Entry) -> Option<String> {
+        let target = self
+            .data
+            .coffees
+            .iter()
+            .find(|c| c.uuid == entry.coffee_id)?
+            .target;
+        let mut parts = Vec::new();
+        if let Some(dose) = target.dose {
+            let unit = self.config.weight_unit;
+            let dose_unit = format!("{} dose", unit.symbol());
+            parts.push(deviation(unit.in_unit(entry.dose - dose), &dose_unit));
+        }
+        if let Some(ratio) = target.ratio
+            && entry.dose > 0.0
+        {
+            parts.push(deviation(entry.output / entry.dose - ratio, "ratio"));
+        }
+        if let Some(time) = target.time {
+            parts.push(deviation(entry.duration - time, "s"));
+        }
+        if let Some(grind) = target.grind {
+            parts.push(deviation(entry.grind_setting - grind, "grind"));
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+
+    /// after `changed` was saved, offers the output or dose that would hit the target ratio, if
+    /// the entry isn't already on it
+    pub(crate) fn offer_ratio(&mut self, changed: Field) {
+        let Some(entry) = self.edited_entry() else {
+            return;
+        };
+        let Some(ratio) = self
+            .data
+            .coffees
+            .iter()
+            .find(|c| c.uuid == entry.coffee_id)
+            .and_then(|c| c.target.ratio)
+            .filter(|&r| r > 0.0)
+        else {
+            return;
+        };
+        let (field, value) = match changed {
+            Field::Dose if entry.dose > 0.0 => (Field::Output, entry.dose * ratio),
+            Field::Output if entry.output > 0.0 => (Field::Dose, entry.output / ratio),
+            _ => return,
+        };
+        let value = (value * 10.0).round() / 10.0;
+        if field
+            .number(entry)
+            .is_some_and(|n| (n - value).abs() >= 0.05)
+        {
+            self.state.ratio_offer = Some(RatioOffer {
+                field,
+                value,
+                ratio,
+            });
+        }
+    }
+
+    /// writes the offered value to the edited entry
+    pub(crate) fn accept_ratio_offer(&mut self, offer: RatioOffer) {
+        if let Some(entry) = self.edited_entry_mut() {
+            offer.field.set_number(entry, offer.value);
+            self.field_saved();
+        }
+    }
+
+    /// the offer, for the footer's second line
+    pub(crate) fn ratio_offer_line(&self) -> Option<Line<'static>> {
+        let offer = self.state.ratio_offer?;
+        Some(Line::from(vec![
+            format!(
+                " {} for 1:{:.1}: {} ",
+                offer.field.label(),
+                offer.ratio,
+                self.weight(offer.value)
+            )
+            .into(),
+            "<=>".fg(self.theme.accent).bold(),
+            " to use it".into(),
+        ]))
+    }
+}
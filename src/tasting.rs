@@ -0,0 +1,101 @@
+//! Scoring how a brew tasted, one aspect at a time, so taste can later be compared against the
+//! parameters that produced it. Scores run from 0 to [`MAX_SCORE`] and are adjusted like a
+//! slider; an aspect that wasn't judged has no score. On top of that, the brew as a whole can be
+//! rated out of [`MAX_RATING`] stars.
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::Rect,
+    style::Stylize,
+    text::Line,
+    widgets::{Paragraph, Widget},
+};
+
+use crate::{App, InputMode};
+
+pub const MAX_SCORE: u8 = 10;
+pub const MAX_RATING: u8 = 5;
+/// where the slider starts for an aspect that hasn't been scored yet
+const START_SCORE: u8 = MAX_SCORE / 2;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Taste {
+    pub acidity: Option<u8>,
+    pub sweetness: Option<u8>,
+    pub body: Option<u8>,
+    pub bitterness: Option<u8>,
+    pub aftertaste: Option<u8>,
+}
+
+/// e.g. "■■■■■■□□□□ 6/10", or "-" for no score
+pub fn slider(score: Option<u8>) -> String {
+    match score {
+        None => String::from("-"),
+        Some(score) => format!(
+            "{}{} {score}/{MAX_SCORE}",
+            "■".repeat(score.into()),
+            "□".repeat((MAX_SCORE - score).into())
+        ),
+    }
+}
+
+/// e.g. "★★★☆☆", or `unrated` for no rating
+pub fn stars(rating: Option<u8>, unrated: &str) -> String {
+    match rating {
+        None => unrated.to_string(),
+        Some(rating) => format!(
+            "{}{}",
+            "★".repeat(rating.into()),
+            "☆".repeat((MAX_RATING - rating).into())
+        ),
+    }
+}
+
+impl App {
+    /// rates the entry in the edit view, or clears its rating with `None`
+    pub(crate) fn rate(&mut self, rating: Option<u8>) {
+        if let Some(entry) = self.edited_entry_mut() {
+            entry.rating = rating;
+            self.field_saved();
+        }
+    }
+
+    /// starts adjusting a score, from its current value or the middle of the scale
+    pub(crate) fn open_score(&mut self, score: Option<u8>) {
+        self.state.edit.score = Some(score.unwrap_or(START_SCORE));
+        self.state.edit.input_mode = InputMode::Editing;
+    }
+
+    pub(crate) fn handle_key_events_score(&mut self, key_event: KeyEvent) {
+        let score = &mut self.state.edit.score;
+        match key_event.code {
+            KeyCode::Char('h' | '-') | KeyCode::Left => {
+                *score = Some(score.map_or(START_SCORE, |s| s.saturating_sub(1)));
+            }
+            KeyCode::Char('l' | '+') | KeyCode::Right => {
+                *score = Some(score.map_or(START_SCORE, |s| (s + 1).min(MAX_SCORE)));
+            }
+            KeyCode::Char('x') | KeyCode::Backspace | KeyCode::Delete => *score = None,
+            KeyCode::Enter => self.save_input(),
+            KeyCode::Esc => self.state.edit.input_mode = InputMode::Normal,
+            _ => {}
+        }
+    }
+
+    pub(crate) fn render_footer_score(&self, area: Rect, buf: &mut Buffer) {
+        let controls = Line::from(vec![
+            " Controls:".into(),
+            " Less/More ".into(),
+            "<h/l>".fg(self.theme.accent).bold(),
+            " | Clear ".into(),
+            "<x>".fg(self.theme.accent).bold(),
+            " | Save ".into(),
+            "<Enter>".fg(self.theme.accent).bold(),
+            " | Cancel ".into(),
+            "<Esc> ".fg(self.theme.accent).bold(),
+        ]);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
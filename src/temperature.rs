@@ -0,0 +1,58 @@
+//! Brew water temperature. It's stored in Celsius and shown in the unit set by the config's
+//! `temperature_unit`, either `"celsius"` or `"fahrenheit"`.
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    /// reads the unit as written in the config, e.g. `"fahrenheit"` or just `"F"`
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key.to_lowercase().as_str() {
+            "c" | "celsius" => Some(TemperatureUnit::Celsius),
+            "f" | "fahrenheit" => Some(TemperatureUnit::Fahrenheit),
+            _ => None,
+        }
+    }
+
+    /// as written to the config
+    pub fn key(self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "celsius",
+            TemperatureUnit::Fahrenheit => "fahrenheit",
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+        }
+    }
+
+    /// a temperature in Celsius, in this unit
+    pub fn in_unit(self, celsius: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    pub fn to_celsius(self, degrees: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => degrees,
+            TemperatureUnit::Fahrenheit => (degrees - 32.0) * 5.0 / 9.0,
+        }
+    }
+
+    /// e.g. "93.0 °C", or "-" for no temperature
+    pub fn format(self, celsius: Option<f64>) -> String {
+        match celsius {
+            None => String::from("-"),
+            Some(c) => format!("{:.1} {}", self.in_unit(c), self.symbol()),
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! A small multi-line text editor for free-form notes. Long lines are wrapped to the width they
+//! are drawn at, and the view scrolls to keep the cursor in sight.
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::Rect,
+    style::{Modifier, Style},
+};
+
+#[derive(Debug)]
+pub struct TextArea {
+    lines: Vec<String>,
+    /// cursor position, as a line and a character (not byte) offset into it
+    row: usize,
+    col: usize,
+    /// first wrapped row on screen
+    scroll: usize,
+}
+
+impl Default for TextArea {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+/// byte offset of character `col` in `s`, or the end of `s`
+fn byte_idx(s: &str, col: usize) -> usize {
+    s.char_indices().nth(col).map_or(s.len(), |(i, _)| i)
+}
+
+impl TextArea {
+    /// starts editing `text` with the cursor at its end
+    pub fn new(text: &str) -> Self {
+        let lines: Vec<String> = text.split('\n').map(String::from).collect();
+        let row = lines.len() - 1;
+        let col = lines[row].chars().count();
+        Self {
+            lines,
+            row,
+            col,
+            scroll: 0,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    fn line_len(&self, row: usize) -> usize {
+        self.lines[row].chars().count()
+    }
+
+    /// applies an editing or cursor movement key, ignoring anything else
+    pub fn input(&mut self, key_event: KeyEvent) {
+        if key_event
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return;
+        }
+        match key_event.code {
+            KeyCode::Char(c) => {
+                let line = &mut self.lines[self.row];
+                line.insert(byte_idx(line, self.col), c);
+                self.col += 1;
+            }
+            KeyCode::Enter => {
+                let line = &mut self.lines[self.row];
+                let rest = line.split_off(byte_idx(line, self.col));
+                self.lines.insert(self.row + 1, rest);
+                self.row += 1;
+                self.col = 0;
+            }
+            KeyCode::Backspace => {
+                if self.col > 0 {
+                    let line = &mut self.lines[self.row];
+                    line.remove(byte_idx(line, self.col - 1));
+                    self.col -= 1;
+                } else if self.row > 0 {
+                    let line = self.lines.remove(self.row);
+                    self.row -= 1;
+                    self.col = self.line_len(self.row);
+                    self.lines[self.row].push_str(&line);
+                }
+            }
+            KeyCode::Delete => {
+                if self.col < self.line_len(self.row) {
+                    let line = &mut self.lines[self.row];
+                    line.remove(byte_idx(line, self.col));
+                } else if self.row + 1 < self.lines.len() {
+                    let next = self.lines.remove(self.row + 1);
+                    self.lines[self.row].push_str(&next);
+                }
+            }
+            KeyCode::Left => {
+                if self.col > 0 {
+                    self.col -= 1;
+                } else if self.row > 0 {
+                    self.row -= 1;
+                    self.col = self.line_len(self.row);
+                }
+            }
+            KeyCode::Right => {
+                if self.col < self.line_len(self.row) {
+                    self.col += 1;
+                } else if self.row + 1 < self.lines.len() {
+                    self.row += 1;
+                    self.col = 0;
+                }
+            }
+            KeyCode::Up if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.col.min(self.line_len(self.row));
+            }
+            KeyCode::Down if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = self.col.min(self.line_len(self.row));
+            }
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = self.line_len(self.row),
+            _ => {}
+        }
+    }
+
+    /// draws the text wrapped to `area`, with the cursor shown as a reversed cell
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let width = area.width as usize;
+        if width == 0 || area.height == 0 {
+            return;
+        }
+        // (line, first character) of every wrapped row, and which of them has the cursor
+        let mut rows = Vec::new();
+        let mut cursor = (0, 0);
+        for (i, line) in self.lines.iter().enumerate() {
+            let len = line.chars().count();
+            // a cursor sitting just past a full row goes at the start of a row of its own
+            let chunks = len.div_ceil(width).max(1)
+                + usize::from(i == self.row && len > 0 && len % width == 0 && self.col == len);
+            if i == self.row {
+                cursor = (rows.len() + self.col / width, self.col % width);
+            }
+            rows.extend((0..chunks).map(|c| (i, c * width)));
+        }
+        let height = area.height as usize;
+        if cursor.0 < self.scroll {
+            self.scroll = cursor.0;
+        } else if cursor.0 >= self.scroll + height {
+            self.scroll = cursor.0 + 1 - height;
+        }
+        for (y, &(i, start)) in rows.iter().skip(self.scroll).take(height).enumerate() {
+            let text: String = self.lines[i].chars().skip(start).take(width).collect();
+            buf.set_string(area.x, area.y + y as u16, text, Style::default());
+        }
+        let (cy, cx) = cursor;
+        if cy >= self.scroll && cy < self.scroll + height {
+            let pos = (area.x + cx as u16, area.y + (cy - self.scroll) as u16);
+            if let Some(cell) = buf.cell_mut(pos) {
+                cell.set_style(Style::default().add_modifier(Modifier::REVERSED));
+            }
+        }
+    }
+}
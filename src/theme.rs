@@ -0,0 +1,138 @@
+//! Colors. The config's `theme` is the name of one of the built-in themes, `dark`, `light`,
+//! `solarized` or `high-contrast`, or an object starting from one of them and changing some of
+//! its colors, which are color names like "cyan" or hex like "#2aa198":
+//!
+//! ```json
+//! "theme": {"base": "solarized", "accent": "#2aa198", "selected": "#073642"}
+//! ```
+//!
+//! `:theme <name>` switches to a built-in theme until the program is closed, and `:theme` on its
+//! own goes back to the config's.
+
+use std::{io, str::FromStr};
+
+use ratatui::style::{palette::tailwind::SLATE, Color, Modifier, Style};
+
+use crate::{
+    json::{invalid, Value},
+    App,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// the text and background everything is drawn on
+    pub base: Style,
+    /// keys in the footer, headings and other things to pick out
+    pub accent: Color,
+    /// the selected row of a list or table
+    pub selected: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            base: Style::new(),
+            accent: Color::Blue,
+            selected: Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD),
+        }
+    }
+}
+
+pub const THEMES: [&str; 4] = ["dark", "light", "solarized", "high-contrast"];
+
+impl Theme {
+    pub fn named(name: &str) -> Option<Self> {
+        let bold = Style::new().add_modifier(Modifier::BOLD);
+        Some(match name {
+            "dark" => Self::default(),
+            "light" => Self {
+                base: Style::new().fg(SLATE.c900).bg(SLATE.c50),
+                accent: Color::Rgb(0x1d, 0x4e, 0xd8),
+                selected: bold.bg(SLATE.c300),
+            },
+            "solarized" => Self {
+                base: Style::new()
+                    .fg(Color::Rgb(0x83, 0x94, 0x96))
+                    .bg(Color::Rgb(0x00, 0x2b, 0x36)),
+                accent: Color::Rgb(0x26, 0x8b, 0xd2),
+                selected: bold
+                    .fg(Color::Rgb(0x93, 0xa1, 0xa1))
+                    .bg(Color::Rgb(0x07, 0x36, 0x42)),
+            },
+            "high-contrast" => Self {
+                base: Style::new().fg(Color::White).bg(Color::Black),
+                accent: Color::LightYellow,
+                selected: bold.fg(Color::Black).bg(Color::White),
+            },
+            _ => return None,
+        })
+    }
+
+    /// reads the config's `theme`, a theme's name or an object changing one's colors
+    pub fn from_config(value: &Value) -> io::Result<Self> {
+        let unknown = |name: &str| {
+            invalid(format!(
+                "there's no theme `{name}`, try one of {}",
+                THEMES.join(", ")
+            ))
+        };
+        match value {
+            Value::Null => Ok(Self::default()),
+            Value::String(name) => Self::named(name).ok_or_else(|| unknown(name)),
+            Value::Object(_) => {
+                let mut theme = match value.get("base") {
+                    None | Some(Value::Null) => Self::default(),
+                    Some(Value::String(name)) => Self::named(name).ok_or_else(|| unknown(name))?,
+                    Some(_) => return Err(invalid("the theme's `base` should be a theme's name")),
+                };
+                if let Some(color) = color(value, "foreground")? {
+                    theme.base = theme.base.fg(color);
+                }
+                if let Some(color) = color(value, "background")? {
+                    theme.base = theme.base.bg(color);
+                }
+                if let Some(color) = color(value, "accent")? {
+                    theme.accent = color;
+                }
+                if let Some(color) = color(value, "selected")? {
+                    theme.selected = theme.selected.bg(color);
+                }
+                Ok(theme)
+            }
+            _ => Err(invalid("`theme` should be a theme's name or an object")),
+        }
+    }
+}
+
+/// one of the theme object's colors
+fn color(value: &Value, key: &str) -> io::Result<Option<Color>> {
+    match value.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Color::from_str(s)
+            .map(Some)
+            .map_err(|_| invalid(format!("the theme's `{key}` isn't a color: {s}"))),
+        Some(_) => Err(invalid(format!("the theme's `{key}` should be a string"))),
+    }
+}
+
+impl App {
+    /// handles `:theme [name]`
+    pub(crate) fn theme_command(&mut self, name: Option<&str>) {
+        match name {
+            None => {
+                self.theme = self.config.theme;
+                self.info("Back to the configured theme");
+            }
+            Some(name) => match Theme::named(name) {
+                Some(theme) => {
+                    self.theme = theme;
+                    self.info(format!("Theme: {name}"));
+                }
+                None => self.error(format!(
+                    "There's no theme {name}, try one of {}",
+                    THEMES.join(", ")
+                )),
+            },
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! How a coffee's shot times spread out, as a histogram of two-second buckets, to show how
+//! consistent the prep is and where the outliers land. Opened with `h` on a coffee's row of the
+//! stats screen.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Bar, BarChart, BarGroup, Block, Widget},
+};
+use uuid::Uuid;
+
+use crate::App;
+
+/// how many seconds each bar takes in
+const BUCKET_SECS: f64 = 2.0;
+
+impl App {
+    /// the number of the coffee's shots in the period starting in each bucket, from the one with
+    /// the fastest shot to the one with the slowest, empty ones included
+    fn time_buckets(&self, coffee_id: Uuid) -> Vec<(f64, u64)> {
+        let buckets: Vec<i64> = self
+            .data
+            .entries
+            .iter()
+            .filter(|e| e.coffee_id == coffee_id && self.in_period(e))
+            .map(|e| (e.duration / BUCKET_SECS).floor() as i64)
+            .collect();
+        let (Some(&first), Some(&last)) = (buckets.iter().min(), buckets.iter().max()) else {
+            return Vec::new();
+        };
+        (first..=last)
+            .map(|b| {
+                let n = buckets.iter().filter(|&&x| x == b).count() as u64;
+                (b as f64 * BUCKET_SECS, n)
+            })
+            .collect()
+    }
+
+    pub(crate) fn render_time_histogram(&self, coffee_id: Uuid, area: Rect, buf: &mut Buffer) {
+        let name = self
+            .data
+            .coffees
+            .iter()
+            .find(|c| c.uuid == coffee_id)
+            .map_or("", |c| c.name.as_str());
+        let block = Block::bordered()
+            .title(format!("{}- {name} shot times ", self.title()))
+            .border_set(border::ROUNDED);
+        let buckets = self.time_buckets(coffee_id);
+        if buckets.is_empty() {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            Line::from("  No shots in this period.")
+                .dark_gray()
+                .render(inner, buf);
+            return;
+        }
+        let bars: Vec<Bar> = buckets
+            .iter()
+            .map(|&(start, n)| {
+                Bar::default()
+                    .label(Line::from(format!("{start:.0}")))
+                    .value(n)
+            })
+            .collect();
+        BarChart::default()
+            .block(block)
+            .bar_width(3)
+            .bar_gap(1)
+            .bar_style(Style::new().fg(self.theme.accent))
+            .value_style(Style::new().black().bg(self.theme.accent))
+            .data(BarGroup::default().bars(&bars))
+            .render(area, buf);
+    }
+}
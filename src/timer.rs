@@ -0,0 +1,207 @@
+//! The shot timer of the edit view. Space starts it and stops it again, and the time it ran, to a
+//! tenth of a second, becomes the entry's duration. While it runs the time is shown in large
+//! digits over the fields; Esc stops it without keeping the time.
+//!
+//! For espresso the timer first counts down the pre-infusion: the entry's own pre-infusion if it
+//! has one, otherwise the config's `preinfusion_countdown_s`. The terminal bell rings when it runs
+//! out. `p` marks the end of the pre-infusion early, or late; the time it was marked at, or the
+//! countdown if it wasn't, becomes the entry's pre-infusion.
+
+use std::{
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::{fields::Field, keymap::TIMER_KEYS, App};
+
+/// how often the screen is redrawn while the timer, or the clock of [`crate::pour`], runs
+pub const TIMER_TICK: Duration = Duration::from_millis(100);
+
+/// the rows of the large digits, five of them for each character the readout uses
+fn glyph(c: char) -> [&'static str; 5] {
+    match c {
+        '0' => ["███", "█ █", "█ █", "█ █", "███"],
+        '1' => ["  █", "  █", "  █", "  █", "  █"],
+        '2' => ["███", "  █", "███", "█  ", "███"],
+        '3' => ["███", "  █", "███", "  █", "███"],
+        '4' => ["█ █", "█ █", "███", "  █", "  █"],
+        '5' => ["███", "█  ", "███", "  █", "███"],
+        '6' => ["███", "█  ", "███", "█ █", "███"],
+        '7' => ["███", "  █", "  █", "  █", "  █"],
+        '8' => ["███", "█ █", "███", "█ █", "███"],
+        '9' => ["███", "█ █", "███", "  █", "███"],
+        '.' => [" ", " ", " ", " ", "█"],
+        ':' => [" ", "█", " ", "█", " "],
+        _ => ["   ", "   ", "   ", "   ", "   "],
+    }
+}
+
+/// `text` in large digits, a space between each character
+pub(crate) fn large(text: &str) -> Vec<String> {
+    (0..5)
+        .map(|row| {
+            text.chars()
+                .map(|c| glyph(c)[row])
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// rings the terminal bell
+pub(crate) fn bell() {
+    let mut stdout = io::stdout();
+    // a bell that doesn't ring isn't worth interrupting the brew over
+    _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+}
+
+/// to a tenth of a second
+fn tenths(seconds: f64) -> f64 {
+    (seconds * 10.0).round() / 10.0
+}
+
+#[derive(Debug)]
+pub struct ShotTimer {
+    started: Instant,
+    /// how long the pre-infusion is counted down for, in seconds, when it is
+    countdown: Option<f64>,
+    /// when the end of the pre-infusion was marked, in seconds from the start
+    preinfused: Option<f64>,
+    /// whether the bell has rung for the end of the countdown
+    rung: bool,
+}
+
+impl ShotTimer {
+    fn elapsed(&self) -> f64 {
+        self.started.elapsed().as_secs_f64()
+    }
+
+    /// the seconds of pre-infusion left, while it's being counted down
+    fn countdown_left(&self) -> Option<f64> {
+        let countdown = self.countdown.filter(|_| self.preinfused.is_none())?;
+        Some(countdown - self.elapsed()).filter(|&left| left > 0.0)
+    }
+}
+
+impl App {
+    /// starts the timer, or stops it and keeps the time as the edited entry's duration
+    pub(crate) fn toggle_timer(&mut self) {
+        let Some(timer) = self.state.shot_timer.take() else {
+            let countdown = self
+                .edited_entry()
+                .filter(|entry| entry.fields().contains(&Field::Preinfusion))
+                .and_then(|entry| entry.preinfusion_s.or(self.config.preinfusion_countdown_s))
+                .filter(|&s| s > 0.0);
+            self.state.shot_timer = Some(ShotTimer {
+                started: Instant::now(),
+                countdown,
+                preinfused: None,
+                rung: false,
+            });
+            return;
+        };
+        let elapsed = timer.elapsed();
+        if let Some(entry) = self.edited_entry_mut() {
+            entry.duration = tenths(elapsed);
+            if let Some(countdown) = timer.countdown {
+                let preinfused = timer.preinfused.unwrap_or(countdown.min(elapsed));
+                entry.preinfusion_s = Some(tenths(preinfused));
+            }
+            self.field_saved();
+        }
+    }
+
+    /// while the timer runs: Space stops it, `p` marks the end of the pre-infusion, Esc drops it,
+    /// and other keys are ignored
+    pub(crate) fn handle_key_events_timer(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char(' ') => self.toggle_timer(),
+            KeyCode::Char('p') => {
+                if let Some(timer) = &mut self.state.shot_timer
+                    && timer.countdown.is_some()
+                    && timer.preinfused.is_none()
+                {
+                    timer.preinfused = Some(timer.elapsed());
+                    timer.rung = true;
+                }
+            }
+            KeyCode::Esc => self.state.shot_timer = None,
+            _ => {}
+        }
+    }
+
+    /// whether the timer runs and the end of the pre-infusion can still be marked
+    pub(crate) fn preinfusing(&self) -> bool {
+        self.state
+            .shot_timer
+            .as_ref()
+            .is_some_and(|t| t.countdown.is_some() && t.preinfused.is_none())
+    }
+
+    /// rings the bell once the pre-infusion has been counted down
+    pub(crate) fn ring_preinfusion(&mut self) {
+        if let Some(timer) = &mut self.state.shot_timer
+            && timer.countdown.is_some()
+            && !timer.rung
+            && timer.countdown_left().is_none()
+        {
+            timer.rung = true;
+            bell();
+            self.info("Pre-infusion done");
+        }
+    }
+
+    /// draws the running time, or the pre-infusion left, as a popup over `area`
+    pub(crate) fn render_timer(&self, area: Rect, buf: &mut Buffer) {
+        let Some(timer) = &self.state.shot_timer else {
+            return;
+        };
+        let left = timer.countdown_left();
+        let digits = large(&format!("{:.1}", left.unwrap_or(tenths(timer.elapsed()))));
+        let width = digits[0].chars().count() as u16 + 6;
+        let mut lines = vec![Line::from("")];
+        lines.extend(digits.into_iter().map(|row| match left {
+            Some(_) => Line::from(row).fg(self.theme.accent),
+            None => Line::from(row).yellow(),
+        }));
+        lines.push(Line::from(""));
+        match (left, timer.countdown) {
+            (Some(_), _) => lines.push(Line::from("pre-infusion left").fg(self.theme.accent)),
+            (None, Some(countdown)) => lines.push(Line::from(format!(
+                "pre-infused {:.1} s",
+                timer.preinfused.unwrap_or(countdown)
+            ))),
+            (None, None) => {}
+        }
+        let [popup] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup] = Layout::horizontal([Constraint::Length(width.max(24))])
+            .flex(Flex::Center)
+            .areas(popup);
+        let block = Block::bordered()
+            .title(" Shot timer ")
+            .border_set(border::ROUNDED);
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .centered()
+            .block(block)
+            .render(popup, buf);
+    }
+
+    pub(crate) fn render_footer_timer(&self, area: Rect, buf: &mut Buffer) {
+        let controls = self.controls(TIMER_KEYS, area.width);
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}
@@ -0,0 +1,149 @@
+//! Notifications: what a command did, or why it couldn't, and things worth knowing that happen on
+//! their own, like autosave writing the log, a countdown running out or a bag running low. Each is
+//! shown as a small popup in the bottom right corner for its own while, newest at the bottom, and
+//! then cleared. Errors are red and stay longest, warnings yellow, so a failed save stands out
+//! from a done one.
+
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Margin, Rect},
+    style::{Color, Style, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::App;
+
+/// how long a notification is shown for, unless it says otherwise
+const INFO_TIMEOUT: Duration = Duration::from_secs(4);
+const WARNING_TIMEOUT: Duration = Duration::from_secs(6);
+const ERROR_TIMEOUT: Duration = Duration::from_secs(8);
+/// the most shown at once; older ones are dropped to make room
+const MAX_TOASTS: usize = 4;
+/// the widest a toast gets, text included
+const MAX_WIDTH: u16 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn color(self) -> Color {
+        match self {
+            Severity::Info => Color::Green,
+            Severity::Warning => Color::Yellow,
+            Severity::Error => Color::Red,
+        }
+    }
+
+    fn timeout(self) -> Duration {
+        match self {
+            Severity::Info => INFO_TIMEOUT,
+            Severity::Warning => WARNING_TIMEOUT,
+            Severity::Error => ERROR_TIMEOUT,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Toast {
+    text: String,
+    severity: Severity,
+    shown: Instant,
+    timeout: Duration,
+}
+
+impl Toast {
+    fn left(&self) -> Duration {
+        self.timeout.saturating_sub(self.shown.elapsed())
+    }
+}
+
+impl App {
+    /// shows `text` for `timeout`. The same message again only restarts its time, so autosave
+    /// saying "Saved" every few edits doesn't fill the corner.
+    pub(crate) fn notify(&mut self, severity: Severity, text: String, timeout: Duration) {
+        let toasts = &mut self.state.toasts;
+        toasts.retain(|t| t.text != text);
+        if toasts.len() == MAX_TOASTS {
+            toasts.pop_front();
+        }
+        toasts.push_back(Toast {
+            text,
+            severity,
+            shown: Instant::now(),
+            timeout,
+        });
+    }
+
+    pub(crate) fn info(&mut self, text: impl Into<String>) {
+        self.notify(Severity::Info, text.into(), Severity::Info.timeout());
+    }
+
+    pub(crate) fn warn(&mut self, text: impl Into<String>) {
+        self.notify(Severity::Warning, text.into(), Severity::Warning.timeout());
+    }
+
+    pub(crate) fn error(&mut self, text: impl Into<String>) {
+        self.notify(Severity::Error, text.into(), Severity::Error.timeout());
+    }
+
+    /// shows `done` if `result` is fine, and its error otherwise
+    pub(crate) fn report_result<E: Display>(&mut self, result: Result<(), E>, done: &str) {
+        match result {
+            Ok(()) if done.is_empty() => {}
+            Ok(()) => self.info(done),
+            Err(e) => self.error(e.to_string()),
+        }
+    }
+
+    /// how long until the next notification is cleared, while there are any
+    pub(crate) fn toast_left(&self) -> Option<Duration> {
+        self.state.toasts.iter().map(Toast::left).min()
+    }
+
+    /// clears the notifications that have been shown for long enough
+    pub(crate) fn expire_toasts(&mut self) {
+        self.state.toasts.retain(|t| !t.left().is_zero());
+    }
+
+    /// draws the notifications stacked up from the bottom right corner of `area`, inside the
+    /// border of what's drawn there
+    pub(crate) fn render_toasts(&self, area: Rect, buf: &mut Buffer) {
+        let area = area.inner(Margin::new(1, 1));
+        let mut bottom = area.bottom();
+        for toast in self.state.toasts.iter().rev() {
+            if bottom < area.top() + 3 {
+                break;
+            }
+            let text = format!(" {} ", toast.text);
+            let width = (text.chars().count() as u16 + 2)
+                .min(MAX_WIDTH)
+                .min(area.width);
+            let popup = Rect::new(area.right() - width, bottom - 3, width, 3);
+            let color = toast.severity.color();
+            let mut line = Line::from(text).fg(color);
+            if toast.severity == Severity::Error {
+                line = line.bold();
+            }
+            let block = Block::bordered()
+                .border_set(border::ROUNDED)
+                .border_style(Style::new().fg(color));
+            Clear.render(popup, buf);
+            Paragraph::new(line)
+                .style(self.theme.base)
+                .block(block)
+                .render(popup, buf);
+            bottom -= 3;
+        }
+    }
+}
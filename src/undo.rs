@@ -0,0 +1,86 @@
+//! Undo and redo, on `u` and Ctrl-R. Every change to the data is reported through
+//! `App::mark_changed`, which makes it a step of the history. A step is a copy of the data from
+//! before the change, so undoing puts back everything the change touched, like the beans an added
+//! entry took out of its bag.
+
+use std::{collections::VecDeque, mem, time::Instant};
+
+use crate::{storage::Dataset, App, Phase};
+
+/// how many changes can be undone
+const UNDO_STEPS: usize = 50;
+
+#[derive(Debug, Default)]
+pub struct History {
+    undo: VecDeque<Dataset>,
+    redo: Vec<Dataset>,
+    /// the data as of the latest change, which becomes an undo step once the next change comes in
+    current: Dataset,
+}
+
+impl History {
+    /// a history with nothing to undo, starting from `data`
+    pub fn new(data: &Dataset) -> Self {
+        Self {
+            current: data.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// records a change that left the data as `data`
+    pub fn record(&mut self, data: &Dataset) {
+        let before = mem::replace(&mut self.current, data.clone());
+        self.push_undo(before);
+        self.redo.clear();
+    }
+
+    /// keeps `before` as the next step back, dropping the oldest past [`UNDO_STEPS`]
+    fn push_undo(&mut self, before: Dataset) {
+        if self.undo.len() == UNDO_STEPS {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(before);
+    }
+}
+
+impl App {
+    pub(crate) fn undo(&mut self) {
+        if let Some(before) = self.history.undo.pop_back() {
+            let after = mem::replace(&mut self.data, before);
+            self.history.redo.push(after);
+            self.history_moved();
+        }
+    }
+
+    pub(crate) fn redo(&mut self) {
+        if let Some(after) = self.history.redo.pop() {
+            let before = mem::replace(&mut self.data, after);
+            self.history.push_undo(before);
+            self.history_moved();
+        }
+    }
+
+    /// after stepping through the history, saves the data it landed on and keeps the view on
+    /// entries that still exist
+    fn history_moved(&mut self) {
+        self.history.current = self.data.clone();
+        // not `mark_changed`, which would record this as a change of its own
+        self.last_change = Some(Instant::now());
+        // the marked entries may not be where they were
+        self.state.compare_marks.clear();
+        if let Phase::EditEntry(idx) = self.phase
+            && idx >= self.data.entries.len()
+        {
+            self.phase = Phase::ListView;
+        }
+        let listed = self.listed_entries().len();
+        if self
+            .state
+            .entry_list_state
+            .selected()
+            .is_some_and(|row| row >= listed)
+        {
+            self.state.entry_list_state.select(listed.checked_sub(1));
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Saved views: a filter and a sort kept under a name, stored with the rest of the data.
+//! `:view save <name>` saves the list's current ones, `:view <name>` brings them back,
+//! `:view delete <name>` forgets them, and `v` in the list goes through the saved views in turn,
+//! then back to the whole list.
+
+use crate::{filter::Filter, sort::Sort, App};
+
+#[derive(Debug, Clone)]
+pub struct SavedView {
+    pub name: String,
+    pub filter: Option<Filter>,
+    pub sort: Option<Sort>,
+}
+
+impl App {
+    /// handles `:view [save|delete] <name>`
+    pub(crate) fn view_command(&mut self, action: Option<&str>, name: Option<&str>) {
+        match (action, name) {
+            (Some("save"), Some(name)) => self.save_view(name),
+            (Some("delete"), Some(name)) => self.delete_view(name),
+            (Some(name), None) => {
+                if let Some(i) = self.data.views.iter().position(|v| v.name == name) {
+                    self.apply_view(Some(i));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// saves the list's filter and sort as `name`, replacing the view of that name if there is one
+    fn save_view(&mut self, name: &str) {
+        let view = SavedView {
+            name: name.to_string(),
+            filter: self.state.filter.clone(),
+            sort: self.state.sort,
+        };
+        match self.data.views.iter().position(|v| v.name == name) {
+            Some(i) => self.data.views[i] = view,
+            None => self.data.views.push(view),
+        }
+        self.state.view = Some(name.to_string());
+        self.mark_changed();
+    }
+
+    fn delete_view(&mut self, name: &str) {
+        let before = self.data.views.len();
+        self.data.views.retain(|v| v.name != name);
+        if self.data.views.len() == before {
+            return;
+        }
+        if self.state.view.as_deref() == Some(name) {
+            self.state.view = None;
+        }
+        self.mark_changed();
+    }
+
+    /// lists the entries the way view `i` does, or all of them in the order they were added for
+    /// `None`
+    fn apply_view(&mut self, i: Option<usize>) {
+        let view = i.and_then(|i| self.data.views.get(i)).cloned();
+        self.state.filter = view.as_ref().and_then(|v| v.filter.clone());
+        self.set_sort(view.as_ref().and_then(|v| v.sort));
+        self.state.view = view.map(|v| v.name);
+        self.state.entry_list_state.select_first();
+    }
+
+    /// moves on to the saved view after the current one, or to the whole list after the last
+    pub(crate) fn next_view(&mut self) {
+        let current = self
+            .state
+            .view
+            .as_ref()
+            .and_then(|name| self.data.views.iter().position(|v| v.name == *name));
+        let next = match current {
+            None => 0,
+            Some(i) => i + 1,
+        };
+        self.apply_view((next < self.data.views.len()).then_some(next));
+    }
+}
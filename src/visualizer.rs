@@ -0,0 +1,135 @@
+//! Uploading shots to [Visualizer](https://visualizer.coffee). Entries are sent in the JSON shot
+//! format Visualizer reads from Decent machines, with the parameters in `meta` and a two-point
+//! weight curve, since there is no flow data to go with them. There is no HTTP client among our
+//! dependencies, so this drives `curl`, which has to be on the `PATH`.
+//!
+//! Requests are authenticated with the `visualizer_token` from the config file.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use crate::{
+    json::{self, invalid, Value},
+    App, Entry, Phase,
+};
+
+const UPLOAD_URL: &str = "https://visualizer.coffee/api/shots/upload";
+const SHOT_URL: &str = "https://visualizer.coffee/shots/";
+
+fn shot(entry: &Entry, coffee: &str, grinder: &str) -> Value {
+    let series = |end: f64| Value::Array(vec![0.0.into(), end.into()]);
+    Value::Object(vec![
+        ("version".into(), 2.0.into()),
+        ("clock".into(), (entry.dt_taken.timestamp() as f64).into()),
+        (
+            "timestamp".into(),
+            (entry.dt_taken.timestamp() as f64).into(),
+        ),
+        ("elapsed".into(), series(entry.duration)),
+        (
+            "weight".into(),
+            Value::Object(vec![("by_time".into(), series(entry.output))]),
+        ),
+        (
+            "meta".into(),
+            Value::Object(vec![
+                (
+                    "bean".into(),
+                    Value::Object(vec![("type".into(), coffee.into())]),
+                ),
+                (
+                    "grinder".into(),
+                    Value::Object(vec![
+                        ("model".into(), grinder.into()),
+                        ("setting".into(), format!("{}", entry.grind_setting).into()),
+                    ]),
+                ),
+                (
+                    "shot".into(),
+                    Value::Object(vec![("notes".into(), entry.notes.as_str().into())]),
+                ),
+                ("in".into(), entry.dose.into()),
+                ("out".into(), entry.output.into()),
+                ("time".into(), entry.duration.into()),
+            ]),
+        ),
+    ])
+}
+
+/// uploads `shot` and returns the URL of the new shot page. The shot goes through a file in
+/// `dir`, the data directory, and the token through curl's stdin, so neither shows up in the
+/// process list. The file is made fresh, never written through one that's already there, after
+/// clearing any an earlier run left behind.
+fn upload(token: &str, shot: &Value, dir: &Path) -> io::Result<String> {
+    fs::create_dir_all(dir)?;
+    let file = dir.join(format!("upload-{}.json", std::process::id()));
+    _ = fs::remove_file(&file);
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&file)?
+        .write_all(shot.to_string().as_bytes())?;
+    let result = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--fail",
+            "--header",
+            "@-",
+            "--form",
+        ])
+        .arg(format!("file=@{};type=application/json", file.display()))
+        .arg(UPLOAD_URL)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            let mut stdin = child.stdin.take().unwrap();
+            writeln!(stdin, "Authorization: Bearer {token}")?;
+            drop(stdin);
+            child.wait_with_output()
+        });
+    _ = fs::remove_file(&file);
+    let output =
+        result.map_err(|e| io::Error::new(e.kind(), format!("could not run curl: {e}")))?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "visualizer upload failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    let response = String::from_utf8_lossy(&output.stdout);
+    let id = json::parse(&response)?
+        .get("id")
+        .and_then(Value::as_str)
+        .map(String::from)
+        .ok_or_else(|| invalid(format!("unexpected response from visualizer: {response}")))?;
+    Ok(format!("{SHOT_URL}{id}"))
+}
+
+impl App {
+    /// uploads the entry being edited, or the one selected in the list
+    pub(crate) fn upload_to_visualizer(&self) -> io::Result<String> {
+        let token = self.config.visualizer_token.as_deref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "set `visualizer_token` in the config file to upload shots",
+            )
+        })?;
+        let idx = match self.phase {
+            Phase::EditEntry(idx) => Some(idx),
+            Phase::ListView => self.selected_entry(),
+            _ => None,
+        };
+        let entry = idx
+            .and_then(|idx| self.data.entries.get(idx))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no entry selected"))?;
+        let shot = shot(entry, self.coffee_name(entry), self.grinder_name(entry));
+        upload(token, &shot, &self.storage_options.data_dir)
+    }
+}
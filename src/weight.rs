@@ -0,0 +1,103 @@
+//! Dose and output weights. They're stored in grams and shown and typed in the unit set by the
+//! config's `weight_unit`, either `"grams"` or `"ounces"`, or with `:set units=oz`.
+
+use crate::fields::Field;
+
+/// grams in an avoirdupois ounce
+const GRAMS_PER_OUNCE: f64 = 28.349_523_125;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum WeightUnit {
+    #[default]
+    Grams,
+    Ounces,
+}
+
+impl WeightUnit {
+    /// reads the unit as written in the config, e.g. `"ounces"` or just `"oz"`
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key.to_lowercase().as_str() {
+            "g" | "grams" => Some(WeightUnit::Grams),
+            "oz" | "ounces" => Some(WeightUnit::Ounces),
+            _ => None,
+        }
+    }
+
+    /// as written to the config
+    pub fn key(self) -> &'static str {
+        match self {
+            WeightUnit::Grams => "grams",
+            WeightUnit::Ounces => "ounces",
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            WeightUnit::Grams => "g",
+            WeightUnit::Ounces => "oz",
+        }
+    }
+
+    /// the decimals shown, more for ounces since a tenth of one is nearly 3 g
+    pub fn decimals(self) -> usize {
+        match self {
+            WeightUnit::Grams => 1,
+            WeightUnit::Ounces => 2,
+        }
+    }
+
+    /// a weight in grams, in this unit
+    pub fn in_unit(self, grams: f64) -> f64 {
+        match self {
+            WeightUnit::Grams => grams,
+            WeightUnit::Ounces => grams / GRAMS_PER_OUNCE,
+        }
+    }
+
+    pub fn to_grams(self, weight: f64) -> f64 {
+        match self {
+            WeightUnit::Grams => weight,
+            WeightUnit::Ounces => weight * GRAMS_PER_OUNCE,
+        }
+    }
+
+    /// e.g. "18.0 g" or "0.63 oz"
+    pub fn format(self, grams: f64) -> String {
+        format!(
+            "{:.2$} {}",
+            self.in_unit(grams),
+            self.symbol(),
+            self.decimals()
+        )
+    }
+}
+
+impl crate::App {
+    /// a dose or output in grams, in the unit the settings say
+    pub(crate) fn weight(&self, grams: f64) -> String {
+        self.config.weight_unit.format(grams)
+    }
+
+    /// a dose or output in grams as it's typed in, rounded to the unit's decimals
+    pub(crate) fn typed_weight(&self, grams: f64) -> String {
+        let unit = self.config.weight_unit;
+        let rounding = 10_f64.powi(unit.decimals() as i32);
+        format!("{}", (unit.in_unit(grams) * rounding).round() / rounding)
+    }
+
+    /// what follows a number field's value, the dose and output's in the unit the settings say
+    pub(crate) fn field_unit(&self, field: Field) -> String {
+        match field {
+            Field::Dose | Field::Output => format!(" {}", self.config.weight_unit.symbol()),
+            _ => field.unit().to_string(),
+        }
+    }
+
+    /// a number typed into `field` as it's kept, the dose and output in grams
+    pub(crate) fn typed_number(&self, field: Field, n: f64) -> f64 {
+        match field {
+            Field::Dose | Field::Output => self.config.weight_unit.to_grams(n),
+            _ => n,
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! The new entry wizard, an alternative to filling in the blank form: `w` on the home screen or in
+//! the list, or `:wizard`, asks for the coffee, grinder, dose, grind, the shot itself with the
+//! timer, the yield and some notes, one at a time. Enter goes on once the step's answer makes
+//! sense, Esc goes back a step, and the shot is added after the notes.
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{Event, KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Margin, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Paragraph, Widget},
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use crate::{catalog::Kind, fields::Field, valid_float, App, InputMode, Phase};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    Coffee,
+    Grinder,
+    Dose,
+    Grind,
+    Brew,
+    Output,
+    Notes,
+}
+
+const STEPS: [Step; 7] = [
+    Step::Coffee,
+    Step::Grinder,
+    Step::Dose,
+    Step::Grind,
+    Step::Brew,
+    Step::Output,
+    Step::Notes,
+];
+
+impl Step {
+    fn index(self) -> usize {
+        STEPS.iter().position(|&s| s == self).unwrap_or(0)
+    }
+
+    fn question(self) -> &'static str {
+        match self {
+            Step::Coffee => "Which coffee is it?",
+            Step::Grinder => "Which grinder?",
+            Step::Dose => "How much coffee went in?",
+            Step::Grind => "At what grind setting?",
+            Step::Brew => "Brew it.",
+            Step::Output => "How much came out?",
+            Step::Notes => "Anything to note?",
+        }
+    }
+
+    /// the field typed in at this step, for the ones that are a number
+    fn number_field(self) -> Option<Field> {
+        match self {
+            Step::Dose => Some(Field::Dose),
+            Step::Grind => Some(Field::GrindSetting),
+            Step::Output => Some(Field::Output),
+            _ => None,
+        }
+    }
+
+    fn kind(self) -> Option<Kind> {
+        match self {
+            Step::Coffee => Some(Kind::Coffee),
+            Step::Grinder => Some(Kind::Grinder),
+            _ => None,
+        }
+    }
+}
+
+impl App {
+    /// starts the wizard on a new entry filled in like the form's
+    pub(crate) fn open_wizard(&mut self) {
+        self.new_entry();
+        self.wizard_step(Step::Coffee);
+    }
+
+    /// moves to `step`, setting up what it's answered with
+    fn wizard_step(&mut self, step: Step) {
+        self.phase = Phase::Wizard(step);
+        self.state.wizard_error = None;
+        if let Some(kind) = step.kind() {
+            self.open_selector(kind);
+            self.state.edit.input_mode = InputMode::Editing;
+        }
+        let value = match (step.number_field(), self.state.new_entry.as_ref()) {
+            (Some(field @ (Field::Dose | Field::Output)), Some(entry)) => field
+                .number(entry)
+                .filter(|&n| n > 0.0)
+                .map_or(String::new(), |grams| self.typed_weight(grams)),
+            (Some(field), Some(entry)) => field
+                .number(entry)
+                .filter(|&n| n > 0.0)
+                .map_or(String::new(), |n| format!("{n}")),
+            (None, Some(entry)) if step == Step::Notes => entry.notes.clone(),
+            _ => String::new(),
+        };
+        self.state.edit.input = Input::new(value);
+    }
+
+    fn wizard_back(&mut self, step: Step) {
+        match step.index() {
+            0 => {
+                self.state.new_entry = None;
+                self.phase = Phase::ListView;
+            }
+            i => self.wizard_step(STEPS[i - 1]),
+        }
+    }
+
+    fn wizard_next(&mut self, step: Step) {
+        match STEPS.get(step.index() + 1) {
+            Some(&next) => self.wizard_step(next),
+            None => self.add_new_entry(),
+        }
+    }
+
+    /// checks what was typed at a number step and writes it to the entry
+    fn wizard_number(&mut self, field: Field) -> Result<(), String> {
+        let value = self.state.edit.input.value().trim();
+        // parse() alone would take NaN and inf
+        if !valid_float(value) {
+            return Err(format!("`{value}` isn't a number"));
+        }
+        let n: f64 = value.parse().unwrap_or_default();
+        if n < 0.0 || (n == 0.0 && field != Field::GrindSetting) {
+            return Err(format!(
+                "the {} should be more than 0",
+                field.label().to_lowercase()
+            ));
+        }
+        let n = self.typed_number(field, n);
+        if let Some(entry) = self.state.new_entry.as_mut() {
+            field.set_number(entry, n);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn handle_key_events_wizard(&mut self, step: Step, key_event: KeyEvent) {
+        if let Some(kind) = step.kind() {
+            if key_event.code == KeyCode::Esc {
+                self.wizard_back(step);
+                return;
+            }
+            self.handle_key_events_selector(kind, key_event);
+            // the selector goes back to normal once something has been picked
+            if matches!(self.state.edit.input_mode, InputMode::Normal) {
+                self.wizard_next(step);
+            }
+            return;
+        }
+        if step == Step::Brew {
+            match key_event.code {
+                KeyCode::Char(' ') => {
+                    let stopping = self.state.shot_timer.is_some();
+                    self.toggle_timer();
+                    if stopping {
+                        self.wizard_next(step);
+                    }
+                }
+                _ if self.state.shot_timer.is_some() => self.handle_key_events_timer(key_event),
+                KeyCode::Enter => self.wizard_next(step),
+                KeyCode::Esc => self.wizard_back(step),
+                _ => {}
+            }
+            return;
+        }
+        match key_event.code {
+            KeyCode::Esc => self.wizard_back(step),
+            KeyCode::Enter => {
+                let checked = match step.number_field() {
+                    Some(field) => self.wizard_number(field),
+                    None => {
+                        let notes = self.state.edit.input.value().trim().to_string();
+                        if let Some(entry) = self.state.new_entry.as_mut() {
+                            entry.notes = notes;
+                        }
+                        Ok(())
+                    }
+                };
+                match checked {
+                    Ok(()) => self.wizard_next(step),
+                    Err(e) => self.state.wizard_error = Some(e),
+                }
+            }
+            _ => {
+                _ = self.state.edit.input.handle_event(&Event::Key(key_event));
+                self.state.wizard_error = None;
+            }
+        }
+    }
+
+    pub(crate) fn render_wizard(&mut self, step: Step, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title(format!(
+                " Coffee Tracking - New entry, step {} of {} ",
+                step.index() + 1,
+                STEPS.len()
+            ))
+            .border_set(border::ROUNDED);
+        let inner = block.inner(area).inner(Margin::new(2, 1));
+        block.render(area, buf);
+        let [question_area, _, answer_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+        Paragraph::new(Line::from(step.question()).bold()).render(question_area, buf);
+        if let Some(kind) = step.kind() {
+            self.render_selector(kind, answer_area, buf);
+            return;
+        }
+        if step == Step::Brew {
+            let hint = match self.state.shot_timer {
+                None => "Space starts the timer, Enter goes on without timing the shot.",
+                Some(_) => "Space stops the timer.",
+            };
+            Paragraph::new(hint).render(answer_area, buf);
+            self.render_timer(answer_area, buf);
+            return;
+        }
+        let unit = step
+            .number_field()
+            .map_or(String::new(), |field| self.field_unit(field));
+        let mut lines = vec![Line::from(vec![
+            "> ".fg(self.theme.accent).bold(),
+            self.state.edit.input.value().to_string().into(),
+            "█".into(),
+            unit.dark_gray(),
+        ])];
+        if let Some(error) = &self.state.wizard_error {
+            lines.push(Line::from(""));
+            lines.push(Line::from(error.clone()).red());
+        }
+        Paragraph::new(lines).render(answer_area, buf);
+    }
+
+    pub(crate) fn render_footer_wizard(&self, step: Step, area: Rect, buf: &mut Buffer) {
+        if step.kind().is_some() {
+            self.render_footer_selector(area, buf);
+            return;
+        }
+        if self.state.shot_timer.is_some() {
+            self.render_footer_timer(area, buf);
+            return;
+        }
+        let mut controls = Line::from(" Controls:");
+        if step == Step::Brew {
+            controls.push_span(" Start timer ");
+            controls.push_span("<Space>".fg(self.theme.accent).bold());
+            controls.push_span(" |");
+        }
+        controls.push_span(match step {
+            Step::Notes => " Add ",
+            Step::Brew => " Skip ",
+            _ => " Next ",
+        });
+        controls.push_span("<Enter>".fg(self.theme.accent).bold());
+        controls.push_span(" | Back ");
+        controls.push_span("<Esc> ".fg(self.theme.accent).bold());
+        let cmd = Line::from(self.state.command.buffer.clone());
+        Paragraph::new(vec![controls, cmd]).render(area, buf);
+    }
+}